@@ -187,6 +187,7 @@ local airport = airport({
     tower = 118.6,
     ground = 118.6,
     center = 118.7,
+    clearance = 118.6,
   },
   -- Between runway 13 and 22
   center      = { 0, -3000 },
@@ -274,8 +274,10 @@ local airport = airport({
     tower = 118.6,
     ground = 118.6,
     center = 118.7,
+    clearance = 118.6,
   },
   center = { 600, -100 },
+  location = { lat = 37.6189, lon = -122.3750 },
 
   runways = {
     runway19L,
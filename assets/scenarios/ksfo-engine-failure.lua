@@ -0,0 +1,83 @@
+-- An arrival loses an engine on approach while a departure is pushing back.
+
+local TICK_RATE = 15
+local function minutes(m)
+  return m * 60 * TICK_RATE
+end
+
+return {
+  name = "Engine failure on approach",
+  description = "Get an arrival with an engine failure on the ground safely, without holding up the departure.",
+  airport = "ksfo",
+  status = {
+    divert_arrivals = false,
+    delay_departures = false,
+    automate_air = false,
+    automate_ground = true,
+  },
+  airports = {
+    { id = "KLAX", pos = { 0, -600000 } },
+    { id = "KSEA", pos = { 0, 600000 } },
+  },
+  aircraft = {
+    {
+      callsign = "AAL123",
+      type = "b737",
+      origin = "KLAX",
+      destination = "KSFO",
+      state = { type = "arrival" },
+    },
+    {
+      callsign = "UAL456",
+      type = "a21n",
+      origin = "KSFO",
+      destination = "KSEA",
+      state = { type = "gate", gate = "A1" },
+    },
+  },
+  events = {
+    {
+      trigger = {
+        type = "segment",
+        value = { id = "AAL123", segment = "approach" },
+      },
+      action = {
+        type = "emergency",
+        value = { id = "AAL123", emergency = "engine-failure" },
+      },
+    },
+    {
+      trigger = { type = "at", value = minutes(2) },
+      action = {
+        type = "pilot-request",
+        value = { id = "AAL123", text = "request lower" },
+      },
+    },
+  },
+  objectives = {
+    {
+      name = "Land AAL123",
+      goal = {
+        type = "segment",
+        value = { id = "AAL123", segment = "landing" },
+      },
+      deadline = minutes(20),
+    },
+    {
+      name = "Get UAL456 airborne",
+      goal = {
+        type = "segment",
+        value = { id = "UAL456", segment = "departure" },
+      },
+      deadline = minutes(20),
+    },
+    {
+      name = "Keep separation",
+      goal = {
+        type = "limit",
+        value = { stat = "separation-losses", max = 0 },
+      },
+      deadline = minutes(20),
+    },
+  },
+}
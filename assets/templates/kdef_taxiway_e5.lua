@@ -0,0 +1,37 @@
+-- Adds a fifth crossing between taxiways A and B at the default airport.
+local TAXIWAY_DISTANCE = 400
+
+local runway22 = runway({
+  id      = "22",
+  start   = { 1500, 0 },
+  heading = 225.0,
+  length  = 7000.0,
+})
+
+local taxiwayA = taxiway({
+  id = "A",
+  a  = vec2(runway22.start):move(runway22.heading + 90, TAXIWAY_DISTANCE):into(),
+  b  = vec2(runway22.start):move(runway22.heading, runway22.length):move(runway22.heading + 90, TAXIWAY_DISTANCE):into(),
+})
+
+local taxiwayB = taxiway({
+  id = "B",
+  a  = vec2(runway22.start):move(runway22.heading - 90, TAXIWAY_DISTANCE):into(),
+  b  = vec2(runway22.start):move(runway22.heading, runway22.length):move(runway22.heading - 90, TAXIWAY_DISTANCE):into(),
+})
+
+local taxiwayE5 = taxiway({
+  id = "E5",
+  a  = vec2(taxiwayA.b):lerp(vec2(taxiwayA.a), 0.75):into(),
+  b  = vec2(taxiwayB.b):lerp(vec2(taxiwayB.a), 0.75):into(),
+})
+
+return template({
+  id       = "kdef_taxiway_e5",
+  name     = "Taxiway E5",
+  airport  = "KDEF",
+  -- Must match the airport's center.
+  center   = { 0, -3000 },
+  cost     = 20000,
+  taxiways = { taxiwayE5 },
+})
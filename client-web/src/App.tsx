@@ -5,7 +5,7 @@ import Canvas from './Canvas';
 import StripBoard from './StripBoard';
 import FreqSelector from './FreqSelector';
 import { useDemoMode, useStorageAtom } from './lib/hooks';
-import { baseAPIPath, getMessages, usePing } from './lib/api';
+import { apiFetch, baseAPIPath, getMessages, usePing } from './lib/api';
 import { useQueryClient } from '@tanstack/solid-query';
 import Flights from './Airport';
 import GameButtons from './GameButtons';
@@ -17,7 +17,7 @@ export default function App() {
   const client = useQueryClient();
 
   async function sendTextMessage(text: string) {
-    await fetch(`${baseAPIPath}/api/comms/text?frequency=${frequency()}`, {
+    await apiFetch(`/api/comms/text?frequency=${frequency()}`, {
       body: text,
       method: 'POST',
     });
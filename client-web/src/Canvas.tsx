@@ -867,23 +867,36 @@ export default function Canvas() {
     ctx.fillText(id, pos[0], pos[1] - fontSize);
   }
 
+  function taxiwayPoints(taxiway: Taxiway): Array<Vec2> {
+    return [taxiway.a, ...taxiway.via, taxiway.b].map((p) =>
+      scalePoint(p, radar().scale, radar().shiftPoint)
+    );
+  }
+
   function drawTaxiway(ctx: Ctx, taxiway: Taxiway) {
     resetTransform(ctx);
-    let start = scalePoint(taxiway.a, radar().scale, radar().shiftPoint);
-    let end = scalePoint(taxiway.b, radar().scale, radar().shiftPoint);
+    let points = taxiwayPoints(taxiway);
+    let start = points[0];
+    let end = points[points.length - 1];
+    let radius = scaleFeetToPixels(taxiway.radius, radar().scale);
 
     ctx.strokeStyle = colors.special.taxiway;
     ctx.lineWidth = scaleFeetToPixels(200, radar().scale);
     ctx.beginPath();
     ctx.moveTo(start[0], start[1]);
+    for (let i = 1; i < points.length - 1; i++) {
+      let corner = points[i];
+      let next = points[i + 1];
+      ctx.arcTo(corner[0], corner[1], next[0], next[1], radius);
+    }
     ctx.lineTo(end[0], end[1]);
     ctx.stroke();
   }
 
   function drawTaxiwayLabel(ctx: Ctx, taxiway: Taxiway) {
-    let start = scalePoint(taxiway.a, radar().scale, radar().shiftPoint);
-    let end = scalePoint(taxiway.b, radar().scale, radar().shiftPoint);
-    let middle = midpointBetweenPoints(start, end);
+    let points = taxiwayPoints(taxiway);
+    let i = Math.floor((points.length - 1) / 2);
+    let middle = midpointBetweenPoints(points[i], points[i + 1]);
     let textWidth = ctx.measureText(taxiway.id).width + 10;
     ctx.fillStyle = colors.text_background;
     ctx.fillRect(
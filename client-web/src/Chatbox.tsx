@@ -8,8 +8,26 @@ import {
 } from './lib/atoms';
 import { createEffect, createSignal } from 'solid-js';
 import useGlobalShortcuts, { useStorageAtom } from './lib/hooks';
-import { useMessages } from './lib/api';
+import { useCoordination, useMessages } from './lib/api';
 import { OutgoingCommandReply } from '../bindings/OutgoingCommandReply';
+import { Coordination } from '../bindings/Coordination';
+
+function describeCoordination(c: Coordination): string {
+  switch (c.kind.type) {
+    case 'handoff':
+      return 'handoff';
+    case 'point-out':
+      return 'point out';
+    case 'altitude':
+      return `request altitude ${c.kind.value}`;
+    case 'heading':
+      return `request heading ${c.kind.value}`;
+    case 'approved':
+      return 'approved';
+    case 'unable':
+      return 'unable';
+  }
+}
 
 export default function Chatbox({
   sendMessage,
@@ -23,12 +41,14 @@ export default function Chatbox({
   let [selectedAircraft, setSelectedAircraft] = useAtom(selectedAircraftAtom);
   let [useTTS] = useStorageAtom(useTTSAtomKey, useTTSAtom);
   let [showAll, setShowAll] = createSignal(false);
+  let [showCoordination, setShowCoordination] = createSignal(false);
   let [text, setText] = createSignal('');
   let [lastRead, setLastRead] = createSignal(Date.now() / 1000);
   let [voices, setVoices] = createSignal<
     Map<String, { rate: number; pitch: number }>
   >(new Map());
   const messages = useMessages();
+  const coordination = useCoordination();
 
   function randBetween(min: number, max: number) {
     return Math.floor(Math.random() * (max - min + 1) + min);
@@ -115,9 +135,10 @@ export default function Chatbox({
       // Subscribe to frequency and showAll signals
       frequency();
       showAll();
+      showCoordination();
 
       // Subscribe to messages signal
-      if (messages.data.length > 0) {
+      if (messages.data.length > 0 || coordination.data.length > 0) {
         chatbox.scrollTo(0, chatbox.scrollHeight);
       }
     }
@@ -127,6 +148,10 @@ export default function Chatbox({
     setShowAll((b) => !b);
   }
 
+  function toggleCoordination() {
+    setShowCoordination((b) => !b);
+  }
+
   function handleSendMessage(text: string) {
     const trimmed = text.trim();
 
@@ -147,9 +172,40 @@ export default function Chatbox({
           value={showAll() ? 'Show Yours' : 'Show All'}
           onclick={toggleAll}
         />
+        <input
+          type="button"
+          value={showCoordination() ? 'Show Radio' : 'Show Coordination'}
+          onclick={toggleCoordination}
+        />
       </div>
       <div class="messages" ref={chatbox}>
-        {messages.data
+        {showCoordination()
+          ? coordination.data
+              .filter(
+                (c) =>
+                  showAll() || c.from === frequency() || c.to === frequency()
+              )
+              .map((c) => (
+                <div
+                  classList={{
+                    message: true,
+                    selected: c.aircraft === selectedAircraft(),
+                  }}
+                >
+                  <span class="frequency">
+                    {c.from} → {c.to}
+                  </span>
+                  <span
+                    class="callsign"
+                    onClick={() => setSelectedAircraft(c.aircraft)}
+                  >
+                    {c.aircraft}
+                  </span>
+                  <span class="text">{describeCoordination(c)}</span>
+                </div>
+              ))
+          : null}
+        {showCoordination() ? null : messages.data
           .filter(
             (m) => (showAll() || m.frequency === frequency()) && m.reply !== ''
           )
@@ -9,7 +9,7 @@ import {
 } from './lib/atoms';
 import { createSignal, onMount } from 'solid-js';
 import { useStorageAtom } from './lib/hooks';
-import { baseAPIPath, getMessages } from './lib/api';
+import { apiFetch, getMessages } from './lib/api';
 import { useQueryClient } from '@tanstack/solid-query';
 
 export default function GameButtons() {
@@ -35,7 +35,7 @@ export default function GameButtons() {
     setIsRecording(false);
     whisper.stopRecording(async (blob) => {
       const value = await blob.arrayBuffer();
-      await fetch(`${baseAPIPath}/api/comms/voice?frequency=${frequency()}`, {
+      await apiFetch(`/api/comms/voice?frequency=${frequency()}`, {
         body: value,
         method: 'POST',
       });
@@ -119,7 +119,7 @@ export default function GameButtons() {
   });
 
   async function sendPause() {
-    await fetch(`${baseAPIPath}/api/pause`, {
+    await apiFetch(`/api/pause`, {
       method: 'POST',
     });
   }
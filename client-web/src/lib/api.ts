@@ -9,6 +9,7 @@ import { Aircraft } from '../../bindings/Aircraft';
 import { World } from '../../bindings/World';
 import { OutgoingCommandReply } from '../../bindings/OutgoingCommandReply';
 import { AirportStatus } from '../../bindings/AirportStatus';
+import { Coordination } from '../../bindings/Coordination';
 import { DefaultAirportStatus, DefaultWorld } from './lib';
 
 const defaultURL = `${window.location.protocol}//${window.location.host}${window.location.pathname.replace(/\/$/, '')}`;
@@ -17,6 +18,16 @@ export const baseAPIPath: string = search.has('api')
   ? (search.get('api') ?? defaultURL)
   : defaultURL;
 
+// An API token, if the server requires one (e.g. `?token=...`).
+const token = search.get('token');
+
+/** Fetches from the API, with the API token if there is one. */
+export function apiFetch(path: string, init: RequestInit = {}) {
+  const headers = new Headers(init.headers);
+  if (token) headers.set('Authorization', `Bearer ${token}`);
+  return fetch(`${baseAPIPath}${path}`, { ...init, headers });
+}
+
 export type ServerTicks = { ticks: number; lastFetch: number };
 export type Ping = { connected: boolean; server_ticks: ServerTicks };
 
@@ -28,7 +39,7 @@ export function usePing() {
     queryFn: async () => {
       const server_ticks = { ticks: 0, lastFetch: Date.now() };
       try {
-        const result = await fetch(`${baseAPIPath}${getPing}`);
+        const result = await apiFetch(`${getPing}`);
         if (!result.ok) return { connected: false, server_ticks };
         server_ticks.ticks = parseInt(await result.text());
         return { connected: true, server_ticks };
@@ -54,7 +65,7 @@ export function useAircraftWithRate(renderRate: Accessor<number>) {
   return createQuery<Array<Aircraft>>(() => ({
     queryKey: [getAircraft],
     queryFn: async () => {
-      const result = await fetch(`${baseAPIPath}${getAircraft}`);
+      const result = await apiFetch(`${getAircraft}`);
       if (!result.ok) return [];
       return result.json();
     },
@@ -70,7 +81,7 @@ export function useAircraft() {
   return createQuery<Array<Aircraft>>(() => ({
     queryKey: [getAircraft],
     queryFn: async () => {
-      const result = await fetch(`${baseAPIPath}${getAircraft}`);
+      const result = await apiFetch(`${getAircraft}`);
       if (!result.ok) return [];
       return result.json();
     },
@@ -84,7 +95,7 @@ export function useWorld() {
   return createQuery<World>(() => ({
     queryKey: [getWorld],
     queryFn: async () => {
-      const result = await fetch(`${baseAPIPath}${getWorld}`);
+      const result = await apiFetch(`${getWorld}`);
       if (!result.ok) return null;
       return result.json();
     },
@@ -101,7 +112,7 @@ export function useMessages() {
   return createQuery<Array<OutgoingCommandReply>>(() => ({
     queryKey: [getMessages],
     queryFn: async () => {
-      const result = await fetch(`${baseAPIPath}${getMessages}`);
+      const result = await apiFetch(`${getMessages}`);
       if (!result.ok) return [];
       return result.json();
     },
@@ -127,6 +138,32 @@ export function useMessages() {
   }));
 }
 
+export const getCoordination = '/api/coordination';
+export function useCoordination() {
+  return createQuery<Array<Coordination>>(() => ({
+    queryKey: [getCoordination],
+    queryFn: async () => {
+      const result = await apiFetch(`${getCoordination}`);
+      if (!result.ok) return [];
+      return result.json();
+    },
+    reconcile: (oldData, newData) => {
+      // Prevent rerenders if the data hasn't changed.
+      if (oldData && fastDeepEqual(oldData, newData)) {
+        return oldData;
+      } else {
+        return newData;
+      }
+    },
+    initialData: [],
+    staleTime: 1000,
+    refetchInterval: 1000,
+    refetchOnMount: 'always',
+    refetchOnReconnect: 'always',
+    throwOnError: true,
+  }));
+}
+
 export const getAirportStatusKey = `/api/status`;
 export const getAirportStatus = (id: string) => `${getAirportStatusKey}/${id}`;
 export function useAirportStatus(id: Resource<string> | Accessor<string>) {
@@ -136,7 +173,7 @@ export function useAirportStatus(id: Resource<string> | Accessor<string>) {
       if (id() === undefined) {
         return undefined;
       } else {
-        const result = await fetch(`${baseAPIPath}${getAirportStatus(id()!)}`);
+        const result = await apiFetch(`${getAirportStatus(id()!)}`);
         if (!result.ok) return [];
         return result.json();
       }
@@ -159,7 +196,7 @@ export function useSetAirportStatus() {
   return createMutation(() => ({
     mutationKey: [postAirportStatusKey],
     mutationFn: async ({ id, status }: { id: string; status: AirportStatus }) =>
-      await fetch(`${baseAPIPath}${postAirportStatus(id)}`, {
+      await apiFetch(`${postAirportStatus(id)}`, {
         method: 'POST',
         body: JSON.stringify(status),
         headers: {
@@ -1,7 +1,7 @@
 use editor::draw::Draw;
 use engine::{
   AIRSPACE_RADIUS, APPROACH_ALTITUDE, NAUTICALMILES_TO_FEET,
-  assets::load_assets,
+  assets::{assets_root, load_assets},
   engine::Engine,
   entities::{
     aircraft::{Aircraft, AircraftState},
@@ -86,7 +86,7 @@ fn model(app: &App) -> Model {
   let egui = Egui::from_window(&window);
 
   let mut engine = Engine {
-    airports: load_assets().airports,
+    airports: load_assets(assets_root()).unwrap().airports,
     ..Default::default()
   };
 
@@ -51,10 +51,14 @@ fn draw_label(
 impl Draw for Taxiway {
   fn draw(&self, draw: &nannou::Draw, scale: f32, offset: Vec2) {
     draw
-      .line()
-      .start(glam_to_geom(scale_point(self.a, offset, scale)))
-      .end(glam_to_geom(scale_point(self.b, offset, scale)))
+      .polyline()
       .weight(200.0 * scale)
+      .points(
+        self
+          .path()
+          .into_iter()
+          .map(|p| glam_to_geom(scale_point(p, offset, scale))),
+      )
       .color(color::rgb::<u8>(
         TAXIWAY_COLOR,
         TAXIWAY_COLOR,
@@ -63,7 +67,9 @@ impl Draw for Taxiway {
   }
 
   fn draw_label(&self, draw: &nannou::Draw, scale: f32, offset: Vec2) {
-    let middle = midpoint(self.a, self.b);
+    let path = self.path();
+    let i = (path.len() - 1) / 2;
+    let middle = midpoint(path[i], path[i + 1]);
     draw_label(self.id.to_string(), middle, draw, scale, offset);
   }
 }
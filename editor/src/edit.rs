@@ -0,0 +1,474 @@
+//! Editing an airport by pointing at it, for the viewer.
+
+use engine::{
+  entities::airport::{Airport, Gate, Runway, Taxiway, Terminal},
+  geometry::{Translate, angle_between_points, find_line_intersection},
+  line::Line,
+};
+use glam::Vec2;
+use internment::Intern;
+
+use crate::closest_point_on_segment;
+
+/// Part of an airport, by its index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Item {
+  Runway(usize),
+  Taxiway(usize),
+  Terminal(usize),
+  /// A gate, by its terminal and its index in that terminal.
+  Gate(usize, usize),
+}
+
+/// The part of an item that is dragged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Handle {
+  /// The whole item.
+  Body,
+  RunwayStart,
+  RunwayEnd,
+  /// `a`, then each point of `via`, then `b`.
+  TaxiwayPoint(usize),
+  /// Corners `a` to `d`.
+  TerminalCorner(usize),
+  /// Either end of the apron.
+  ApronEnd(usize),
+}
+
+impl Item {
+  pub fn kind(&self) -> &'static str {
+    match self {
+      Self::Runway(..) => "Runway",
+      Self::Taxiway(..) => "Taxiway",
+      Self::Terminal(..) => "Terminal",
+      Self::Gate(..) => "Gate",
+    }
+  }
+
+  /// Every item in an airport, with gates before the terminals they are in so
+  /// that they are picked first.
+  pub fn all(airport: &Airport) -> Vec<Item> {
+    let mut items = Vec::new();
+    for (t, terminal) in airport.terminals.iter().enumerate() {
+      items.extend((0..terminal.gates.len()).map(|g| Item::Gate(t, g)));
+    }
+    items.extend((0..airport.runways.len()).map(Item::Runway));
+    items.extend((0..airport.taxiways.len()).map(Item::Taxiway));
+    items.extend((0..airport.terminals.len()).map(Item::Terminal));
+
+    items
+  }
+
+  pub fn id(&self, airport: &Airport) -> Option<Intern<String>> {
+    Some(match *self {
+      Self::Runway(i) => airport.runways.get(i)?.id,
+      Self::Taxiway(i) => airport.taxiways.get(i)?.id,
+      Self::Terminal(i) => airport.terminals.get(i)?.id,
+      Self::Gate(t, g) => airport.terminals.get(t)?.gates.get(g)?.id,
+    })
+  }
+
+  pub fn rename(&self, airport: &mut Airport, id: &str) {
+    let id = Intern::from_ref(id);
+    match *self {
+      Self::Runway(i) => airport.runways[i].id = id,
+      Self::Taxiway(i) => airport.taxiways[i].id = id,
+      Self::Terminal(i) => airport.terminals[i].id = id,
+      Self::Gate(t, g) => airport.terminals[t].gates[g].id = id,
+    }
+  }
+
+  /// The points the item can be dragged by.
+  pub fn handles(&self, airport: &Airport) -> Vec<(Handle, Vec2)> {
+    match *self {
+      Self::Runway(i) => {
+        let runway = &airport.runways[i];
+        vec![
+          (Handle::RunwayStart, runway.start),
+          (Handle::RunwayEnd, runway.end()),
+        ]
+      }
+      Self::Taxiway(i) => taxiway_points(&airport.taxiways[i])
+        .into_iter()
+        .enumerate()
+        .map(|(i, p)| (Handle::TaxiwayPoint(i), p))
+        .collect(),
+      Self::Terminal(i) => {
+        let terminal = &airport.terminals[i];
+        let corners = [terminal.a, terminal.b, terminal.c, terminal.d];
+        corners
+          .into_iter()
+          .enumerate()
+          .map(|(i, p)| (Handle::TerminalCorner(i), p))
+          .chain([
+            (Handle::ApronEnd(0), terminal.apron.0),
+            (Handle::ApronEnd(1), terminal.apron.1),
+          ])
+          .collect()
+      }
+      Self::Gate(t, g) => {
+        vec![(Handle::Body, airport.terminals[t].gates[g].pos)]
+      }
+    }
+  }
+
+  /// The lines the item is drawn with.
+  pub fn lines(&self, airport: &Airport) -> Vec<Line> {
+    match *self {
+      Self::Runway(i) => vec![airport.runways[i].clone().into()],
+      Self::Taxiway(i) => airport.taxiways[i].segments(),
+      Self::Terminal(i) => {
+        let terminal = &airport.terminals[i];
+        let corners = [terminal.a, terminal.b, terminal.c, terminal.d];
+        (0..4)
+          .map(|i| Line::new(corners[i], corners[(i + 1) % 4]))
+          .chain([terminal.apron])
+          .collect()
+      }
+      Self::Gate(..) => Vec::new(),
+    }
+  }
+
+  /// Moves part of the item to a point.
+  pub fn drag(&self, airport: &mut Airport, handle: Handle, to: Vec2) {
+    match (*self, handle) {
+      (Self::Runway(i), Handle::RunwayStart) => {
+        let runway = &mut airport.runways[i];
+        let end = runway.end();
+        *runway = runway_between(runway.id, to, end);
+      }
+      (Self::Runway(i), Handle::RunwayEnd) => {
+        let runway = &mut airport.runways[i];
+        *runway = runway_between(runway.id, runway.start, to);
+      }
+      (Self::Taxiway(i), Handle::TaxiwayPoint(p)) => {
+        let taxiway = &mut airport.taxiways[i];
+        if p == 0 {
+          taxiway.a = to;
+        } else if let Some(via) = taxiway.via.get_mut(p - 1) {
+          *via = to;
+        } else {
+          taxiway.b = to;
+        }
+      }
+      (Self::Terminal(i), Handle::TerminalCorner(c)) => {
+        let terminal = &mut airport.terminals[i];
+        let corner = match c {
+          0 => &mut terminal.a,
+          1 => &mut terminal.b,
+          2 => &mut terminal.c,
+          _ => &mut terminal.d,
+        };
+        *corner = to;
+      }
+      (Self::Terminal(i), Handle::ApronEnd(end)) => {
+        let apron = &mut airport.terminals[i].apron;
+        if end == 0 {
+          apron.0 = to;
+        } else {
+          apron.1 = to;
+        }
+      }
+      (Self::Gate(t, g), _) => airport.terminals[t].gates[g].pos = to,
+      // Whole items are moved with `translate`.
+      _ => {}
+    }
+  }
+
+  /// Moves the whole item by an offset.
+  pub fn translate(&self, airport: &mut Airport, offset: Vec2) {
+    match *self {
+      Self::Runway(i) => {
+        airport.runways[i].translate(offset);
+      }
+      Self::Taxiway(i) => {
+        airport.taxiways[i].translate(offset);
+      }
+      Self::Terminal(i) => {
+        airport.terminals[i].translate(offset);
+      }
+      Self::Gate(t, g) => {
+        airport.terminals[t].gates[g].translate(offset);
+      }
+    }
+  }
+
+  pub fn remove(&self, airport: &mut Airport) {
+    match *self {
+      Self::Runway(i) => {
+        airport.runways.remove(i);
+      }
+      Self::Taxiway(i) => {
+        airport.taxiways.remove(i);
+      }
+      Self::Terminal(i) => {
+        airport.terminals.remove(i);
+      }
+      Self::Gate(t, g) => {
+        airport.terminals[t].gates.remove(g);
+      }
+    }
+  }
+}
+
+fn taxiway_points(taxiway: &Taxiway) -> Vec<Vec2> {
+  core::iter::once(taxiway.a)
+    .chain(taxiway.via.iter().copied())
+    .chain(core::iter::once(taxiway.b))
+    .collect()
+}
+
+fn runway_between(id: Intern<String>, start: Vec2, end: Vec2) -> Runway {
+  Runway {
+    id,
+    start,
+    heading: angle_between_points(start, end),
+    length: start.distance(end),
+  }
+}
+
+/// The item, and which part of it, that is within `tolerance` of a point.
+/// Handles are picked before the lines between them.
+pub fn pick(
+  airport: &Airport,
+  pos: Vec2,
+  tolerance: f32,
+) -> Option<(Item, Handle)> {
+  let items = Item::all(airport);
+
+  let handle = items
+    .iter()
+    .flat_map(|item| {
+      item
+        .handles(airport)
+        .into_iter()
+        .map(move |(handle, point)| (*item, handle, point.distance(pos)))
+    })
+    .filter(|(_, _, distance)| *distance <= tolerance)
+    .min_by(|a, b| a.2.total_cmp(&b.2));
+  if let Some((item, handle, _)) = handle {
+    return Some((item, handle));
+  }
+
+  items
+    .iter()
+    .map(|item| {
+      let distance = item
+        .lines(airport)
+        .iter()
+        .map(|l| closest_point_on_segment(pos, l.0, l.1).distance(pos))
+        .fold(f32::MAX, f32::min);
+      (*item, distance)
+    })
+    .filter(|(_, distance)| *distance <= tolerance)
+    .min_by(|a, b| a.1.total_cmp(&b.1))
+    .map(|(item, _)| (item, Handle::Body))
+}
+
+/// Snaps a point to the nearest place where lines cross or end, or else onto
+/// the nearest line, if either is within `tolerance`. The item being moved,
+/// if any, is ignored.
+pub fn snap(
+  airport: &Airport,
+  pos: Vec2,
+  tolerance: f32,
+  ignore: Option<Item>,
+) -> Vec2 {
+  let items: Vec<Item> = Item::all(airport)
+    .into_iter()
+    .filter(|item| Some(*item) != ignore)
+    .collect();
+  let lines: Vec<Line> =
+    items.iter().flat_map(|item| item.lines(airport)).collect();
+
+  let mut points: Vec<Vec2> = items
+    .iter()
+    .flat_map(|item| item.handles(airport))
+    .map(|(_, point)| point)
+    .collect();
+  for (i, a) in lines.iter().enumerate() {
+    points.extend(
+      lines[i + 1..]
+        .iter()
+        .filter_map(|b| find_line_intersection(*a, *b)),
+    );
+  }
+
+  nearest(points.into_iter(), pos, tolerance)
+    .or_else(|| {
+      let on_lines = lines
+        .iter()
+        .map(|l| closest_point_on_segment(pos, l.0, l.1));
+      nearest(on_lines, pos, tolerance)
+    })
+    .unwrap_or(pos)
+}
+
+fn nearest(
+  points: impl Iterator<Item = Vec2>,
+  pos: Vec2,
+  tolerance: f32,
+) -> Option<Vec2> {
+  points
+    .filter(|p| p.distance(pos) <= tolerance)
+    .min_by(|a, b| a.distance(pos).total_cmp(&b.distance(pos)))
+}
+
+/// An ID like `prefix1` that nothing in the airport has yet.
+fn unused_id(airport: &Airport, prefix: &str) -> Intern<String> {
+  let ids: Vec<Intern<String>> = Item::all(airport)
+    .iter()
+    .filter_map(|item| item.id(airport))
+    .collect();
+
+  (1..)
+    .map(|n| Intern::from(format!("{prefix}{n}")))
+    .find(|id| !ids.contains(id))
+    .unwrap()
+}
+
+/// Adds a runway from `start` to `end`, named after its heading.
+pub fn add_runway(airport: &mut Airport, start: Vec2, end: Vec2) -> Item {
+  let heading = angle_between_points(start, end);
+  let number = match (heading / 10.0).round() as u8 {
+    0 => 36,
+    n => n,
+  };
+
+  airport.runways.push(runway_between(
+    Intern::from(format!("{number:02}")),
+    start,
+    end,
+  ));
+  Item::Runway(airport.runways.len() - 1)
+}
+
+pub fn add_taxiway(airport: &mut Airport, a: Vec2, b: Vec2) -> Item {
+  let id = unused_id(airport, "TWY");
+  airport.taxiways.push(Taxiway::new(id, a, b));
+  Item::Taxiway(airport.taxiways.len() - 1)
+}
+
+/// Adds a terminal between two opposite corners, with its apron along the
+/// bottom.
+pub fn add_terminal(airport: &mut Airport, from: Vec2, to: Vec2) -> Item {
+  let (min, max) = (from.min(to), from.max(to));
+  let id = unused_id(airport, "T");
+  airport.terminals.push(Terminal {
+    id,
+    a: Vec2::new(min.x, max.y),
+    b: max,
+    c: Vec2::new(max.x, min.y),
+    d: min,
+    gates: Vec::new(),
+    apron: Line::new(min, Vec2::new(max.x, min.y)),
+  });
+  Item::Terminal(airport.terminals.len() - 1)
+}
+
+/// Adds a gate to the nearest terminal, facing its apron. Gates need a
+/// terminal, so nothing is added if there are none.
+pub fn add_gate(airport: &mut Airport, pos: Vec2) -> Option<Item> {
+  let apron_point =
+    |t: &Terminal| closest_point_on_segment(pos, t.apron.0, t.apron.1);
+  let (index, terminal) =
+    airport.terminals.iter().enumerate().min_by(|a, b| {
+      apron_point(a.1)
+        .distance(pos)
+        .total_cmp(&apron_point(b.1).distance(pos))
+    })?;
+
+  let gate = Gate {
+    id: unused_id(airport, "G"),
+    pos,
+    heading: angle_between_points(pos, apron_point(terminal)),
+    available: true,
+  };
+  airport.terminals[index].gates.push(gate);
+
+  Some(Item::Gate(index, airport.terminals[index].gates.len() - 1))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn airport() -> Airport {
+    let mut airport = Airport::new(Intern::from_ref("KTST"), Vec2::ZERO);
+    add_runway(
+      &mut airport,
+      Vec2::new(0.0, 1000.0),
+      Vec2::new(0.0, -1000.0),
+    );
+    add_taxiway(&mut airport, Vec2::new(-500.0, 0.0), Vec2::new(500.0, 0.0));
+
+    airport
+  }
+
+  #[test]
+  fn test_add() {
+    let mut airport = airport();
+    assert_eq!(airport.runways[0].id.as_str(), "18");
+    assert_eq!(airport.taxiways[0].id.as_str(), "TWY1");
+
+    add_terminal(
+      &mut airport,
+      Vec2::new(100.0, 300.0),
+      Vec2::new(400.0, 100.0),
+    );
+    let gate = add_gate(&mut airport, Vec2::new(250.0, 200.0)).unwrap();
+    assert_eq!(gate, Item::Gate(0, 0));
+    assert_eq!(airport.terminals[0].gates[0].id.as_str(), "G1");
+    // Facing the apron along the bottom of the terminal.
+    assert!((airport.terminals[0].gates[0].heading - 180.0).abs() < 0.1);
+  }
+
+  #[test]
+  fn test_pick_and_drag() {
+    let mut airport = airport();
+
+    assert_eq!(
+      pick(&airport, Vec2::new(5.0, -995.0), 20.0),
+      Some((Item::Runway(0), Handle::RunwayEnd))
+    );
+    assert_eq!(
+      pick(&airport, Vec2::new(200.0, 10.0), 20.0),
+      Some((Item::Taxiway(0), Handle::Body))
+    );
+    assert_eq!(pick(&airport, Vec2::new(200.0, 100.0), 20.0), None);
+
+    Item::Runway(0).drag(
+      &mut airport,
+      Handle::RunwayEnd,
+      Vec2::new(0.0, -2000.0),
+    );
+    assert_eq!(airport.runways[0].length, 3000.0);
+    assert_eq!(airport.runways[0].start, Vec2::new(0.0, 1000.0));
+  }
+
+  #[test]
+  fn test_snap() {
+    let airport = airport();
+
+    // To where the runway and taxiway cross.
+    assert!(
+      snap(&airport, Vec2::new(10.0, -10.0), 50.0, None)
+        .abs_diff_eq(Vec2::ZERO, 0.01)
+    );
+    // Onto the taxiway.
+    assert!(
+      snap(&airport, Vec2::new(200.0, 10.0), 50.0, None)
+        .abs_diff_eq(Vec2::new(200.0, 0.0), 0.01)
+    );
+    // The taxiway is ignored while it is being moved.
+    assert_eq!(
+      snap(
+        &airport,
+        Vec2::new(200.0, 10.0),
+        50.0,
+        Some(Item::Taxiway(0))
+      ),
+      Vec2::new(200.0, 10.0)
+    );
+  }
+}
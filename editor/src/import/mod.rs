@@ -0,0 +1,249 @@
+//! Importers that build airports from real-world data.
+
+pub mod osm;
+pub mod xplane;
+
+use std::{collections::HashMap, fs, path::Path};
+
+use engine::{
+  entities::airport::{Airport, Gate, Runway, Taxiway, Terminal},
+  geometry::{angle_between_points, delta_angle},
+  line::Line,
+};
+use glam::Vec2;
+use internment::Intern;
+
+use crate::closest_point_on_segment;
+
+/// Gates closer than this to each other share a terminal.
+const GATE_CLUSTER_DISTANCE: f32 = 1500.0;
+/// How far a terminal's outline reaches past its outermost gates.
+const TERMINAL_PADDING: f32 = 200.0;
+/// How far an apron reaches past the taxiway it joins, so that they cross.
+const APRON_OVERLAP: f32 = 100.0;
+/// The most that a taxiway can bend and still be treated as straight.
+const MAX_STRAIGHT_BEND: f32 = 2.0;
+
+#[derive(Debug)]
+pub enum ImportError {
+  Read(std::io::Error),
+  /// The file has no airport with this ID.
+  NotFound(String),
+  /// A line that couldn't be read.
+  Invalid(usize, String),
+  NoRunways(String),
+  /// An OpenStreetMap file that couldn't be read.
+  Osm(String),
+}
+
+impl core::fmt::Display for ImportError {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    match self {
+      Self::Read(e) => write!(f, "failed to read file: {e}"),
+      Self::NotFound(id) => write!(f, "airport {id} not found"),
+      Self::Invalid(line, message) => write!(f, "line {line}: {message}"),
+      Self::NoRunways(id) => write!(f, "airport {id} has no runways"),
+      Self::Osm(e) => write!(f, "failed to read OpenStreetMap data: {e}"),
+    }
+  }
+}
+
+impl std::error::Error for ImportError {}
+
+/// Reads the airport `icao` from an OpenStreetMap extract (`.osm` or `.pbf`),
+/// or otherwise an X-Plane `apt.dat` file.
+pub fn read_airport(input: &Path, icao: &str) -> Result<Airport, ImportError> {
+  match input.extension().and_then(|e| e.to_str()) {
+    Some("osm" | "xml" | "pbf") => osm::read(input, icao),
+    _ => {
+      let source = fs::read_to_string(input).map_err(ImportError::Read)?;
+      xplane::parse(&source, icao)
+    }
+  }
+}
+
+/// Both ends of a runway, from threshold `a` to threshold `b`.
+pub fn runway_pair(a_id: &str, a: Vec2, b_id: &str, b: Vec2) -> [Runway; 2] {
+  let length = a.distance(b);
+  [
+    Runway {
+      id: Intern::from_ref(a_id),
+      start: a,
+      heading: angle_between_points(a, b),
+      length,
+    },
+    Runway {
+      id: Intern::from_ref(b_id),
+      start: b,
+      heading: angle_between_points(b, a),
+      length,
+    },
+  ]
+}
+
+/// Joins taxiway segments with the same name that continue each other into
+/// one taxiway that bends, dropping bends too slight to matter.
+///
+/// Real taxiways are made up of many short segments, but the segments of a
+/// taxiway must be one taxiway to be taxied via by name. Segments can only be
+/// joined where exactly two of them meet, so a taxiway that branches is left
+/// as more than one.
+pub fn join_segments(segments: Vec<Taxiway>) -> Vec<Taxiway> {
+  let mut by_name: HashMap<Intern<String>, Vec<Taxiway>> = HashMap::new();
+  for segment in segments {
+    by_name.entry(segment.id).or_default().push(segment);
+  }
+
+  let mut names: Vec<_> = by_name.keys().copied().collect();
+  names.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+
+  let mut joined = Vec::new();
+  for name in names {
+    let mut group = by_name.remove(&name).unwrap_or_default();
+    while let Some(first) = group.pop() {
+      let mut points = vec![first.a, first.b];
+      while let Some(point) = extend_chain(&mut group, *points.last().unwrap())
+      {
+        points.push(point);
+      }
+      points.reverse();
+      while let Some(point) = extend_chain(&mut group, *points.last().unwrap())
+      {
+        points.push(point);
+      }
+
+      let points = straighten(points);
+      let [a, via @ .., b] = points.as_slice() else {
+        continue;
+      };
+      joined.push(Taxiway::new(name, *a, *b).with_via(via.to_vec()));
+    }
+  }
+
+  joined
+}
+
+/// Takes the segment that continues from `end`, returning its far end, if
+/// exactly one does.
+fn extend_chain(group: &mut Vec<Taxiway>, end: Vec2) -> Option<Vec2> {
+  let touches =
+    |t: &Taxiway| t.a.distance(end) < 1.0 || t.b.distance(end) < 1.0;
+  let mut touching = group.iter().enumerate().filter(|(_, t)| touches(t));
+  let (i, _) = touching.next()?;
+  if touching.next().is_some() {
+    return None;
+  }
+
+  let segment = group.swap_remove(i);
+  Some(if segment.a.distance(end) < 1.0 {
+    segment.b
+  } else {
+    segment.a
+  })
+}
+
+/// Drops the points of a line that it barely bends at.
+fn straighten(points: Vec<Vec2>) -> Vec<Vec2> {
+  let mut straight: Vec<Vec2> = Vec::with_capacity(points.len());
+  for (i, point) in points.iter().enumerate() {
+    if let (Some(before), Some(after)) = (straight.last(), points.get(i + 1)) {
+      let bend = delta_angle(
+        angle_between_points(*before, *point),
+        angle_between_points(*point, *after),
+      );
+      if bend.abs() <= MAX_STRAIGHT_BEND {
+        continue;
+      }
+    }
+
+    straight.push(*point);
+  }
+
+  straight
+}
+
+/// Groups gates that are near each other into terminals, each with an apron
+/// that joins the nearest taxiway.
+pub fn group_gates(gates: Vec<Gate>, taxiways: &[Taxiway]) -> Vec<Terminal> {
+  let mut clusters: Vec<Vec<Gate>> = Vec::new();
+  for gate in gates {
+    let near: Vec<usize> = clusters
+      .iter()
+      .enumerate()
+      .filter(|(_, c)| {
+        c.iter()
+          .any(|g| g.pos.distance(gate.pos) <= GATE_CLUSTER_DISTANCE)
+      })
+      .map(|(i, _)| i)
+      .collect();
+
+    // Join every cluster the gate is near into one.
+    let mut cluster = vec![gate];
+    for i in near.into_iter().rev() {
+      cluster.extend(clusters.swap_remove(i));
+    }
+    clusters.push(cluster);
+  }
+
+  clusters
+    .into_iter()
+    .enumerate()
+    .map(|(i, gates)| terminal(i, gates, &[], taxiways))
+    .collect()
+}
+
+/// A terminal around its gates and any outline, such as a building, with an
+/// apron that joins the nearest taxiway.
+pub fn terminal(
+  index: usize,
+  gates: Vec<Gate>,
+  outline: &[Vec2],
+  taxiways: &[Taxiway],
+) -> Terminal {
+  let points = gates.iter().map(|g| g.pos).chain(outline.iter().copied());
+  let min =
+    points.clone().fold(Vec2::MAX, Vec2::min) - Vec2::splat(TERMINAL_PADDING);
+  let max = points.fold(Vec2::MIN, Vec2::max) + Vec2::splat(TERMINAL_PADDING);
+  let center = gates.iter().map(|g| g.pos).sum::<Vec2>() / gates.len() as f32;
+
+  let apron = match nearest_taxiway_point(center, taxiways) {
+    Some(point) if point.distance(center) > 1.0 => {
+      Line::new(center, point.move_towards(center, -APRON_OVERLAP))
+    }
+    // Without a taxiway to join, run the apron across the gates.
+    _ => Line::new(Vec2::new(min.x, center.y), Vec2::new(max.x, center.y)),
+  };
+
+  Terminal {
+    id: Intern::from(format!("T{}", index + 1)),
+    a: Vec2::new(min.x, max.y),
+    b: max,
+    c: Vec2::new(max.x, min.y),
+    d: min,
+    gates,
+    apron,
+  }
+}
+
+/// The closest point to `point` on any taxiway.
+pub fn nearest_taxiway_point(
+  point: Vec2,
+  taxiways: &[Taxiway],
+) -> Option<Vec2> {
+  taxiways
+    .iter()
+    .flat_map(|t| t.segments())
+    .map(|line| closest_point_on_segment(point, line.0, line.1))
+    .min_by(|a, b| a.distance(point).total_cmp(&b.distance(point)))
+}
+
+/// A name that can be said over the radio, such as "A5" for "Gate A 5".
+pub fn clean_name(name: &str) -> String {
+  let name = name.trim();
+  let name = name
+    .strip_prefix("Gate ")
+    .or_else(|| name.strip_prefix("GATE "))
+    .unwrap_or(name);
+
+  name.split_whitespace().collect::<Vec<_>>().join("")
+}
@@ -0,0 +1,449 @@
+//! Imports airports from OpenStreetMap extracts, in XML (`.osm`) or PBF
+//! (`.osm.pbf`) form.
+//!
+//! Only `aeroway` features are used. If the extract has an
+//! `aeroway=aerodrome` area tagged with the airport's ICAO code, features
+//! outside of it are ignored; otherwise the whole extract is imported.
+
+use std::{collections::HashMap, fs, path::Path};
+
+use engine::{
+  entities::airport::{Airport, Gate, Runway, Taxiway},
+  geo::LatLon,
+  geometry::{angle_between_points, delta_angle},
+  validate::reciprocal_id,
+};
+use glam::Vec2;
+use internment::Intern;
+use osmpbf::{Element, ElementReader};
+
+use super::{
+  ImportError, clean_name, group_gates, join_segments, nearest_taxiway_point,
+  runway_pair, terminal,
+};
+
+/// A node or way with an `aeroway` tag.
+#[derive(Debug, Default)]
+struct Feature {
+  tags: HashMap<String, String>,
+  /// The way's nodes, or just the node itself.
+  nodes: Vec<i64>,
+}
+
+impl Feature {
+  fn aeroway(&self) -> Option<&str> {
+    self.tags.get("aeroway").map(String::as_str)
+  }
+
+  /// A name that can be used in a clearance.
+  fn reference(&self) -> Option<&str> {
+    self
+      .tags
+      .get("ref")
+      .or_else(|| self.tags.get("name"))
+      .map(String::as_str)
+  }
+
+  fn is_closed(&self) -> bool {
+    self.nodes.len() > 2 && self.nodes.first() == self.nodes.last()
+  }
+}
+
+#[derive(Debug, Default)]
+struct OsmData {
+  positions: HashMap<i64, LatLon>,
+  features: Vec<Feature>,
+}
+
+impl OsmData {
+  fn add_node<'a>(
+    &mut self,
+    id: i64,
+    pos: LatLon,
+    tags: impl Iterator<Item = (&'a str, &'a str)>,
+  ) {
+    self.positions.insert(id, pos);
+    self.add_feature(vec![id], tags);
+  }
+
+  fn add_feature<'a>(
+    &mut self,
+    nodes: Vec<i64>,
+    tags: impl Iterator<Item = (&'a str, &'a str)>,
+  ) {
+    let tags: HashMap<String, String> =
+      tags.map(|(k, v)| (k.to_owned(), v.to_owned())).collect();
+    if tags.contains_key("aeroway") {
+      self.features.push(Feature { tags, nodes });
+    }
+  }
+
+  fn points(&self, feature: &Feature) -> Vec<LatLon> {
+    feature
+      .nodes
+      .iter()
+      .filter_map(|id| self.positions.get(id).copied())
+      .collect()
+  }
+}
+
+/// Reads the airport `icao` from an OpenStreetMap extract.
+pub fn read(path: &Path, icao: &str) -> Result<Airport, ImportError> {
+  let data = if path.extension().is_some_and(|e| e == "pbf") {
+    read_pbf(path)?
+  } else {
+    parse_xml(&fs::read_to_string(path).map_err(ImportError::Read)?)?
+  };
+
+  build(&data, icao)
+}
+
+fn read_pbf(path: &Path) -> Result<OsmData, ImportError> {
+  let reader = ElementReader::from_path(path)
+    .map_err(|e| ImportError::Osm(e.to_string()))?;
+
+  let mut data = OsmData::default();
+  reader
+    .for_each(|element| match element {
+      Element::Node(n) => {
+        data.add_node(n.id(), LatLon::new(n.lat(), n.lon()), n.tags())
+      }
+      Element::DenseNode(n) => {
+        data.add_node(n.id(), LatLon::new(n.lat(), n.lon()), n.tags())
+      }
+      Element::Way(w) => data.add_feature(w.refs().collect(), w.tags()),
+      Element::Relation(_) => {}
+    })
+    .map_err(|e| ImportError::Osm(e.to_string()))?;
+
+  Ok(data)
+}
+
+fn parse_xml(source: &str) -> Result<OsmData, ImportError> {
+  let document = roxmltree::Document::parse(source)
+    .map_err(|e| ImportError::Osm(e.to_string()))?;
+
+  let mut data = OsmData::default();
+  for element in document.root_element().children() {
+    let children = || element.children().filter(|c| c.is_element());
+    let tags = children()
+      .filter(|c| c.has_tag_name("tag"))
+      .filter_map(|c| Some((c.attribute("k")?, c.attribute("v")?)));
+    if element.has_tag_name("node") {
+      let pos = LatLon::new(
+        attribute(&document, element, "lat")?,
+        attribute(&document, element, "lon")?,
+      );
+      data.add_node(attribute(&document, element, "id")?, pos, tags);
+    } else if element.has_tag_name("way") {
+      let nodes = children()
+        .filter(|c| c.has_tag_name("nd"))
+        .filter_map(|c| c.attribute("ref")?.parse().ok())
+        .collect();
+      data.add_feature(nodes, tags);
+    }
+  }
+
+  Ok(data)
+}
+
+fn attribute<T: core::str::FromStr>(
+  document: &roxmltree::Document,
+  element: roxmltree::Node,
+  name: &str,
+) -> Result<T, ImportError> {
+  element
+    .attribute(name)
+    .and_then(|v| v.parse().ok())
+    .ok_or_else(|| {
+      let line = document.text_pos_at(element.range().start).row;
+      ImportError::Invalid(line as usize, format!("invalid or missing {name}"))
+    })
+}
+
+fn build(data: &OsmData, icao: &str) -> Result<Airport, ImportError> {
+  let aerodrome = data.features.iter().find(|f| {
+    f.aeroway() == Some("aerodrome")
+      && f.is_closed()
+      && f
+        .tags
+        .get("icao")
+        .is_some_and(|i| i.eq_ignore_ascii_case(icao))
+  });
+  let bounds = aerodrome.map(|f| {
+    let points = data.points(f);
+    let min = points.iter().fold(LatLon::new(f64::MAX, f64::MAX), |m, p| {
+      LatLon::new(m.lat.min(p.lat), m.lon.min(p.lon))
+    });
+    let max = points.iter().fold(LatLon::new(f64::MIN, f64::MIN), |m, p| {
+      LatLon::new(m.lat.max(p.lat), m.lon.max(p.lon))
+    });
+    (min, max)
+  });
+
+  // Features of a kind, as the points along them.
+  let features = |kinds: &[&str]| {
+    data
+      .features
+      .iter()
+      .filter(|f| f.aeroway().is_some_and(|a| kinds.contains(&a)))
+      .map(|f| (f, data.points(f)))
+      .filter(|(_, points)| {
+        bounds.is_none_or(|(min, max)| {
+          points.iter().any(|p| {
+            (min.lat..=max.lat).contains(&p.lat)
+              && (min.lon..=max.lon).contains(&p.lon)
+          })
+        })
+      })
+      .collect::<Vec<_>>()
+  };
+
+  let runways = features(&["runway"]);
+  let origin = {
+    let points: Vec<LatLon> = match aerodrome {
+      Some(f) => data.points(f),
+      None => runways
+        .iter()
+        .flat_map(|(_, p)| p.iter().copied())
+        .collect(),
+    };
+    if points.is_empty() {
+      return Err(ImportError::NoRunways(icao.to_owned()));
+    }
+
+    let count = points.len() as f64;
+    let (lat, lon) = points
+      .iter()
+      .fold((0.0, 0.0), |(lat, lon), p| (lat + p.lat, lon + p.lon));
+    LatLon::new(lat / count, lon / count)
+  };
+  let project = |points: &[LatLon]| -> Vec<Vec2> {
+    points.iter().map(|p| p.project(origin)).collect()
+  };
+
+  let mut airport = Airport::new(Intern::from(icao.to_uppercase()), Vec2::ZERO);
+  airport.location = Some(origin);
+
+  // Runways mapped as areas are usually drawn alongside their centerline.
+  let mut by_ref: Vec<(Option<&str>, Vec<Vec2>)> = Vec::new();
+  for (feature, points) in runways.iter().filter(|(f, _)| !f.is_closed()) {
+    let reference = feature.tags.get("ref").map(String::as_str);
+    let points = project(points);
+    // Runways split into several ways share a ref.
+    match by_ref
+      .iter_mut()
+      .find(|(r, _)| reference.is_some() && *r == reference)
+    {
+      Some((_, existing)) => existing.extend(points),
+      None => by_ref.push((reference, points)),
+    }
+  }
+  for (reference, points) in by_ref {
+    airport
+      .runways
+      .extend(runway_ends(reference, &points).into_iter().flatten());
+  }
+  if airport.runways.is_empty() {
+    return Err(ImportError::NoRunways(icao.to_owned()));
+  }
+
+  // Split taxiways into straight segments, then join the ones that line up.
+  let mut segments = Vec::new();
+  for (feature, points) in features(&["taxiway", "taxilane"]) {
+    let Some(name) = feature.reference() else {
+      continue;
+    };
+
+    let id = Intern::from(clean_name(name));
+    segments.extend(
+      project(&points)
+        .windows(2)
+        .map(|w| Taxiway::new(id, w[0], w[1])),
+    );
+  }
+  airport.taxiways = join_segments(segments);
+
+  // Gates face the taxiway they push back onto.
+  let mut gates = Vec::new();
+  for (feature, points) in features(&["gate"]) {
+    let points = project(&points);
+    let (Some(name), &[pos]) = (feature.reference(), points.as_slice()) else {
+      continue;
+    };
+
+    gates.push(Gate {
+      id: Intern::from(clean_name(name)),
+      pos,
+      heading: nearest_taxiway_point(pos, &airport.taxiways)
+        .map(|p| angle_between_points(pos, p))
+        .unwrap_or_default(),
+      available: true,
+    });
+  }
+
+  // Use terminal buildings to group gates, or aprons if there are none.
+  let mut outlines = Vec::new();
+  for kind in ["terminal", "apron"] {
+    if outlines.is_empty() {
+      outlines = features(&[kind])
+        .into_iter()
+        .filter(|(f, _)| f.is_closed())
+        .map(|(_, points)| project(&points))
+        .collect();
+    }
+  }
+
+  airport.terminals = if outlines.is_empty() {
+    group_gates(gates, &airport.taxiways)
+  } else {
+    let centers: Vec<Vec2> = outlines
+      .iter()
+      .map(|o| o.iter().sum::<Vec2>() / o.len() as f32)
+      .collect();
+    let mut groups: Vec<Vec<Gate>> = vec![Vec::new(); outlines.len()];
+    for gate in gates {
+      let nearest = (0..centers.len())
+        .min_by(|a, b| {
+          centers[*a]
+            .distance(gate.pos)
+            .total_cmp(&centers[*b].distance(gate.pos))
+        })
+        .unwrap_or_default();
+      groups[nearest].push(gate);
+    }
+
+    groups
+      .into_iter()
+      .zip(outlines.iter())
+      .filter(|(gates, _)| !gates.is_empty())
+      .enumerate()
+      .map(|(i, (gates, outline))| {
+        terminal(i, gates, outline, &airport.taxiways)
+      })
+      .collect()
+  };
+
+  Ok(airport)
+}
+
+/// Both ends of a runway from the points along it.
+///
+/// The ends are the two points furthest apart. They're named from the
+/// runway's `ref`, such as "10L/28R", matching each number to the heading it
+/// points along, or from the runway's heading if it has no usable `ref`.
+fn runway_ends(
+  reference: Option<&str>,
+  points: &[Vec2],
+) -> Option<[Runway; 2]> {
+  let (a, b) = points
+    .iter()
+    .enumerate()
+    .flat_map(|(i, a)| points[i + 1..].iter().map(move |b| (*a, *b)))
+    .max_by(|x, y| x.0.distance(x.1).total_cmp(&y.0.distance(y.1)))?;
+  let heading = angle_between_points(a, b);
+
+  let ids: Vec<String> = reference
+    .map(|r| r.split('/').map(|id| id.trim().to_owned()).collect())
+    .unwrap_or_default();
+  let (first, second) = match ids.as_slice() {
+    [first, second] => (first.clone(), second.clone()),
+    [first] => (first.clone(), reciprocal_id(first)?),
+    _ => {
+      let number = match (heading / 10.0).round() as u8 {
+        0 => 36,
+        n => n,
+      };
+      let first = format!("{number:02}");
+      let second = reciprocal_id(&first)?;
+      (first, second)
+    }
+  };
+
+  // Runway numbers are magnetic, but are close enough to tell the ends apart.
+  let number_heading = |id: &str| -> Option<f32> {
+    let digits: String = id.chars().take_while(char::is_ascii_digit).collect();
+    digits.parse::<f32>().ok().map(|n| n * 10.0)
+  };
+  let first_starts_at_a = number_heading(&first)
+    .is_none_or(|h| delta_angle(heading, h).abs() <= 90.0);
+
+  Some(if first_starts_at_a {
+    runway_pair(&first, a, &second, b)
+  } else {
+    runway_pair(&first, b, &second, a)
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const OSM: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<osm version="0.6">
+  <node id="1" lat="37.000" lon="-122.010"/>
+  <node id="2" lat="37.000" lon="-122.000"/>
+  <node id="3" lat="37.000" lon="-121.990"/>
+  <node id="4" lat="37.002" lon="-122.010"/>
+  <node id="5" lat="37.002" lon="-122.000"/>
+  <node id="6" lat="37.002" lon="-121.990"/>
+  <node id="7" lat="37.003" lon="-122.000">
+    <tag k="aeroway" v="gate"/>
+    <tag k="ref" v="B 4"/>
+  </node>
+  <way id="10">
+    <nd ref="3"/>
+    <nd ref="2"/>
+    <tag k="aeroway" v="runway"/>
+    <tag k="ref" v="09/27"/>
+  </way>
+  <way id="11">
+    <nd ref="2"/>
+    <nd ref="1"/>
+    <tag k="aeroway" v="runway"/>
+    <tag k="ref" v="09/27"/>
+  </way>
+  <way id="12">
+    <nd ref="4"/>
+    <nd ref="5"/>
+    <nd ref="6"/>
+    <tag k="aeroway" v="taxiway"/>
+    <tag k="ref" v="A"/>
+  </way>
+</osm>"#;
+
+  #[test]
+  fn test_build() {
+    let airport = build(&parse_xml(OSM).unwrap(), "KTST").unwrap();
+
+    // The two halves of the runway are joined, and 09 starts at the west end.
+    assert_eq!(airport.runways.len(), 2);
+    let [r09, r27] = [&airport.runways[0], &airport.runways[1]];
+    assert_eq!((r09.id.as_str(), r27.id.as_str()), ("09", "27"));
+    assert!(r09.start.x < r27.start.x);
+    assert!((r09.heading - 90.0).abs() < 1.0);
+
+    // The taxiway's segments are in line, so they're joined.
+    assert_eq!(airport.taxiways.len(), 1);
+
+    let gate = &airport.terminals[0].gates[0];
+    assert_eq!(gate.id.as_str(), "B4");
+    assert!((gate.heading - 180.0).abs() < 1.0);
+  }
+
+  #[test]
+  fn test_runway_ends() {
+    let points = [Vec2::new(0.0, 1000.0), Vec2::new(0.0, -1000.0)];
+
+    let [a, b] = runway_ends(None, &points).unwrap();
+    assert_eq!((a.id.as_str(), b.id.as_str()), ("18", "36"));
+    assert_eq!(a.start, points[0]);
+
+    let [a, b] = runway_ends(Some("36R/18L"), &points).unwrap();
+    assert_eq!((a.id.as_str(), b.id.as_str()), ("36R", "18L"));
+    assert_eq!(a.start, points[1]);
+
+    let [a, b] = runway_ends(Some("18"), &points).unwrap();
+    assert_eq!((a.id.as_str(), b.id.as_str()), ("18", "36"));
+  }
+}
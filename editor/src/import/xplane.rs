@@ -0,0 +1,299 @@
+//! Imports airports from X-Plane's `apt.dat` format.
+//!
+//! See <https://developer.x-plane.com/article/airport-data-apt-dat-12-00-file-format-specification/>.
+
+use std::collections::HashMap;
+
+use engine::{
+  entities::airport::{Airport, Frequencies, Gate, Taxiway},
+  geo::LatLon,
+};
+use glam::Vec2;
+use internment::Intern;
+
+use super::{ImportError, clean_name, group_gates, join_segments, runway_pair};
+
+/// Row codes that start a land airport, seaplane base or heliport.
+const AIRPORT_HEADERS: [&str; 3] = ["1", "16", "17"];
+const END_OF_FILE: &str = "99";
+
+struct Row<'a> {
+  line: usize,
+  fields: Vec<&'a str>,
+}
+
+impl<'a> Row<'a> {
+  fn code(&self) -> &'a str {
+    self.fields[0]
+  }
+
+  fn field(&self, index: usize) -> Result<&'a str, ImportError> {
+    self.fields.get(index).copied().ok_or_else(|| {
+      ImportError::Invalid(self.line, format!("missing field {index}"))
+    })
+  }
+
+  fn number<T: core::str::FromStr>(
+    &self,
+    index: usize,
+  ) -> Result<T, ImportError> {
+    let field = self.field(index)?;
+    field.parse().map_err(|_| {
+      ImportError::Invalid(self.line, format!("invalid number \"{field}\""))
+    })
+  }
+
+  fn lat_lon(&self, index: usize) -> Result<LatLon, ImportError> {
+    Ok(LatLon::new(self.number(index)?, self.number(index + 1)?))
+  }
+
+  /// The rest of the row from a field, for names that contain spaces.
+  fn text(&self, index: usize) -> String {
+    self.fields.get(index..).unwrap_or_default().join(" ")
+  }
+}
+
+/// Reads the airport `icao` from the contents of an `apt.dat` file.
+///
+/// Points are projected around the airport's datum, or the middle of its
+/// runways if it has none, which becomes the airport's location.
+pub fn parse(source: &str, icao: &str) -> Result<Airport, ImportError> {
+  let rows = airport_rows(source, icao)?;
+
+  let mut datum = (None, None);
+  let mut runways: Vec<(&str, LatLon, &str, LatLon)> = Vec::new();
+  let mut nodes: HashMap<&str, LatLon> = HashMap::new();
+  let mut edges: Vec<(&str, &str, String, usize)> = Vec::new();
+  let mut gates: Vec<(String, LatLon, f32)> = Vec::new();
+  let mut frequencies: HashMap<u16, f32> = HashMap::new();
+
+  for row in rows.iter() {
+    match row.code() {
+      "100" => runways.push((
+        row.field(8)?,
+        row.lat_lon(9)?,
+        row.field(17)?,
+        row.lat_lon(18)?,
+      )),
+      "1302" => match row.field(1)? {
+        "datum_lat" => datum.0 = Some(row.number(2)?),
+        "datum_lon" => datum.1 = Some(row.number(2)?),
+        _ => {}
+      },
+      "1201" => {
+        nodes.insert(row.field(4)?, row.lat_lon(1)?);
+      }
+      // Only named taxiways can be given in a clearance.
+      "1202" if row.field(4)?.starts_with("taxiway") => {
+        let name = row.text(5);
+        if !name.is_empty() {
+          edges.push((row.field(1)?, row.field(2)?, name, row.line));
+        }
+      }
+      "1300" => {
+        gates.push((row.text(6), row.lat_lon(1)?, row.number(3)?));
+      }
+      // Legacy startup locations, from before 1300 rows.
+      "15" => gates.push((row.text(4), row.lat_lon(1)?, row.number(3)?)),
+      code => {
+        // Frequencies are in 10 kHz units for 50-56, and kHz for 1050-1056.
+        let (kind, frequency) = match code.parse::<u16>() {
+          Ok(kind @ 50..=56) => (kind - 50, row.number::<f32>(1)? / 100.0),
+          Ok(kind @ 1050..=1056) => {
+            (kind - 1050, row.number::<f32>(1)? / 1000.0)
+          }
+          _ => continue,
+        };
+        frequencies.entry(kind).or_insert(frequency);
+      }
+    }
+  }
+
+  if runways.is_empty() {
+    return Err(ImportError::NoRunways(icao.to_owned()));
+  }
+
+  let origin = match datum {
+    (Some(lat), Some(lon)) => LatLon::new(lat, lon),
+    _ => {
+      let ends = runways.iter().flat_map(|(_, a, _, b)| [a, b]);
+      let count = (runways.len() * 2) as f64;
+      let (lat, lon) =
+        ends.fold((0.0, 0.0), |(lat, lon), p| (lat + p.lat, lon + p.lon));
+      LatLon::new(lat / count, lon / count)
+    }
+  };
+
+  let mut airport = Airport::new(Intern::from(icao.to_uppercase()), Vec2::ZERO);
+  airport.location = Some(origin);
+  airport.frequencies = frequencies_from(&frequencies);
+
+  for (a_id, a, b_id, b) in runways {
+    airport.runways.extend(runway_pair(
+      a_id,
+      a.project(origin),
+      b_id,
+      b.project(origin),
+    ));
+  }
+
+  let mut segments = Vec::new();
+  for (a, b, name, line) in edges {
+    let (Some(a), Some(b)) = (nodes.get(a), nodes.get(b)) else {
+      return Err(ImportError::Invalid(line, "unknown taxi node".to_owned()));
+    };
+
+    segments.push(Taxiway::new(
+      Intern::from(name),
+      a.project(origin),
+      b.project(origin),
+    ));
+  }
+  airport.taxiways = join_segments(segments);
+
+  let gates = gates
+    .into_iter()
+    .map(|(name, pos, heading)| Gate {
+      id: Intern::from(clean_name(&name)),
+      pos: pos.project(origin),
+      heading,
+      available: true,
+    })
+    .collect();
+  airport.terminals = group_gates(gates, &airport.taxiways);
+
+  Ok(airport)
+}
+
+/// The rows of the airport `icao`, without its header.
+fn airport_rows<'a>(
+  source: &'a str,
+  icao: &str,
+) -> Result<Vec<Row<'a>>, ImportError> {
+  let mut rows = source
+    .lines()
+    .enumerate()
+    .map(|(i, line)| Row {
+      line: i + 1,
+      fields: line.split_whitespace().collect(),
+    })
+    .filter(|row| !row.fields.is_empty());
+
+  // Headers are "1 elevation 0 0 ICAO name".
+  rows
+    .by_ref()
+    .find(|row| {
+      AIRPORT_HEADERS.contains(&row.code())
+        && row
+          .fields
+          .get(4)
+          .is_some_and(|id| id.eq_ignore_ascii_case(icao))
+    })
+    .ok_or_else(|| ImportError::NotFound(icao.to_owned()))?;
+
+  Ok(
+    rows
+      .take_while(|row| {
+        !AIRPORT_HEADERS.contains(&row.code()) && row.code() != END_OF_FILE
+      })
+      .collect(),
+  )
+}
+
+/// Fills in any positions the airport doesn't staff from the ones it does.
+fn frequencies_from(found: &HashMap<u16, f32>) -> Frequencies {
+  // Offsets from 50 and 1050.
+  const UNICOM: u16 = 1;
+  const CLEARANCE: u16 = 2;
+  const GROUND: u16 = 3;
+  const TOWER: u16 = 4;
+  const APPROACH: u16 = 5;
+  const DEPARTURE: u16 = 6;
+
+  let defaults = Frequencies::default();
+  let tower = found
+    .get(&TOWER)
+    .or(found.get(&UNICOM))
+    .copied()
+    .unwrap_or(defaults.tower);
+  let approach = found.get(&APPROACH).copied().unwrap_or(tower);
+
+  Frequencies {
+    approach,
+    departure: found.get(&DEPARTURE).copied().unwrap_or(approach),
+    tower,
+    ground: found.get(&GROUND).copied().unwrap_or(tower),
+    center: approach,
+    clearance: found.get(&CLEARANCE).copied(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const APT_DAT: &str = "I
+1200 Version - data cycle 2024.01
+
+1 13 0 0 KTST Test Field
+1302 datum_lat 37.000000
+1302 datum_lon -122.000000
+100 45.72 1 0 0.25 0 2 1 18 37.01000000 -122.00000000 0 0 3 0 0 0 36 36.99000000 -122.00000000 0 0 3 0 0 0
+1201 37.00900000 -121.99700000 both 0 A_north
+1201 37.00000000 -121.99700000 both 1 A_middle
+1201 37.00000000 -121.99600000 both 2 A_apron
+1201 36.99100000 -121.99700000 both 3 A_south
+1201 37.00900000 -122.00100000 both 4 B_west
+1202 0 1 twoway taxiway_E A
+1202 1 3 twoway taxiway_E A
+1202 1 2 twoway taxiway_E
+1202 0 4 twoway taxiway_E B
+1300 37.00000000 -121.99500000 270.00 gate jets|turboprops Gate A 1
+1300 37.00050000 -121.99500000 270.00 gate jets|turboprops Gate A 2
+1054 118700 TOWER
+1053 121900 GROUND
+53 12180 GROUND 2
+1 20 0 0 KOTH Other Field
+100 45.72 1 0 0.25 0 2 1 09 37.5 -122.5 0 0 3 0 0 0 27 37.5 -122.4 0 0 3 0 0 0
+99
+";
+
+  #[test]
+  fn test_parse() {
+    let airport = parse(APT_DAT, "ktst").unwrap();
+    assert_eq!(airport.id.as_str(), "KTST");
+    assert_eq!(airport.location, Some(LatLon::new(37.0, -122.0)));
+
+    // Both ends of the runway.
+    let ids: Vec<_> = airport.runways.iter().map(|r| r.id.as_str()).collect();
+    assert_eq!(ids, ["18", "36"]);
+    assert!((airport.runways[0].heading - 180.0).abs() < 0.1);
+    assert!((airport.runways[1].heading - 0.0).abs() < 0.1);
+
+    // A's two segments are joined, and the unnamed connector is dropped.
+    let ids: Vec<_> = airport.taxiways.iter().map(|t| t.id.as_str()).collect();
+    assert_eq!(ids, ["A", "B"]);
+
+    assert_eq!(airport.terminals.len(), 1);
+    let gates: Vec<_> = airport.terminals[0]
+      .gates
+      .iter()
+      .map(|g| g.id.as_str())
+      .collect();
+    assert!(gates.contains(&"A1") && gates.contains(&"A2"));
+
+    // The first ground frequency wins, and approach falls back to tower.
+    assert_eq!(airport.frequencies.tower, 118.7);
+    assert_eq!(airport.frequencies.ground, 121.9);
+    assert_eq!(airport.frequencies.approach, 118.7);
+    assert_eq!(airport.frequencies.clearance, None);
+  }
+
+  #[test]
+  fn test_parse_missing() {
+    assert!(matches!(
+      parse(APT_DAT, "KXXX"),
+      Err(ImportError::NotFound(_))
+    ));
+  }
+}
@@ -2,6 +2,9 @@ use glam::Vec2;
 use nannou::geom;
 
 pub mod draw;
+pub mod edit;
+pub mod import;
+pub mod save;
 
 pub fn glam_to_geom(v: Vec2) -> geom::Vec2 {
   geom::Vec2::new(v.x, v.y)
@@ -19,3 +22,9 @@ pub fn unscale_point(point: Vec2, offset: Vec2, scale: f32) -> Vec2 {
 pub fn midpoint(a: Vec2, b: Vec2) -> Vec2 {
   (a + b) * 0.5
 }
+
+/// The closest point to `point` on the line segment from `a` to `b`.
+pub fn closest_point_on_segment(point: Vec2, a: Vec2, b: Vec2) -> Vec2 {
+  let t = (point - a).dot(b - a) / a.distance_squared(b).max(f32::EPSILON);
+  a.lerp(b, t.clamp(0.0, 1.0))
+}
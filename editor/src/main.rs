@@ -1,12 +1,17 @@
 pub mod viewer;
 
-use std::{path::PathBuf, sync::mpsc, thread};
+use std::{
+  path::{Path, PathBuf},
+  sync::mpsc,
+  thread,
+};
 
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use notify::{Event, RecursiveMode, Watcher};
 
+use editor::{import, save};
 use engine::{
-  compile::{compile_airport, setup_lua},
+  compile::{add_module_path, compile_airport, load_airport, setup_lua},
   entities::airport::Airport,
 };
 use viewer::start_app;
@@ -14,9 +19,17 @@ use viewer::start_app;
 /// View and edit an Airwave world file
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
+#[command(
+  args_conflicts_with_subcommands = true,
+  subcommand_negates_reqs = true
+)]
 struct Cli {
+  #[command(subcommand)]
+  command: Option<Command>,
+
   /// The file to load
-  path: PathBuf,
+  #[arg(required = true)]
+  path: Option<PathBuf>,
 
   /// Watch for changes
   #[arg(short, long)]
@@ -27,28 +40,138 @@ struct Cli {
   view: bool,
 }
 
+#[derive(Subcommand, Debug)]
+enum Command {
+  /// Import a real airport from an X-Plane apt.dat file or an OpenStreetMap
+  /// extract
+  Import {
+    /// The apt.dat, .osm or .osm.pbf file to read
+    input: PathBuf,
+
+    /// The ICAO code of the airport
+    icao: String,
+
+    /// Where to write the airport, as .lua or .json
+    output: PathBuf,
+  },
+  /// Check an airport for problems
+  Validate {
+    /// The airport script to check
+    path: PathBuf,
+  },
+}
+
+/// Imports an airport and writes it out, returning the exit code.
+fn import(input: &Path, icao: &str, output: &Path) -> i32 {
+  let airport = match import::read_airport(input, icao) {
+    Ok(airport) => airport,
+    Err(e) => {
+      eprintln!("Error importing {icao}: {e}");
+      return 1;
+    }
+  };
+
+  if let Err(e) = save::save_airport(&airport, output) {
+    eprintln!("Error writing {}: {e}", output.display());
+    return 1;
+  }
+
+  println!(
+    "Imported {} with {} runways, {} taxiways and {} terminals",
+    airport.id,
+    airport.runways.len(),
+    airport.taxiways.len(),
+    airport.terminals.len()
+  );
+
+  0
+}
+
+/// Prints any problems with an airport, returning the exit code.
+fn validate(path: &Path) -> i32 {
+  let lua = setup_lua();
+  if let Some(dir) = path.parent() {
+    add_module_path(&lua, dir).unwrap();
+  }
+
+  let mut airport = match load_airport(&lua, path) {
+    Ok(airport) => airport,
+    Err(e) => {
+      eprintln!("Error compiling: {e}");
+      return 1;
+    }
+  };
+  airport.extend_all();
+  airport.calculate_waypoints();
+
+  let issues = airport.validate();
+  for issue in issues.iter() {
+    if issue.is_error() {
+      eprintln!("error: {issue}");
+    } else {
+      eprintln!("warning: {issue}");
+    }
+  }
+
+  if issues.is_empty() {
+    println!("No problems found in airport \"{}\"", airport.id);
+  }
+
+  if issues.iter().any(|i| i.is_error()) {
+    1
+  } else {
+    0
+  }
+}
+
 pub fn main() -> mlua::Result<()> {
+  let args = Cli::parse();
+  match &args.command {
+    Some(Command::Import {
+      input,
+      icao,
+      output,
+    }) => std::process::exit(import(input, icao, output)),
+    Some(Command::Validate { path }) => std::process::exit(validate(path)),
+    None => {}
+  }
+
   let (sender, receiver) = mpsc::channel::<Airport>();
 
   let thread = thread::spawn(|| {
     let args = Cli::parse();
-    let lua = setup_lua();
+    let path = args.path.unwrap();
+    let dir = path.parent().map(|d| d.to_owned());
+    // Each compile gets a fresh Lua, so that changed modules are required
+    // again rather than coming from `package.loaded`.
+    let new_lua = || {
+      let lua = setup_lua();
+      if let Some(dir) = &dir {
+        add_module_path(&lua, dir).unwrap();
+      }
+      lua
+    };
 
     if args.watch {
       let (tx, rx) = mpsc::channel::<notify::Result<Event>>();
 
-      compile_airport(&lua, &args.path, args.view.then(|| sender.clone()));
+      compile_airport(&new_lua(), &path, args.view.then(|| sender.clone()));
 
       let mut watcher = notify::recommended_watcher(tx).unwrap();
-      watcher.watch(&args.path, RecursiveMode::Recursive).unwrap();
+      watcher.watch(&path, RecursiveMode::Recursive).unwrap();
+      // Shared modules are required from `lib`, next to the script.
+      let modules = dir.as_ref().map(|d| d.join("lib")).filter(|d| d.is_dir());
+      if let Some(modules) = &modules {
+        watcher.watch(modules, RecursiveMode::Recursive).unwrap();
+      }
       // Block forever, printing out events as they come in
       for res in rx {
         match res {
           Ok(event) => {
             if matches!(event.kind, notify::EventKind::Modify(..)) {
               compile_airport(
-                &lua,
-                &args.path,
+                &new_lua(),
+                &path,
                 args.view.then(|| sender.clone()),
               );
             }
@@ -57,13 +180,13 @@ pub fn main() -> mlua::Result<()> {
         }
       }
     } else {
-      compile_airport(&lua, &args.path, args.view.then_some(sender));
+      compile_airport(&new_lua(), &path, args.view.then_some(sender));
     }
   });
 
   let args = Cli::parse();
   if args.view {
-    start_app(receiver);
+    start_app(receiver, args.path.unwrap());
   } else {
     thread.join().unwrap();
   }
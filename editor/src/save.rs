@@ -0,0 +1,169 @@
+//! Writes airports back out as JSON or Lua.
+//!
+//! Lua scripts can do more than list an airport, so the editor only owns a
+//! generated section of them, between two marker comments:
+//!
+//! ```lua
+//! -- editor: begin generated
+//! local generated = airport({ ... })
+//! -- editor: end generated
+//!
+//! return generated
+//! ```
+//!
+//! Saving replaces what is between the markers and leaves the rest of the
+//! script alone. A script without the markers is backed up to `.lua.bak`
+//! before it is replaced by one that has them.
+
+use std::{fs, io, path::Path};
+
+use engine::entities::airport::Airport;
+use serde_json::Value;
+
+pub const GENERATED_BEGIN: &str = "-- editor: begin generated";
+pub const GENERATED_END: &str = "-- editor: end generated";
+
+/// Writes an airport as JSON, or into the generated section of a Lua script
+/// if the path ends in `.lua`.
+pub fn save_airport(airport: &Airport, path: &Path) -> io::Result<()> {
+  if path.extension().is_none_or(|e| e != "lua") {
+    let json =
+      serde_json::to_string_pretty(airport).map_err(io::Error::other)?;
+    return fs::write(path, json);
+  }
+
+  let script = match fs::read_to_string(path) {
+    Ok(script) => Some(script),
+    Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+    Err(e) => return Err(e),
+  };
+  if let Some(script) =
+    script.as_deref().filter(|s| generated_section(s).is_none())
+  {
+    fs::write(path.with_extension("lua.bak"), script)?;
+  }
+
+  fs::write(path, with_generated_section(script.as_deref(), airport))
+}
+
+/// The byte range between the markers of a script's generated section.
+fn generated_section(script: &str) -> Option<core::ops::Range<usize>> {
+  let begin = script.find(GENERATED_BEGIN)? + GENERATED_BEGIN.len();
+  let end = begin + script[begin..].find(GENERATED_END)?;
+
+  Some(begin..end)
+}
+
+/// Replaces the generated section of a script with an airport, or writes a new
+/// script around one if there is no script or it has no section.
+pub fn with_generated_section(
+  script: Option<&str>,
+  airport: &Airport,
+) -> String {
+  let section = format!("\nlocal generated = airport({})\n", to_lua(airport));
+  match script.and_then(|s| Some((s, generated_section(s)?))) {
+    Some((script, range)) => {
+      let mut script = script.to_owned();
+      script.replace_range(range, &section);
+      script
+    }
+    None => {
+      format!("{GENERATED_BEGIN}{section}{GENERATED_END}\n\nreturn generated\n")
+    }
+  }
+}
+
+/// Writes an airport as a Lua table.
+pub fn to_lua(airport: &Airport) -> String {
+  let value = serde_json::to_value(airport).unwrap_or_default();
+  let mut lua = String::new();
+  write_lua(&value, 0, &mut lua);
+
+  lua
+}
+
+fn write_lua(value: &Value, indent: usize, out: &mut String) {
+  let pad = "  ".repeat(indent + 1);
+  match value {
+    Value::Null => out.push_str("nil"),
+    Value::Bool(b) => out.push_str(&b.to_string()),
+    Value::Number(n) => out.push_str(&n.to_string()),
+    Value::String(s) => {
+      out.push('"');
+      for c in s.chars() {
+        match c {
+          '"' => out.push_str("\\\""),
+          '\\' => out.push_str("\\\\"),
+          '\n' => out.push_str("\\n"),
+          c => out.push(c),
+        }
+      }
+      out.push('"');
+    }
+    // Points stay on one line.
+    Value::Array(items) if items.iter().all(Value::is_number) => {
+      out.push_str("{ ");
+      for (i, item) in items.iter().enumerate() {
+        if i > 0 {
+          out.push_str(", ");
+        }
+        write_lua(item, indent, out);
+      }
+      out.push_str(" }");
+    }
+    Value::Array(items) => {
+      out.push_str("{\n");
+      for item in items {
+        out.push_str(&pad);
+        write_lua(item, indent + 1, out);
+        out.push_str(",\n");
+      }
+      out.push_str(&"  ".repeat(indent));
+      out.push('}');
+    }
+    Value::Object(fields) => {
+      out.push_str("{\n");
+      for (key, item) in fields.iter().filter(|(_, v)| !v.is_null()) {
+        out.push_str(&pad);
+        out.push_str(key);
+        out.push_str(" = ");
+        write_lua(item, indent + 1, out);
+        out.push_str(",\n");
+      }
+      out.push_str(&"  ".repeat(indent));
+      out.push('}');
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use glam::Vec2;
+  use internment::Intern;
+
+  use super::*;
+
+  #[test]
+  fn test_with_generated_section() {
+    let airport = Airport::new(Intern::from_ref("KTST"), Vec2::ZERO);
+
+    let new = with_generated_section(None, &airport);
+    assert!(new.starts_with(GENERATED_BEGIN));
+    assert!(new.contains("local generated = airport({\n"));
+    assert!(new.contains("id = \"KTST\","));
+    assert!(new.ends_with("return generated\n"));
+
+    // Only the generated section is replaced.
+    let script = format!(
+      "local x = 1\n{GENERATED_BEGIN}\nold\n{GENERATED_END}\nreturn x\n"
+    );
+    let saved = with_generated_section(Some(&script), &airport);
+    assert!(saved.starts_with("local x = 1\n"));
+    assert!(saved.ends_with(&format!("{GENERATED_END}\nreturn x\n")));
+    assert!(!saved.contains("\nold\n"));
+    assert!(saved.contains("KTST"));
+
+    // A script without markers is replaced.
+    assert_eq!(with_generated_section(Some("return 1\n"), &airport), new);
+  }
+}
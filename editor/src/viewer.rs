@@ -1,15 +1,31 @@
-use std::sync::{Mutex, OnceLock, mpsc};
+use std::{
+  path::PathBuf,
+  sync::{Mutex, OnceLock, mpsc},
+};
 
-use editor::draw::Draw;
-use engine::entities::airport::Airport;
+use editor::{
+  draw::Draw,
+  edit::{self, Handle, Item},
+  glam_to_geom, save, scale_point, unscale_point,
+};
+use engine::{compile::compiled_path, entities::airport::Airport};
 use nannou::prelude::*;
-use nannou_egui::Egui;
+use nannou_egui::{
+  Egui,
+  egui::{self, Id, Widget},
+};
+
+/// How close, in pixels, the cursor must be to something to pick or snap to
+/// it.
+const PICK_DISTANCE: f32 = 10.0;
 
 static GLOBAL_CHANNEL: OnceLock<Mutex<mpsc::Receiver<Airport>>> =
   OnceLock::new();
+static GLOBAL_PATH: OnceLock<PathBuf> = OnceLock::new();
 
-pub fn start_app(channel: mpsc::Receiver<Airport>) {
+pub fn start_app(channel: mpsc::Receiver<Airport>, path: PathBuf) {
   GLOBAL_CHANNEL.set(Mutex::new(channel)).unwrap();
+  GLOBAL_PATH.set(path).unwrap();
 
   nannou::app(model).update(update).run();
 }
@@ -20,12 +36,54 @@ struct HeldKeys {
   alt: bool,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Tool {
+  Select,
+  Runway,
+  Taxiway,
+  Terminal,
+  Gate,
+}
+
+impl Tool {
+  const ALL: [Tool; 5] = [
+    Tool::Select,
+    Tool::Runway,
+    Tool::Taxiway,
+    Tool::Terminal,
+    Tool::Gate,
+  ];
+}
+
+/// Part of an item being dragged, and where the cursor last was.
+struct Grab {
+  item: Item,
+  handle: Handle,
+  last: glam::Vec2,
+}
+
 struct Model {
   egui: Egui,
+  path: PathBuf,
+  /// The airport as it is written, which is what gets edited.
+  source: Airport,
+  /// The airport as it is played, which is what gets drawn.
   airport: Airport,
+  changed: bool,
+
+  tool: Tool,
+  selected: Option<Item>,
+  name: String,
+  grab: Option<Grab>,
+  /// Where the item being created starts.
+  creating: Option<glam::Vec2>,
+  /// The cursor in world space, after snapping.
+  cursor: glam::Vec2,
+  status: String,
 
   is_mouse_down: bool,
   is_over_ui: bool,
+  is_typing: bool,
 
   drag_anchor: Option<glam::Vec2>,
   old_shift_pos: glam::Vec2,
@@ -35,12 +93,47 @@ struct Model {
   held_keys: HeldKeys,
 }
 
+impl Model {
+  fn select(&mut self, item: Option<Item>) {
+    self.selected = item;
+    self.name = item
+      .and_then(|i| i.id(&self.source))
+      .map(|id| id.to_string())
+      .unwrap_or_default();
+  }
+
+  fn remove_selected(&mut self) {
+    if let Some(item) = self.selected.take() {
+      item.remove(&mut self.source);
+      self.name.clear();
+      self.changed = true;
+    }
+  }
+
+  fn save(&mut self, path: PathBuf) {
+    self.status = match save::save_airport(&self.source, &path) {
+      Ok(()) => format!("Saved to {}", path.display()),
+      Err(e) => format!("Error saving: {e}"),
+    };
+  }
+
+  /// The world position of the cursor, snapped unless Alt is held.
+  fn snap(&self, pos: glam::Vec2, ignore: Option<Item>) -> glam::Vec2 {
+    if self.held_keys.alt {
+      pos
+    } else {
+      edit::snap(&self.source, pos, PICK_DISTANCE / self.scale, ignore)
+    }
+  }
+}
+
 fn model(app: &App) -> Model {
   // Create window
   let window_id = app
     .new_window()
     .view(view)
     .raw_event(raw_window_event)
+    .key_pressed(key_pressed)
     .build()
     .unwrap();
   let window = app.window(window_id).unwrap();
@@ -48,10 +141,22 @@ fn model(app: &App) -> Model {
 
   Model {
     egui,
+    path: GLOBAL_PATH.get().cloned().unwrap_or_default(),
+    source: Airport::default(),
     airport: Airport::default(),
+    changed: false,
+
+    tool: Tool::Select,
+    selected: None,
+    name: String::new(),
+    grab: None,
+    creating: None,
+    cursor: glam::Vec2::default(),
+    status: String::new(),
 
     is_mouse_down: false,
     is_over_ui: false,
+    is_typing: false,
 
     drag_anchor: None,
     old_shift_pos: glam::Vec2::default(),
@@ -67,18 +172,110 @@ fn model(app: &App) -> Model {
 }
 
 fn update(_app: &App, model: &mut Model, update: Update) {
-  model.egui.set_elapsed_time(update.since_start);
-  let _ = model.egui.begin_frame();
-
   if let Some(mutex) = GLOBAL_CHANNEL.get() {
     if let Ok(chan) = mutex.lock() {
       while let Ok(airport) = chan.try_recv() {
-        model.airport = airport;
-        model.airport.extend_all();
-        model.airport.calculate_waypoints();
+        model.source = airport;
+        model.changed = true;
       }
     }
   }
+
+  // Forget the selection if it no longer exists, such as after a reload.
+  if model
+    .selected
+    .is_some_and(|i| i.id(&model.source).is_none())
+  {
+    model.select(None);
+  }
+
+  model.egui.set_elapsed_time(update.since_start);
+  let ctx = model.egui.begin_frame();
+
+  let mut remove = false;
+  let mut save = None;
+  let side_panel =
+    egui::panel::SidePanel::new(egui::panel::Side::Left, Id::new("side_panel"))
+      .resizable(false)
+      .min_width(200.0)
+      .show(&ctx, |ui| {
+        ui.horizontal_wrapped(|ui| {
+          for tool in Tool::ALL {
+            ui.selectable_value(&mut model.tool, tool, format!("{tool:?}"));
+          }
+        });
+        ui.label("Hold Alt to place without snapping.");
+        ui.separator();
+
+        if let Some(item) = model.selected {
+          ui.label(item.kind());
+          if ui.text_edit_singleline(&mut model.name).changed()
+            && !model.name.is_empty()
+          {
+            item.rename(&mut model.source, &model.name);
+            model.changed = true;
+          }
+
+          let source = &mut model.source;
+          let value = match item {
+            Item::Runway(i) => {
+              let runway = &mut source.runways[i];
+              ui.horizontal(|ui| {
+                ui.label("Length");
+                let length = egui::DragValue::new(&mut runway.length)
+                  .clamp_range(0.0..=f32::MAX)
+                  .ui(ui);
+                model.changed |= length.changed();
+              });
+              Some((&mut runway.heading, "Heading"))
+            }
+            Item::Taxiway(i) => {
+              Some((&mut source.taxiways[i].radius, "Radius"))
+            }
+            Item::Gate(t, g) => {
+              Some((&mut source.terminals[t].gates[g].heading, "Heading"))
+            }
+            Item::Terminal(_) => None,
+          };
+          if let Some((value, label)) = value {
+            ui.horizontal(|ui| {
+              ui.label(label);
+              model.changed |= egui::DragValue::new(value).ui(ui).changed();
+            });
+          }
+
+          remove = ui.button("Delete").clicked();
+          ui.separator();
+        }
+
+        ui.horizontal(|ui| {
+          if ui.button("Save").clicked() {
+            save = Some(model.path.clone());
+          }
+          if ui.button("Export JSON").clicked() {
+            save = Some(compiled_path(&model.path));
+          }
+        });
+        ui.label(&model.status);
+      });
+
+  model.is_over_ui = side_panel.response.hovered();
+  model.is_typing = ctx.wants_keyboard_input();
+  drop(ctx);
+
+  if remove {
+    model.remove_selected();
+  }
+  if let Some(path) = save {
+    model.save(path);
+  }
+
+  if model.changed {
+    model.airport = model.source.clone();
+    model.airport.extend_all();
+    model.airport.calculate_waypoints();
+    model.changed = false;
+  }
 }
 
 fn real_mouse_pos(app: &App, model: &Model) -> glam::Vec2 {
@@ -91,6 +288,107 @@ fn real_mouse_pos(app: &App, model: &Model) -> glam::Vec2 {
   glam::Vec2::new(pos.x - half_size.x, -pos.y + half_size.y)
 }
 
+fn key_pressed(_app: &App, model: &mut Model, key: Key) {
+  if model.is_typing {
+    return;
+  }
+
+  match key {
+    Key::Delete | Key::Back => model.remove_selected(),
+    Key::Escape => {
+      model.select(None);
+      model.creating = None;
+      model.tool = Tool::Select;
+    }
+    Key::S if model.held_keys.ctrl => model.save(model.path.clone()),
+    _ => {}
+  }
+}
+
+fn mouse_pressed(model: &mut Model, screen_pos: glam::Vec2) {
+  let pos = unscale_point(screen_pos, model.shift_pos, model.scale);
+  let snapped = model.snap(pos, None);
+
+  match model.tool {
+    Tool::Select => {
+      let picked = edit::pick(&model.source, pos, PICK_DISTANCE / model.scale);
+      model.select(picked.map(|(item, _)| item));
+      if let Some((item, handle)) = picked {
+        model.grab = Some(Grab {
+          item,
+          handle,
+          last: pos,
+        });
+      } else {
+        model.is_mouse_down = true;
+        model.drag_anchor = Some(screen_pos);
+        model.old_shift_pos = model.shift_pos;
+      }
+    }
+    Tool::Gate => {
+      let gate = edit::add_gate(&mut model.source, snapped);
+      if gate.is_none() {
+        model.status = "Gates must be added near a terminal".to_owned();
+      }
+      model.select(gate);
+      model.changed = true;
+    }
+    Tool::Runway | Tool::Taxiway | Tool::Terminal => {
+      model.creating = Some(snapped);
+    }
+  }
+}
+
+fn mouse_released(model: &mut Model) {
+  model.is_mouse_down = false;
+  model.drag_anchor = None;
+  model.grab = None;
+
+  let Some(start) = model.creating.take() else {
+    return;
+  };
+  // Ignore clicks that weren't meant to draw anything.
+  let end = model.cursor;
+  if start.distance(end) < PICK_DISTANCE / model.scale {
+    return;
+  }
+
+  let item = match model.tool {
+    Tool::Runway => edit::add_runway(&mut model.source, start, end),
+    Tool::Taxiway => edit::add_taxiway(&mut model.source, start, end),
+    Tool::Terminal => edit::add_terminal(&mut model.source, start, end),
+    Tool::Select | Tool::Gate => return,
+  };
+  model.select(Some(item));
+  model.changed = true;
+}
+
+fn mouse_moved(model: &mut Model, screen_pos: glam::Vec2) {
+  let pos = unscale_point(screen_pos, model.shift_pos, model.scale);
+
+  if let Some(grab) = &mut model.grab {
+    let (item, handle) = (grab.item, grab.handle);
+    if handle == Handle::Body {
+      item.translate(&mut model.source, pos - grab.last);
+      grab.last = pos;
+      model.cursor = pos;
+    } else {
+      model.cursor = model.snap(pos, Some(item));
+      item.drag(&mut model.source, handle, model.cursor);
+    }
+    model.changed = true;
+  } else {
+    model.cursor = model.snap(pos, None);
+  }
+
+  if model.is_mouse_down {
+    if let Some(drag_anchor) = model.drag_anchor {
+      model.shift_pos =
+        model.old_shift_pos + (screen_pos - drag_anchor) / model.scale;
+    }
+  }
+}
+
 fn raw_window_event(
   app: &App,
   model: &mut Model,
@@ -128,11 +426,8 @@ fn raw_window_event(
   } = event
   {
     if !model.is_over_ui {
-      model.is_mouse_down = true;
-
       let pos = real_mouse_pos(app, model);
-      model.drag_anchor = Some(pos);
-      model.old_shift_pos = model.shift_pos;
+      mouse_pressed(model, pos);
     }
   } else if let nannou::winit::event::WindowEvent::MouseInput {
     state: nannou::winit::event::ElementState::Released,
@@ -140,28 +435,78 @@ fn raw_window_event(
     ..
   } = event
   {
-    model.is_mouse_down = false;
-    model.drag_anchor = None;
+    mouse_released(model);
   }
 
   // Detect mouse move
   if let nannou::winit::event::WindowEvent::CursorMoved { .. } = event {
     let pos = real_mouse_pos(app, model);
-    if model.is_mouse_down {
-      if let Some(drag_anchor) = model.drag_anchor {
-        model.shift_pos =
-          model.old_shift_pos + (pos - drag_anchor) / model.scale;
-      }
-    }
+    mouse_moved(model, pos);
   }
 }
 
+fn draw_line(
+  draw: &nannou::Draw,
+  a: glam::Vec2,
+  b: glam::Vec2,
+  model: &Model,
+  color: Rgb<u8>,
+) {
+  draw
+    .line()
+    .start(glam_to_geom(scale_point(a, model.shift_pos, model.scale)))
+    .end(glam_to_geom(scale_point(b, model.shift_pos, model.scale)))
+    .weight(2.0)
+    .color(color);
+}
+
+fn draw_point(draw: &nannou::Draw, point: glam::Vec2, model: &Model) {
+  let point = scale_point(point, model.shift_pos, model.scale);
+  draw
+    .rect()
+    .xy(glam_to_geom(point))
+    .w_h(8.0, 8.0)
+    .color(WHITE)
+    .stroke_weight(1.0)
+    .stroke(BLACK);
+}
+
 fn view(app: &App, model: &Model, frame: Frame) {
   let draw = app.draw();
   draw.background().color(BLACK);
 
   model.airport.draw(&draw, model.scale, model.shift_pos);
 
+  if let Some(item) = model.selected {
+    for line in item.lines(&model.source) {
+      draw_line(&draw, line.0, line.1, model, CYAN);
+    }
+    for (_, point) in item.handles(&model.source) {
+      draw_point(&draw, point, model);
+    }
+  }
+
+  if let Some(start) = model.creating {
+    let end = model.cursor;
+    if model.tool == Tool::Terminal {
+      let corners = [
+        start,
+        glam::Vec2::new(end.x, start.y),
+        end,
+        glam::Vec2::new(start.x, end.y),
+      ];
+      for i in 0..4 {
+        draw_line(&draw, corners[i], corners[(i + 1) % 4], model, CYAN);
+      }
+    } else {
+      draw_line(&draw, start, end, model, CYAN);
+    }
+  }
+
+  if model.tool != Tool::Select || model.grab.is_some() {
+    draw_point(&draw, model.cursor, model);
+  }
+
   draw.to_frame(app, &frame).unwrap();
   model.egui.draw_to_frame(&frame).unwrap();
 }
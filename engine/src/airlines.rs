@@ -0,0 +1,184 @@
+use std::{collections::HashMap, path::PathBuf, sync::LazyLock};
+
+use internment::Intern;
+use serde::{Deserialize, Serialize};
+use turborand::{TurboRand, rng::Rng};
+
+use crate::{assets::assets_root, entities::aircraft::AircraftKind};
+
+/// The registry that ships with the engine, used if no asset file is found.
+const DEFAULT_REGISTRY: &str = include_str!("../../assets/airlines.json");
+
+static REGISTRY: LazyLock<Registry> = LazyLock::new(Registry::load);
+
+pub fn airline_asset_path() -> PathBuf {
+  assets_root().join("airlines.json")
+}
+
+/// The airline registry, loaded from the asset file on first use.
+pub fn registry() -> &'static Registry {
+  &REGISTRY
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Airline {
+  /// ICAO airline designator, such as "AAL".
+  pub icao: String,
+  /// Radio telephony designator, such as "American".
+  pub telephony: String,
+  /// Airports the airline is based at, by ID.
+  #[serde(default)]
+  pub bases: Vec<String>,
+  /// Relative share of each aircraft type in the fleet.
+  pub fleet: HashMap<AircraftKind, f32>,
+  /// Inclusive ranges of flight numbers.
+  pub flight_numbers: Vec<(u16, u16)>,
+}
+
+/// Non-airline traffic, identified by its tail number.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct GeneralAviation {
+  /// Chance that a generated callsign is a tail number.
+  pub chance: f64,
+  /// Relative share of each aircraft type in the fleet.
+  pub fleet: HashMap<AircraftKind, f32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct Registry {
+  pub airlines: Vec<Airline>,
+  #[serde(default)]
+  pub general_aviation: GeneralAviation,
+}
+
+fn sample_weighted<'a, T>(
+  rng: &mut Rng,
+  items: impl Iterator<Item = (&'a T, f32)> + Clone,
+) -> Option<&'a T> {
+  let total: f32 = items.clone().map(|(_, w)| w.max(0.0)).sum();
+  if total <= 0.0 {
+    return None;
+  }
+
+  let mut pick = rng.f32() * total;
+  let mut last = None;
+  for (item, weight) in items {
+    pick -= weight.max(0.0);
+    last = Some(item);
+    if pick <= 0.0 {
+      break;
+    }
+  }
+
+  last
+}
+
+fn sample_fleet(
+  rng: &mut Rng,
+  fleet: &HashMap<AircraftKind, f32>,
+) -> AircraftKind {
+  // Sort so that the same seed always picks the same type.
+  let mut fleet: Vec<_> = fleet.iter().map(|(k, w)| (k, *w)).collect();
+  fleet.sort_by_key(|(k, _)| **k as u8);
+
+  sample_weighted(rng, fleet.into_iter())
+    .copied()
+    .unwrap_or_default()
+}
+
+impl Registry {
+  fn load() -> Self {
+    let (source, contents) = match std::fs::read_to_string(airline_asset_path())
+    {
+      Ok(contents) => ("assets", contents),
+      Err(_) => ("built-in", DEFAULT_REGISTRY.to_owned()),
+    };
+
+    match serde_json::from_str::<Self>(&contents) {
+      Ok(registry) => {
+        tracing::info!(
+          "Loaded {} airlines from {source} registry",
+          registry.airlines.len()
+        );
+        registry
+      }
+      Err(e) => {
+        tracing::error!("Failed to read {source} airline registry: {e:?}");
+        serde_json::from_str(DEFAULT_REGISTRY).unwrap_or_default()
+      }
+    }
+  }
+
+  pub fn airline(&self, icao: &str) -> Option<&Airline> {
+    self.airlines.iter().find(|a| a.icao == icao)
+  }
+
+  /// Describes the known callsigns for prompts.
+  pub fn describe(&self) -> String {
+    let airlines = self
+      .airlines
+      .iter()
+      .map(|a| format!("{} ({})", a.icao, a.telephony))
+      .collect::<Vec<_>>()
+      .join(", ");
+
+    format!(
+      "Available Callsigns: {airlines}. General aviation aircraft use their \
+       tail number as their callsign, such as N123AB (November One Two Three \
+       Alfa Bravo)."
+    )
+  }
+
+  /// Generates a callsign and aircraft type. Airlines based at `base` are
+  /// more likely to be picked.
+  pub fn random_callsign(
+    &self,
+    rng: &mut Rng,
+    base: Option<Intern<String>>,
+  ) -> (String, AircraftKind) {
+    if rng.chance(self.general_aviation.chance) {
+      return (
+        random_tail_number(rng),
+        sample_fleet(rng, &self.general_aviation.fleet),
+      );
+    }
+
+    let airline = sample_weighted(
+      rng,
+      self.airlines.iter().map(|a| {
+        let based = base.is_some_and(|b| a.bases.iter().any(|id| **b == *id));
+        (a, if based { 3.0 } else { 1.0 })
+      }),
+    );
+    let Some(airline) = airline else {
+      return (random_tail_number(rng), AircraftKind::default());
+    };
+
+    let number = rng
+      .sample(&airline.flight_numbers)
+      .map(|(start, end)| rng.u16(*start..=*end))
+      .unwrap_or_else(|| rng.u16(1..=9999));
+
+    (
+      format!("{}{number}", airline.icao),
+      sample_fleet(rng, &airline.fleet),
+    )
+  }
+}
+
+/// Generates a US civil registration, such as "N123AB".
+pub fn random_tail_number(rng: &mut Rng) -> String {
+  let letters = b"ABCDEFGHJKLMNPQRSTUVWXYZ";
+  let number = rng.u16(1..=999);
+  let suffix: String = (0..2)
+    .filter_map(|_| rng.sample(letters).map(|c| *c as char))
+    .collect();
+
+  format!("N{number}{suffix}")
+}
+
+/// Whether a callsign is a US civil registration rather than a flight.
+pub fn is_tail_number(callsign: &str) -> bool {
+  let mut chars = callsign.chars();
+  chars.next() == Some('N') && chars.next().is_some_and(|c| c.is_ascii_digit())
+}
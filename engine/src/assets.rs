@@ -1,76 +1,153 @@
-use std::{collections::HashMap, fs, path::Path};
+use std::{
+  collections::HashMap,
+  fs, io,
+  path::{Path, PathBuf},
+  sync::OnceLock,
+  time::SystemTime,
+};
+
+use thiserror::Error;
 
 use crate::{
-  compile::{setup_lua, try_compile_airport},
+  compile::{
+    add_module_path, compiled_path, setup_lua, try_compile_airport,
+    try_compile_template,
+  },
+  economy::Template,
   entities::airport::Airport,
   geometry::Translate,
 };
 
-pub fn airport_asset_path() -> &'static Path {
-  Path::new("assets/airports")
+static ASSETS_ROOT: OnceLock<PathBuf> = OnceLock::new();
+
+/// Sets the directory that all assets are read from. This can only be done
+/// once, before any assets are read.
+pub fn set_assets_root(root: PathBuf) -> Result<(), AssetError> {
+  ASSETS_ROOT.set(root).map_err(AssetError::RootAlreadySet)
+}
+
+/// The directory that all assets are read from, `assets` in the working
+/// directory if it was never set.
+pub fn assets_root() -> &'static Path {
+  ASSETS_ROOT.get_or_init(|| PathBuf::from("assets"))
+}
+
+pub fn airport_asset_path(root: &Path) -> PathBuf {
+  root.join("airports")
+}
+
+pub fn template_asset_path(root: &Path) -> PathBuf {
+  root.join("templates")
+}
+
+/// The directory, within the airports directory, of Lua modules shared
+/// between airports.
+pub const MODULE_DIR: &str = "lib";
+
+#[derive(Error, Debug)]
+pub enum AssetError {
+  /// The assets root was set after it was already set or used.
+  #[error("assets root can't be changed to {0:?} once in use")]
+  RootAlreadySet(PathBuf),
+  #[error("failed to read assets from {0:?}: {1}")]
+  ReadDir(PathBuf, io::Error),
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+  fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+fn read_dir(path: &Path) -> Result<Vec<fs::DirEntry>, AssetError> {
+  fs::read_dir(path)
+    .map(|dir| dir.flatten().collect())
+    .map_err(|e| AssetError::ReadDir(path.to_owned(), e))
 }
 
 #[derive(Debug, Clone, Default)]
 pub struct Assets {
   pub airports: HashMap<String, Airport>,
+  pub templates: Vec<Template>,
 }
 
-pub fn load_assets() -> Assets {
+/// Loads airports and templates from an assets root, compiling any airports
+/// whose scripts have changed. Assets that fail to load are logged and skipped.
+pub fn load_assets(root: &Path) -> Result<Assets, AssetError> {
   let mut assets = Assets::default();
+  let airport_path = airport_asset_path(root);
 
-  // Compile any assets that don't have a matching json file.
-  let lua_files: Vec<_> = fs::read_dir(airport_asset_path())
-    .unwrap()
-    .flatten()
-    .filter(|f| f.file_name().to_str().unwrap().ends_with(".lua"))
-    .map(|f| {
-      (
-        f.path(),
-        f.file_name().to_string_lossy().replace(".lua", ""),
-      )
-    })
-    .collect();
+  let lua = setup_lua();
+  if let Err(e) = add_module_path(&lua, &airport_path) {
+    tracing::error!("Failed to set up Lua modules: {e}");
+  }
+
+  // Shared modules can change any airport, so they're as new as the newest.
+  let modules_modified = read_dir(&airport_path.join(MODULE_DIR))
+    .unwrap_or_default()
+    .iter()
+    .filter_map(|f| modified(&f.path()))
+    .max();
 
-  let json_files: Vec<_> = fs::read_dir(airport_asset_path())
-    .unwrap()
-    .flatten()
-    .filter(|f| f.file_name().to_str().unwrap().ends_with(".json"))
-    .map(|f| f.file_name().to_string_lossy().replace(".json", ""))
+  let files: Vec<PathBuf> = read_dir(&airport_path)?
+    .into_iter()
+    .map(|f| f.path())
     .collect();
+  let with_extension = |extension: &str| {
+    files
+      .iter()
+      .filter(|p| p.extension().is_some_and(|e| e == extension))
+      .cloned()
+      .collect::<Vec<_>>()
+  };
 
-  let lua = setup_lua();
-  for (path, lua_filename) in lua_files {
-    if !json_files.contains(&lua_filename) {
-      match try_compile_airport(&lua, &path) {
-        Ok(_) => {
-          tracing::info!("Compiled: {:?}", path);
-        }
-        Err(e) => tracing::error!("Failed to compile: {:?}: {:?}", path, e),
+  // Compile any airports that are missing or older than their script.
+  let mut json_files = with_extension("json");
+  for path in with_extension("lua") {
+    let compiled = modified(&compiled_path(&path));
+    let source = modified(&path).max(modules_modified);
+    if compiled.is_some() && compiled >= source {
+      continue;
+    }
+
+    match try_compile_airport(&lua, &path) {
+      Ok(_) => {
+        tracing::info!("Compiled: {:?}", path);
+        json_files.push(compiled_path(&path));
       }
+      Err(e) if compiled.is_some() => tracing::error!(
+        "Failed to compile {:?}, using the last compiled version: {e}",
+        path
+      ),
+      Err(e) => tracing::error!("Failed to compile {:?}: {e}", path),
     }
   }
+  json_files.sort();
+  json_files.dedup();
 
   // Gather all compiled assets.
-  let json_files: Vec<_> = fs::read_dir(airport_asset_path())
-    .unwrap()
-    .flatten()
-    .filter(|f| f.file_name().to_str().unwrap().ends_with(".json"))
-    .collect();
   for path in json_files {
-    match fs::read_to_string(path.path()) {
+    let Some(name) = path.file_stem().and_then(|n| n.to_str()) else {
+      tracing::warn!("Skipping airport file with a non-UTF-8 name: {path:?}");
+      continue;
+    };
+
+    match fs::read_to_string(&path) {
       Ok(content) => {
         match serde_json::from_str::<Airport>(&content) {
           Ok(mut airport) => {
             airport.translate(airport.center * -1.0);
             airport.extend_all();
             airport.calculate_waypoints();
+            for issue in airport.validate() {
+              if issue.is_error() {
+                tracing::error!("Airport \"{}\": {issue}", airport.id);
+              } else {
+                tracing::warn!("Airport \"{}\": {issue}", airport.id);
+              }
+            }
 
-            let name = path.file_name();
-            let name = name.to_str().unwrap().replace(".json", "");
             tracing::info!(
-              "Loaded airport \"{}\" from {}",
-              airport.id,
-              path.file_name().to_str().unwrap()
+              "Loaded airport \"{}\" from {name}.json",
+              airport.id
             );
             assets.airports.insert(name.to_owned(), airport);
           }
@@ -89,5 +166,57 @@ pub fn load_assets() -> Assets {
     }
   }
 
-  assets
+  // Templates are small, so they're compiled every time.
+  if let Ok(dir) = fs::read_dir(template_asset_path(root)) {
+    for path in dir
+      .flatten()
+      .map(|f| f.path())
+      .filter(|p| p.extension().is_some_and(|e| e == "lua"))
+    {
+      match try_compile_template(&lua, &path) {
+        Ok(template) => {
+          tracing::info!(
+            "Loaded template \"{}\" for {} from {:?}",
+            template.id,
+            template.airport,
+            path.file_name().unwrap_or_default()
+          );
+          assets.templates.push(template);
+        }
+        Err(e) => tracing::error!("Failed to compile {:?}: {e}", path),
+      }
+    }
+  }
+
+  Ok(assets)
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+  use glam::Vec2;
+  use internment::Intern;
+
+  use super::*;
+
+  #[test]
+  fn test_load_assets_skips_non_utf8_names() {
+    let root = std::env::temp_dir()
+      .join(format!("airwave-assets-{}", std::process::id()));
+    let airports = airport_asset_path(&root);
+    fs::create_dir_all(&airports).unwrap();
+
+    let airport = Airport::new(Intern::from_ref("KSFO"), Vec2::ZERO);
+    let json = serde_json::to_string(&airport).unwrap();
+    fs::write(airports.join("ksfo.json"), &json).unwrap();
+    fs::write(airports.join(OsStr::from_bytes(b"bad\xff.json")), &json)
+      .unwrap();
+
+    let assets = load_assets(&root).unwrap();
+    assert_eq!(assets.airports.len(), 1);
+    assert!(assets.airports.contains_key("ksfo"));
+
+    fs::remove_dir_all(root).unwrap();
+  }
 }
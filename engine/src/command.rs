@@ -6,8 +6,9 @@ use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 
 use crate::{
-  ExportedDuration, abbreviate_altitude, duration_now, nato_phonetic,
-  pathfinder::Node, wordify::wordify,
+  ExportedDuration, abbreviate_altitude, duration_now,
+  entities::aircraft::Emergency, nato_phonetic, pathfinder::Node,
+  wordify::wordify,
 };
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -34,6 +35,13 @@ pub enum Task {
   NamedFrequency(String),
   ResumeOwnNavigation,
   Speed(f32),
+  Squawk(u16),
+  Clearance {
+    route: Intern<String>,
+    initial_altitude: f32,
+    squawk: Option<u16>,
+    departure_frequency: f32,
+  },
 
   Taxi(Vec<Node<()>>),
   TaxiContinue,
@@ -106,13 +114,16 @@ pub enum CommandReply {
   Blank { text: String },
   WithoutCallsign { text: String },
   WithCallsign { text: String },
+  Request { text: String },
 
   GoAround { runway: String },
   HoldShortRunway { runway: String },
   ReadyForTaxi { gate: String },
+  RequestClearance { gate: String, destination: String },
   TaxiToGates { runway: String },
   ArriveInAirspace { direction: String, altitude: f32 },
   TARAResolved { assigned_alt: f32 },
+  DeclareEmergency { kind: Emergency, runway: String },
 }
 
 impl fmt::Display for CommandWithFreq {
@@ -132,6 +143,9 @@ impl fmt::Display for CommandWithFreq {
       CommandReply::WithCallsign { text } => {
         write!(f, "{text}, {}.", decoded_callsign)
       }
+      CommandReply::Request { text } => {
+        write!(f, "{decoded_callsign}, {text}.")
+      }
 
       CommandReply::GoAround { runway } => {
         write!(
@@ -165,6 +179,15 @@ impl fmt::Display for CommandWithFreq {
           nato_phonetic(gate)
         )
       }
+      CommandReply::RequestClearance { gate, destination } => {
+        write!(
+          f,
+          "Clearance, {} is at gate {}, IFR to {}, requesting clearance.",
+          decoded_callsign,
+          nato_phonetic(gate),
+          destination
+        )
+      }
       CommandReply::TaxiToGates { runway } => {
         write!(
           f,
@@ -180,6 +203,19 @@ impl fmt::Display for CommandWithFreq {
           abbreviate_altitude(*assigned_alt)
         )
       }
+      CommandReply::DeclareEmergency { kind, runway } => {
+        if let Some(prefix) = kind.prefix() {
+          write!(f, "{prefix}, {decoded_callsign}, {kind}")?;
+        } else {
+          write!(f, "{decoded_callsign}, declaring {kind}")?;
+        }
+
+        if runway.is_empty() {
+          write!(f, ".")
+        } else {
+          write!(f, ", requesting runway {runway}.")
+        }
+      }
     }
   }
 }
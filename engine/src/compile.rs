@@ -1,63 +1,91 @@
-use std::{fs, path::PathBuf, sync::mpsc};
+use std::{
+  fs,
+  path::{Path, PathBuf},
+  sync::mpsc,
+};
 
 use glam::Vec2;
 use mlua::{
-  FromLua, Lua, LuaSerdeExt, MetaMethod, Result, UserData, UserDataMethods,
-  Value,
+  FromLua, Lua, LuaSerdeExt, MetaMethod, Result, Table, UserData,
+  UserDataMethods, Value,
 };
 
 use crate::{
+  economy::Template,
   entities::airport::{Airport, Gate, Runway, Taxiway, Terminal},
-  geometry::{add_degrees, inverse_degrees, move_point, subtract_degrees},
+  geo::LatLon,
+  geometry::{
+    add_degrees, angle_between_points, inverse_degrees, move_point,
+    subtract_degrees,
+  },
 };
 
-pub fn try_compile_airport(lua: &Lua, path: &PathBuf) -> Result<Airport> {
-  let script = if let Ok(script) = std::fs::read_to_string(path) {
-    script
-  } else {
-    eprint!("Failed to load script file: {:?}", path);
-    std::process::exit(1);
-  };
+/// The path an airport script is compiled to.
+pub fn compiled_path(path: &Path) -> PathBuf {
+  path.with_extension("json")
+}
 
-  let airport: Airport = lua.from_value(lua.load(script).eval()?)?;
-  let json_path = path.to_str().unwrap().replace(".lua", ".json");
+/// Runs a script, naming the chunk after its file so that errors point to
+/// the line they came from.
+pub fn eval_script(lua: &Lua, path: &Path) -> Result<Value> {
+  let script = fs::read_to_string(path)?;
+  lua
+    .load(script)
+    .set_name(format!("@{}", path.display()))
+    .eval()
+}
+
+/// Runs an airport script without writing the compiled airport.
+pub fn load_airport(lua: &Lua, path: &Path) -> Result<Airport> {
+  lua.from_value(eval_script(lua, path)?)
+}
+
+pub fn try_compile_airport(lua: &Lua, path: &Path) -> Result<Airport> {
+  let airport = load_airport(lua, path)?;
   let json_string = serde_json::to_string(&airport).unwrap();
-  fs::write(json_path.clone(), json_string)?;
+  fs::write(compiled_path(path), json_string)?;
 
   Ok(airport)
 }
 
+pub fn try_compile_template(lua: &Lua, path: &Path) -> Result<Template> {
+  lua.from_value(eval_script(lua, path)?)
+}
+
+/// Lets scripts `require` shared modules from a directory, so that
+/// `require("lib.runways")` loads `lib/runways.lua`.
+pub fn add_module_path(lua: &Lua, dir: &Path) -> Result<()> {
+  let package: Table = lua.globals().get("package")?;
+  let path: String = package.get("path")?;
+  package.set("path", format!("{};{path}", dir.join("?.lua").display()))
+}
+
 fn log_compile_airport(
   lua: &Lua,
   path: &PathBuf,
   sender: Option<mpsc::Sender<Airport>>,
 ) -> Result<()> {
-  let script = if let Ok(script) = std::fs::read_to_string(path) {
-    script
-  } else {
-    eprint!("Failed to load script file: {:?}", path);
-    std::process::exit(1);
-  };
-
   // This happens due to an issue with file watching. So I think it's fine if we
   // ignore blank files altogether anyway.
-  if script.is_empty() {
+  if fs::metadata(path)?.len() == 0 {
     return Ok(());
   }
 
-  let airport: Airport = lua.from_value(lua.load(script).eval()?)?;
+  let airport: Airport = lua.from_value(eval_script(lua, path)?)?;
   if let Some(send) = sender {
     let _ = send.send(airport.clone());
   }
 
-  let json_path = path.to_str().unwrap().replace(".lua", ".json");
+  let json_path = compiled_path(path);
   let json_string = serde_json::to_string(&airport).unwrap();
   let json_size = json_string.len();
-  fs::write(json_path.clone(), json_string)?;
+  fs::write(&json_path, json_string)?;
 
   println!(
     "Wrote airport \"{}\" to {} ({} bytes)",
-    airport.id, json_path, json_size
+    airport.id,
+    json_path.display(),
+    json_size
   );
 
   Ok(())
@@ -70,7 +98,7 @@ pub fn compile_airport(
 ) {
   match log_compile_airport(lua, path, sender) {
     Ok(_) => println!("Changes detected, recompiling..."),
-    Err(e) => eprintln!("Error compiling: {:?}", e),
+    Err(e) => eprintln!("Error compiling: {e}"),
   };
 }
 
@@ -106,6 +134,9 @@ impl UserData for LuaVec2 {
     methods.add_method("angle_between", |_, a, b: LuaVec2| {
       Ok(a.inner.angle_to(b.inner))
     });
+    methods.add_method("heading_to", |_, a, b: LuaVec2| {
+      Ok(angle_between_points(a.inner, b.inner))
+    });
     methods.add_method("move", |_, a, (degrees, length): (f32, f32)| {
       Ok(LuaVec2::from(move_point(a.inner, degrees, length)))
     });
@@ -143,6 +174,27 @@ impl LuaVec2 {
   }
 }
 
+/// Projects latitudes and longitudes onto an airport's local plane, so that
+/// airports can be written in real-world coordinates.
+#[derive(Debug, Clone, Copy)]
+pub struct LuaProjection {
+  origin: LatLon,
+}
+
+impl UserData for LuaProjection {
+  fn add_methods<M: UserDataMethods<Self>>(methods: &mut M) {
+    methods.add_method("point", |_, p, (lat, lon): (f64, f64)| {
+      Ok(LuaVec2::from(LatLon::new(lat, lon).project(p.origin)))
+    });
+  }
+
+  fn add_fields<F: mlua::UserDataFields<Self>>(fields: &mut F) {
+    fields.add_field_method_get("origin", |lua, p: &LuaProjection| {
+      lua.to_value(&p.origin)
+    });
+  }
+}
+
 pub fn setup_lua() -> Lua {
   let lua = Lua::new();
   let globals = lua.globals();
@@ -172,6 +224,11 @@ pub fn setup_lua() -> Lua {
       lua.from_value::<Terminal>(value.clone()).map(|_| value)
     })
     .unwrap();
+  let assert_template = lua
+    .create_function(|lua, value: Value| {
+      lua.from_value::<Template>(value.clone()).map(|_| value)
+    })
+    .unwrap();
 
   let add_deg = lua
     .create_function(|_, (a, b): (f32, f32)| Ok(add_degrees(a, b)))
@@ -188,6 +245,7 @@ pub fn setup_lua() -> Lua {
   globals.set("taxiway", assert_taxiway).unwrap();
   globals.set("gate", assert_gate).unwrap();
   globals.set("terminal", assert_terminal).unwrap();
+  globals.set("template", assert_template).unwrap();
 
   globals.set("add_degrees", add_deg).unwrap();
   globals.set("subtract_degrees", sub_deg).unwrap();
@@ -207,5 +265,14 @@ pub fn setup_lua() -> Lua {
     .unwrap();
   globals.set("vec2", vec2_constructor).unwrap();
 
+  let projection_constructor = lua
+    .create_function(|lua, origin: Value| {
+      Ok(LuaProjection {
+        origin: lua.from_value(origin)?,
+      })
+    })
+    .unwrap();
+  globals.set("projection", projection_constructor).unwrap();
+
   lua
 }
@@ -0,0 +1,73 @@
+use std::time::Duration;
+
+use internment::Intern;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::{ExportedDuration, duration_now};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "kebab-case")]
+#[serde(tag = "type", content = "value")]
+pub enum CoordinationKind {
+  // Requests
+  /// Offers control of the aircraft to the receiving position, before it is
+  /// told to change frequency.
+  Handoff,
+  /// Points out an aircraft that will pass through the receiving position's
+  /// airspace, without handing it off.
+  PointOut,
+  /// Asks for the aircraft to be at an altitude.
+  Altitude(f32),
+  /// Asks for the aircraft to be on a heading.
+  Heading(f32),
+
+  // Replies
+  /// Agrees to the pending request for the aircraft.
+  Approved,
+  /// Turns down the pending request for the aircraft.
+  Unable,
+}
+
+impl CoordinationKind {
+  pub fn is_request(&self) -> bool {
+    !matches!(self, Self::Approved | Self::Unable)
+  }
+}
+
+/// A message between two positions about an aircraft. Positions are
+/// identified by their frequency.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct Coordination {
+  #[ts(as = "String")]
+  pub aircraft: Intern<String>,
+  pub from: f32,
+  pub to: f32,
+  pub kind: CoordinationKind,
+  #[ts(as = "ExportedDuration")]
+  pub created: Duration,
+}
+
+impl Coordination {
+  pub fn new(
+    aircraft: Intern<String>,
+    from: f32,
+    to: f32,
+    kind: CoordinationKind,
+  ) -> Self {
+    Self {
+      aircraft,
+      from,
+      to,
+      kind,
+      created: duration_now(),
+    }
+  }
+
+  /// Creates a reply to this message, sent back to its sender.
+  pub fn reply(&self, kind: CoordinationKind) -> Self {
+    Self::new(self.aircraft, self.to, self.from, kind)
+  }
+}
@@ -0,0 +1,221 @@
+use glam::Vec2;
+use internment::Intern;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::{
+  entities::{
+    aircraft::AircraftStats,
+    airport::{Airport, Gate, Taxiway, Terminal},
+  },
+  geometry::Translate,
+};
+
+/// Landing fee per 1,000 lbs of maximum takeoff weight.
+pub const LANDING_FEE_PER_KLB: f32 = 6.5;
+/// Gate fee per seat.
+pub const GATE_FEE_PER_SEAT: f32 = 3.0;
+
+pub const RUNWAY_COST_PER_HOUR: f32 = 500.0;
+pub const GATE_COST_PER_HOUR: f32 = 50.0;
+
+pub const DIVERSION_PENALTY: f32 = 5000.0;
+pub const DELAYED_DEPARTURE_PENALTY: f32 = 1000.0;
+
+pub fn landing_fee(stats: &AircraftStats) -> f32 {
+  stats.max_takeoff_weight / 1000.0 * LANDING_FEE_PER_KLB
+}
+
+pub fn gate_fee(stats: &AircraftStats) -> f32 {
+  stats.seats as f32 * GATE_FEE_PER_SEAT
+}
+
+/// The books of an airport.
+#[derive(
+  Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize, TS,
+)]
+#[ts(export)]
+pub struct Economy {
+  pub balance: f32,
+
+  // Revenue
+  pub landing_fees: f32,
+  pub gate_fees: f32,
+
+  // Expenses
+  pub operating_costs: f32,
+  pub penalties: f32,
+  pub construction: f32,
+}
+
+impl Economy {
+  pub fn charge_landing_fee(&mut self, amount: f32) {
+    self.landing_fees += amount;
+    self.balance += amount;
+  }
+
+  pub fn charge_gate_fee(&mut self, amount: f32) {
+    self.gate_fees += amount;
+    self.balance += amount;
+  }
+
+  pub fn pay_operating_costs(&mut self, amount: f32) {
+    self.operating_costs += amount;
+    self.balance -= amount;
+  }
+
+  pub fn pay_penalty(&mut self, amount: f32) {
+    self.penalties += amount;
+    self.balance -= amount;
+  }
+
+  /// Pays for construction, if the balance allows it.
+  pub fn try_spend(&mut self, amount: f32) -> bool {
+    if self.balance >= amount {
+      self.construction += amount;
+      self.balance -= amount;
+      true
+    } else {
+      false
+    }
+  }
+}
+
+/// A gate, terminal or taxiway that can be bought and built at an airport.
+///
+/// Templates are written in Lua using the airport's own coordinates, and
+/// `center` must match the airport's center so that they line up once the
+/// airport is placed in the world.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct Template {
+  #[ts(as = "String")]
+  pub id: Intern<String>,
+  pub name: String,
+  #[ts(as = "String")]
+  pub airport: Intern<String>,
+  #[ts(as = "(f32, f32)")]
+  pub center: Vec2,
+  pub cost: f32,
+
+  #[serde(default)]
+  pub taxiways: Vec<Taxiway>,
+  #[serde(default)]
+  pub terminals: Vec<Terminal>,
+  /// Gates to add to an existing terminal.
+  #[serde(default)]
+  #[ts(as = "Option<String>")]
+  pub terminal: Option<Intern<String>>,
+  #[serde(default)]
+  pub gates: Vec<Gate>,
+}
+
+impl Template {
+  /// Whether the terminal the template adds gates to exists, either at the
+  /// airport or in the template itself.
+  pub fn has_terminal(&self, airport: &Airport) -> bool {
+    self.terminal.is_none_or(|id| {
+      airport.terminals.iter().any(|t| t.id == id)
+        || self.terminals.iter().any(|t| t.id == id)
+    })
+  }
+
+  /// Builds the template into an airport and recalculates its waypoints.
+  pub fn build(&self, airport: &mut Airport) {
+    let offset = airport.center - self.center;
+
+    for taxiway in self.taxiways.iter() {
+      let mut taxiway = taxiway.clone();
+      taxiway.translate(offset);
+      airport.taxiways.push(taxiway.extend_ends_by(100.0));
+    }
+
+    for terminal in self.terminals.iter() {
+      let mut terminal = terminal.clone();
+      terminal.translate(offset);
+      airport.terminals.push(terminal);
+    }
+
+    if let Some(terminal) = self
+      .terminal
+      .and_then(|id| airport.terminals.iter_mut().find(|t| t.id == id))
+    {
+      for gate in self.gates.iter() {
+        let mut gate = gate.clone();
+        gate.translate(offset);
+        terminal.gates.push(gate);
+      }
+    }
+
+    airport.calculate_waypoints();
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PurchaseError {
+  UnknownAirport,
+  UnknownTemplate,
+  AlreadyBuilt,
+  UnknownTerminal,
+  InsufficientFunds,
+}
+
+impl core::fmt::Display for PurchaseError {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    match self {
+      Self::UnknownAirport => write!(f, "unknown airport"),
+      Self::UnknownTemplate => write!(f, "unknown template for this airport"),
+      Self::AlreadyBuilt => write!(f, "already built"),
+      Self::UnknownTerminal => write!(f, "unknown terminal for the gates"),
+      Self::InsufficientFunds => write!(f, "insufficient funds"),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::line::Line;
+
+  use super::*;
+
+  fn terminal(id: &str) -> Terminal {
+    Terminal {
+      id: Intern::from_ref(id),
+      a: Vec2::ZERO,
+      b: Vec2::ZERO,
+      c: Vec2::ZERO,
+      d: Vec2::ZERO,
+      gates: Vec::new(),
+      apron: Line::new(Vec2::ZERO, Vec2::ZERO),
+    }
+  }
+
+  #[test]
+  fn test_has_terminal() {
+    let mut airport = Airport::new(Intern::from_ref("KSFO"), Vec2::ZERO);
+    airport.terminals.push(terminal("A"));
+
+    let mut template = Template {
+      id: Intern::from_ref("gates"),
+      name: "Gates".to_owned(),
+      airport: airport.id,
+      center: Vec2::ZERO,
+      cost: 0.0,
+      taxiways: Vec::new(),
+      terminals: Vec::new(),
+      terminal: None,
+      gates: Vec::new(),
+    };
+    assert!(template.has_terminal(&airport));
+
+    template.terminal = Some(Intern::from_ref("A"));
+    assert!(template.has_terminal(&airport));
+
+    template.terminal = Some(Intern::from_ref("B"));
+    assert!(!template.has_terminal(&airport));
+
+    // The terminal can be built by the template itself.
+    template.terminals.push(terminal("B"));
+    assert!(template.has_terminal(&airport));
+  }
+}
@@ -11,22 +11,28 @@ use serde::{Deserialize, Serialize};
 use turborand::rng::Rng;
 
 use crate::{
-  DEFAULT_TICK_RATE_TPS, KNOT_TO_FEET_PER_SECOND, MAX_TAXI_SPEED,
-  NAUTICALMILES_TO_FEET,
-  assets::load_assets,
+  DEFAULT_TICK_RATE_TPS, KNOT_TO_FEET_PER_SECOND, LOST_COMMS_TIMEOUT_SECONDS,
+  MAX_TAXI_SPEED, NAUTICALMILES_TO_FEET,
+  assets::{AssetError, assets_root, load_assets},
+  coordination::Coordination,
+  economy::{
+    DIVERSION_PENALTY, GATE_COST_PER_HOUR, PurchaseError, RUNWAY_COST_PER_HOUR,
+    Template, gate_fee, landing_fee,
+  },
   entities::{
     aircraft::{
-      Aircraft, AircraftState, FlightSegment, TCAS, TaxiingState,
+      Aircraft, AircraftState, Emergency, FlightSegment, TCAS, TaxiingState,
+      Transponder,
       events::{AircraftEvent, EventKind, handle_aircraft_event},
     },
     airport::Airport,
-    world::{Game, World},
+    world::{Game, SquawkPool, World},
   },
   geometry::{AngleDirections, angle_between_points, delta_angle, move_point},
   line::Line,
   pathfinder::{Node, NodeBehavior, NodeKind},
   sign3,
-  wayfinder::VORData,
+  wayfinder::{Clearance, VORData},
 };
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -55,6 +61,8 @@ impl From<UICommand> for UIEvent {
 pub enum Event {
   Aircraft(AircraftEvent),
   UiEvent(UIEvent),
+  /// Coordination between positions, which is left to the runner.
+  Coordination(Coordination),
 }
 
 impl From<AircraftEvent> for Event {
@@ -86,6 +94,7 @@ impl EngineConfig {
 #[derive(Debug, Clone)]
 pub struct Engine {
   pub airports: HashMap<String, Airport>,
+  pub templates: Vec<Template>,
   pub config: EngineConfig,
   pub rng: Rng,
 
@@ -103,6 +112,7 @@ impl Default for Engine {
   fn default() -> Self {
     Self {
       airports: Default::default(),
+      templates: Default::default(),
       config: Default::default(),
       rng: Default::default(),
       world: Default::default(),
@@ -116,10 +126,13 @@ impl Default for Engine {
 }
 
 impl Engine {
-  pub fn load_assets(&mut self) {
-    let assets = load_assets();
+  pub fn load_assets(&mut self) -> Result<(), AssetError> {
+    let assets = load_assets(assets_root())?;
 
     self.airports = assets.airports;
+    self.templates = assets.templates;
+
+    Ok(())
   }
 
   pub fn airport(&self, id: impl AsRef<str>) -> Option<&Airport> {
@@ -130,6 +143,77 @@ impl Engine {
     self.airport("default")
   }
 
+  /// Templates that can still be built at an airport.
+  pub fn available_templates(
+    &self,
+    airport_id: Intern<String>,
+  ) -> impl Iterator<Item = &Template> {
+    self.templates.iter().filter(move |t| {
+      t.airport == airport_id && !self.game.built.contains(&(airport_id, t.id))
+    })
+  }
+
+  /// Buys a template from the airport's balance and builds it into the
+  /// airport.
+  pub fn purchase(
+    &mut self,
+    airport_id: Intern<String>,
+    template_id: Intern<String>,
+  ) -> Result<(), PurchaseError> {
+    let Some(airport) =
+      self.world.airports.iter_mut().find(|a| a.id == airport_id)
+    else {
+      return Err(PurchaseError::UnknownAirport);
+    };
+    let Some(template) = self
+      .templates
+      .iter()
+      .find(|t| t.id == template_id && t.airport == airport_id)
+    else {
+      return Err(PurchaseError::UnknownTemplate);
+    };
+    if self.game.built.contains(&(airport_id, template_id)) {
+      return Err(PurchaseError::AlreadyBuilt);
+    }
+    if !template.has_terminal(airport) {
+      return Err(PurchaseError::UnknownTerminal);
+    }
+
+    let economy = self.game.economies.entry(airport_id).or_default();
+    if !economy.try_spend(template.cost) {
+      return Err(PurchaseError::InsufficientFunds);
+    }
+
+    template.build(airport);
+    self.game.built.insert((airport_id, template_id));
+    tracing::info!("Built {} at {}", template.name, airport_id);
+
+    Ok(())
+  }
+
+  pub fn declare_emergency(
+    &mut self,
+    id: Intern<String>,
+    emergency: Emergency,
+  ) {
+    self
+      .events
+      .push(AircraftEvent::new(id, EventKind::Emergency(emergency)).into());
+  }
+
+  /// Records a transmission on a frequency, which every aircraft tuned to it
+  /// will hear.
+  pub fn record_transmission(&mut self, frequency: f32) {
+    for aircraft in self
+      .game
+      .aircraft
+      .iter_mut()
+      .filter(|a| a.frequency == frequency)
+    {
+      aircraft.comms.silence = 0.0;
+    }
+  }
+
   pub fn add_aircraft(&mut self, mut aircraft: Aircraft) {
     while self.game.aircraft.iter().any(|a| a.id == aircraft.id) {
       aircraft.id = Intern::from(Aircraft::random_callsign(&mut self.rng));
@@ -153,15 +237,18 @@ impl Engine {
       tracing::trace!("tick events: {:?}", self.events);
     }
 
+    self.assign_clearance_squawks();
+
     if self.config.run_collisions() {
       events.extend(self.handle_tcas());
+      events.extend(self.detect_separation_losses());
     }
 
     for aircraft in self.game.aircraft.iter_mut() {
       // Run through all events
       for event in self.events.iter().filter_map(|e| match e {
         Event::Aircraft(aircraft_event) => Some(aircraft_event),
-        Event::UiEvent(_) => None,
+        Event::UiEvent(_) | Event::Coordination(_) => None,
       }) {
         if event.id == aircraft.id {
           handle_aircraft_event(
@@ -182,19 +269,32 @@ impl Engine {
 
       // General effects
       aircraft.update_from_targets(dt);
+      let burned = aircraft.update_fuel(&mut events, dt);
       aircraft.update_position(dt);
       aircraft.update_airspace(&self.world);
       aircraft.update_segment(&mut events, &self.world, self.tick_counter);
+
+      // Statistics
+      if let Some(airspace) = aircraft.airspace.filter(|_| burned > 0.0) {
+        let stats = self.game.airport_stats.entry(airspace).or_default();
+        stats.fuel_burned += burned;
+        if aircraft.is_holding() {
+          stats.holding_fuel += burned;
+        }
+      }
     }
 
     self.compute_available_gates();
 
+    self.update_lost_comms(&mut events, dt);
+    self.update_economy(&events, dt);
+
     // ATC Automation
     self.update_auto_approach(&mut events);
     self.update_auto_ground(&mut events);
 
     if self.config.run_collisions() {
-      self.taxi_collisions();
+      events.extend(self.taxi_collisions());
     }
 
     self.tick_counter += 1;
@@ -206,6 +306,57 @@ impl Engine {
 
 // Effects
 impl Engine {
+  pub fn update_economy(&mut self, events: &[Event], dt: f32) {
+    // Operating costs
+    for airport in self.world.airports.iter() {
+      if !self.world.airport_status(airport.id).is_controlled() {
+        continue;
+      }
+
+      let gates: usize = airport.terminals.iter().map(|t| t.gates.len()).sum();
+      let per_hour = airport.runways.len() as f32 * RUNWAY_COST_PER_HOUR
+        + gates as f32 * GATE_COST_PER_HOUR;
+      self
+        .game
+        .economies
+        .entry(airport.id)
+        .or_default()
+        .pay_operating_costs(per_hour * dt / 3600.0);
+    }
+
+    // Fees and penalties
+    for event in events.iter() {
+      let Event::Aircraft(AircraftEvent { id, kind }) = event else {
+        continue;
+      };
+      let Some(aircraft) = self.game.aircraft.iter().find(|a| a.id == *id)
+      else {
+        continue;
+      };
+      let Some(airspace) = aircraft
+        .airspace
+        .filter(|a| self.world.airport_status(*a).is_controlled())
+      else {
+        continue;
+      };
+
+      let economy = self.game.economies.entry(airspace).or_default();
+      let stats = aircraft.stats();
+      match kind {
+        EventKind::Segment(_, FlightSegment::TaxiArr) => {
+          economy.charge_landing_fee(landing_fee(&stats));
+        }
+        EventKind::Segment(FlightSegment::TaxiArr, FlightSegment::Parked) => {
+          economy.charge_gate_fee(gate_fee(&stats));
+        }
+        EventKind::ResumeOwnNavigation { diversion: true } => {
+          economy.pay_penalty(DIVERSION_PENALTY);
+        }
+        _ => {}
+      }
+    }
+  }
+
   pub fn compute_available_gates(&mut self) {
     for airport in self.world.airports.iter_mut() {
       for gate in airport
@@ -235,6 +386,128 @@ impl Engine {
     }
   }
 
+  /// Squawks 7600 for aircraft that haven't heard from a controller in too
+  /// long, and flies the lost-comms procedure for those that have.
+  pub fn update_lost_comms(&mut self, events: &mut Vec<Event>, dt: f32) {
+    for aircraft in self.game.aircraft.iter_mut() {
+      aircraft.comms.silence += dt;
+
+      if aircraft.transponder.is_radio_failure() {
+        if !aircraft.segment.in_air() {
+          continue;
+        }
+
+        // Plan to begin our approach when we would have arrived as filed.
+        if aircraft.comms.approach_at.is_none() {
+          let distance = aircraft
+            .flight_plan
+            .distances(aircraft.pos)
+            .last()
+            .copied()
+            .unwrap_or_default();
+          let seconds = distance
+            / (aircraft.flight_plan.speed * KNOT_TO_FEET_PER_SECOND).max(1.0);
+
+          aircraft.comms.approach_at = Some(
+            self.tick_counter + (seconds * self.tick_rate_tps as f32) as usize,
+          );
+        }
+
+        // Climb to our filed altitude.
+        let filed_altitude = aircraft
+          .flight_plan
+          .altitude
+          .min(aircraft.stats().max_altitude);
+        if matches!(
+          aircraft.segment,
+          FlightSegment::Departure | FlightSegment::Climb
+        ) && aircraft.target.altitude < filed_altitude
+        {
+          events.push(
+            AircraftEvent::new(
+              aircraft.id,
+              EventKind::AltitudeAtOrAbove(filed_altitude),
+            )
+            .into(),
+          );
+        }
+      } else {
+        let controlled = matches!(
+          aircraft.segment,
+          FlightSegment::Departure | FlightSegment::Approach
+        ) && aircraft
+          .airspace
+          .is_some_and(|id| !self.world.airport_status(id).automate_air);
+
+        if controlled && aircraft.comms.silence >= LOST_COMMS_TIMEOUT_SECONDS {
+          tracing::warn!(
+            "{} has heard nothing on {} for {:.0}s",
+            aircraft.id,
+            aircraft.frequency,
+            aircraft.comms.silence
+          );
+
+          events.push(
+            AircraftEvent::new(
+              aircraft.id,
+              EventKind::Squawk(Transponder::RADIO_FAILURE),
+            )
+            .into(),
+          );
+        }
+      }
+    }
+  }
+
+  /// Assigns a discrete code from the departure airport's pool to each
+  /// clearance being issued without one.
+  pub fn assign_clearance_squawks(&mut self) {
+    let mut in_use: Option<HashSet<u16>> = None;
+    for event in self.events.iter_mut() {
+      let Event::Aircraft(AircraftEvent {
+        id,
+        kind: EventKind::Clearance(clearance),
+      }) = event
+      else {
+        continue;
+      };
+      if clearance.squawk.is_some() {
+        continue;
+      }
+      let Some(aircraft) = self.game.aircraft.iter().find(|a| a.id == *id)
+      else {
+        continue;
+      };
+
+      let departing = aircraft.flight_plan.departing;
+      let Some(block) =
+        self.world.airports.iter().position(|a| a.id == departing)
+      else {
+        continue;
+      };
+
+      let in_use = in_use.get_or_insert_with(|| {
+        self
+          .game
+          .aircraft
+          .iter()
+          .map(|a| a.transponder.code)
+          .collect()
+      });
+      let pool = self
+        .game
+        .squawk_pools
+        .entry(departing)
+        .or_insert_with(|| SquawkPool::new(block));
+      clearance.squawk = pool.assign(in_use);
+      if let Some(code) = clearance.squawk {
+        in_use.insert(code);
+      } else {
+        tracing::warn!("No squawk codes left for {}", departing);
+      }
+    }
+  }
+
   pub fn handle_tcas(&mut self) -> Vec<Event> {
     let mut events: Vec<Event> = Vec::new();
     let mut collisions: HashMap<Intern<String>, TCAS> = HashMap::new();
@@ -246,12 +519,15 @@ impl Engine {
       let vertical_distance =
         (aircraft.altitude - other_aircraft.altitude).abs();
 
+      // TCAS can only see aircraft with an active transponder.
+      let both_are_visible = aircraft.transponder.is_active()
+        && other_aircraft.transponder.is_active();
       let both_are_flying = matches!(aircraft.state, AircraftState::Flying)
         && matches!(other_aircraft.state, AircraftState::Flying);
       let both_are_above =
         aircraft.altitude > 2000.0 && other_aircraft.altitude > 2000.0;
 
-      if !both_are_flying || !both_are_above {
+      if !both_are_visible || !both_are_flying || !both_are_above {
         continue;
       }
 
@@ -313,6 +589,13 @@ impl Engine {
 
     self.game.aircraft.iter_mut().for_each(|aircraft| {
       if let Some(tcas) = collisions.get(&aircraft.id) {
+        if tcas.is_ra() && !aircraft.tcas.is_ra() {
+          events.push(Event::Aircraft(AircraftEvent::new(
+            aircraft.id,
+            EventKind::ResolutionAdvisory,
+          )));
+        }
+
         aircraft.tcas = *tcas;
       } else if !aircraft.tcas.is_idle() {
         if aircraft.tcas.is_ra() {
@@ -329,6 +612,62 @@ impl Engine {
     events
   }
 
+  /// Reports pairs of aircraft in the same airspace that come within 3nm and
+  /// 1000ft of each other, once per encounter.
+  pub fn detect_separation_losses(&mut self) -> Vec<Event> {
+    let mut events: Vec<Event> = Vec::new();
+    let mut losses: HashSet<(Intern<String>, Intern<String>)> = HashSet::new();
+
+    let min_distance = NAUTICALMILES_TO_FEET * 3.0;
+    let min_vertical_distance = 1000.0;
+
+    for pair in self
+      .game
+      .aircraft
+      .iter()
+      .filter(|a| {
+        matches!(
+          a.state,
+          AircraftState::Flying | AircraftState::Landing { .. }
+        ) && a.airspace.is_some()
+      })
+      .combinations(2)
+    {
+      let aircraft = pair.first().unwrap();
+      let other_aircraft = pair.last().unwrap();
+
+      // Aircraft on final are separated by the tower.
+      let both_are_landing =
+        matches!(aircraft.state, AircraftState::Landing { .. })
+          && matches!(other_aircraft.state, AircraftState::Landing { .. });
+      if aircraft.airspace != other_aircraft.airspace || both_are_landing {
+        continue;
+      }
+
+      let distance = aircraft.pos.distance_squared(other_aircraft.pos);
+      let vertical_distance =
+        (aircraft.altitude - other_aircraft.altitude).abs();
+
+      if distance < min_distance.powf(2.0)
+        && vertical_distance < min_vertical_distance
+      {
+        let key = (aircraft.id, other_aircraft.id);
+        if !self.game.separation_losses.contains(&key) {
+          events.push(Event::Aircraft(AircraftEvent::new(
+            aircraft.id,
+            EventKind::SeparationLoss(other_aircraft.id),
+          )));
+        }
+
+        losses.insert(key);
+      }
+    }
+
+    self.game.separation_losses = losses;
+
+    events
+  }
+
   // FIXME: There's a bug here when aircraft land it spits out a ton of
   // TaxiContinue events. Not sure why.
   pub fn taxi_collisions(&mut self) -> Vec<Event> {
@@ -483,8 +822,13 @@ impl Engine {
     let mut speeds: Vec<(Intern<String>, f32, f32)> = Vec::new();
 
     for (_, mut aircrafts) in airspaces.into_iter() {
+      // Aircraft in an emergency get priority over everyone else.
       aircrafts.sort_by(|a, b| {
-        a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal)
+        a.0
+          .emergency
+          .is_none()
+          .cmp(&b.0.emergency.is_none())
+          .then(a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
       });
 
       let mut aircrafts = aircrafts.into_iter();
@@ -545,10 +889,12 @@ impl Engine {
     }
 
     for aircraft in self.game.aircraft.iter() {
+      // Aircraft without a radio fly the same approach as automated ones.
       if matches!(aircraft.segment, FlightSegment::Approach)
-        && aircraft
+        && (aircraft
           .airspace
           .is_some_and(|a| self.world.airport_status(a).automate_air)
+          || aircraft.lost_comms_approach(self.tick_counter))
       {
         if let Some(airport) =
           aircraft.airspace.and_then(|id| self.world.airport(id))
@@ -559,9 +905,10 @@ impl Engine {
             .iter()
             .find(|w| w.name == Intern::from_ref("STAR"))
           {
-            airport
+            let runway = airport
               .runways
               .iter()
+              .filter(|r| !self.world.is_runway_closed(airport.id, r.id))
               .dedup_by(|a, b| a.heading == b.heading)
               .min_by(|a, b| {
                 let dist_a = star.data.pos.distance_squared(a.start);
@@ -569,8 +916,12 @@ impl Engine {
                 dist_a
                   .partial_cmp(&dist_b)
                   .unwrap_or(std::cmp::Ordering::Equal)
-              })
-              .unwrap()
+              });
+            let Some(runway) = runway else {
+              tracing::warn!("No open runway for {}!", aircraft.id);
+              continue;
+            };
+            runway
           } else {
             tracing::error!("No STAR, so no runway for {}!", aircraft.id);
             continue;
@@ -664,20 +1015,26 @@ impl Engine {
               let min_landing_separation = NAUTICALMILES_TO_FEET * 3.5;
 
               if distance <= land_distance.powf(2.0) {
-                if let Some((too_close, distance)) = self
-                  .game
-                  .aircraft
-                  .iter()
-                  .filter(|a| {
-                    a.id != aircraft.id
-                      && a.airspace == aircraft.airspace
-                      && a.segment == FlightSegment::Landing
-                  })
-                  .map(|a| (a, a.pos.distance_squared(aircraft.pos)))
-                  .find(|(_, distance)| {
-                    *distance < (min_landing_separation).powf(2.0)
-                  })
-                {
+                // Never resequence an aircraft in an emergency.
+                let too_close = if aircraft.emergency.is_none() {
+                  self
+                    .game
+                    .aircraft
+                    .iter()
+                    .filter(|a| {
+                      a.id != aircraft.id
+                        && a.airspace == aircraft.airspace
+                        && a.segment == FlightSegment::Landing
+                    })
+                    .map(|a| (a, a.pos.distance_squared(aircraft.pos)))
+                    .find(|(_, distance)| {
+                      *distance < (min_landing_separation).powf(2.0)
+                    })
+                } else {
+                  None
+                };
+
+                if let Some((too_close, distance)) = too_close {
                   let downwind_fix = move_point(
                     runway.start,
                     directions.forward,
@@ -796,6 +1153,25 @@ impl Engine {
               }
             }
           }
+        } else if matches!(aircraft.segment, FlightSegment::Parked)
+          && aircraft.flight_plan.clearance.is_none()
+        {
+          if let Some(departure) =
+            self.world.airport(aircraft.flight_plan.departing)
+          {
+            events.push(
+              AircraftEvent::new(
+                aircraft.id,
+                EventKind::Clearance(Clearance {
+                  route: aircraft.flight_plan.arriving,
+                  initial_altitude: aircraft.flight_plan.altitude,
+                  squawk: None,
+                  departure_frequency: departure.frequencies.departure,
+                }),
+              )
+              .into(),
+            );
+          }
         } else if matches!(aircraft.segment, FlightSegment::Parked) {
           if let AircraftState::Parked { .. } = &aircraft.state {
             if let Some(airport) =
@@ -807,7 +1183,10 @@ impl Engine {
               if let Some((departure, arrival)) = departure.zip(arrival) {
                 let departure_angle =
                   angle_between_points(departure.center, arrival.center);
-                let runways = departure.runways.iter();
+                let runways = departure
+                  .runways
+                  .iter()
+                  .filter(|r| !self.world.is_runway_closed(departure.id, r.id));
 
                 let mut smallest_angle = f32::MAX;
                 let mut closest = None;
@@ -819,8 +1198,10 @@ impl Engine {
                   }
                 }
 
-                // If an airport doesn't have a runway, we have other problems.
-                let runway = closest.unwrap();
+                // Wait for a runway to open.
+                let Some(runway) = closest else {
+                  continue;
+                };
                 let node_index = airport
                   .pathfinder
                   .graph
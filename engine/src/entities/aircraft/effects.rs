@@ -1,5 +1,6 @@
 use crate::{
-  KNOT_TO_FEET_PER_SECOND, MIN_CRUISE_ALTITUDE, NAUTICALMILES_TO_FEET,
+  EMERGENCY_FUEL_MINUTES, KNOT_TO_FEET_PER_SECOND, MIN_CRUISE_ALTITUDE,
+  MINIMUM_FUEL_MINUTES, NAUTICALMILES_TO_FEET, TAXI_TURN_LOOKAHEAD,
   TRANSITION_ALTITUDE,
   command::{CommandReply, CommandWithFreq},
   engine::Event,
@@ -14,15 +15,33 @@ use crate::{
 };
 
 use super::{
-  Aircraft, AircraftState, FlightSegment, LandingState, TCAS,
+  Aircraft, AircraftState, Emergency, FlightSegment, LandingState, TCAS,
   events::{AircraftEvent, EventKind},
 };
 
 // Engine Effects.
 impl Aircraft {
   pub fn update_from_targets(&mut self, dt: f32) {
+    // Aircraft in an emergency can't exceed their degraded limits.
+    if self.emergency.is_some() {
+      let stats = self.stats();
+      self.target.altitude = self.target.altitude.min(stats.max_altitude);
+      self.target.speed = self.target.speed.min(stats.max_speed);
+    }
+
+    let target_altitude = match self.tcas {
+      TCAS::Idle | TCAS::Warning => self.target.altitude,
+      TCAS::Hold => self.altitude,
+      TCAS::Climb => self.altitude + 1000.0,
+      TCAS::Descend => self.altitude - 1000.0,
+    };
+
     // In feet per second
-    let climb_speed = self.climb_speed() * dt;
+    let climb_speed = if target_altitude < self.altitude {
+      self.descent_speed()
+    } else {
+      self.climb_speed()
+    } * dt;
     // In degrees per second
     let turn_speed = self.turn_speed() * dt;
     // In knots per second
@@ -32,13 +51,6 @@ impl Aircraft {
     let mut heading = self.heading;
     let mut speed = self.speed;
 
-    let target_altitude = match self.tcas {
-      TCAS::Idle | TCAS::Warning => self.target.altitude,
-      TCAS::Hold => self.altitude,
-      TCAS::Climb => self.altitude + 1000.0,
-      TCAS::Descend => self.altitude - 1000.0,
-    };
-
     // Snap values if they're close enough
     if (altitude - target_altitude).abs() < climb_speed {
       altitude = target_altitude;
@@ -85,6 +97,46 @@ impl Aircraft {
     }
   }
 
+  /// Burns fuel for this tick, returning the amount burned in pounds.
+  pub fn update_fuel(&mut self, events: &mut Vec<Event>, dt: f32) -> f32 {
+    let flow = self.fuel_flow();
+    let burned = (flow * dt / 3600.0).min(self.fuel.on_board);
+
+    self.fuel.on_board -= burned;
+    self.fuel.burned += burned;
+    self.fuel.flow = flow;
+    // Endurance is calculated at no less than holding fuel flow so that it
+    // stays meaningful while on the ground.
+    self.fuel.endurance =
+      self.fuel.on_board / flow.max(self.stats().hold_fuel_flow) * 60.0;
+
+    if self.segment.in_air() {
+      if self.fuel.endurance < EMERGENCY_FUEL_MINUTES
+        && self.emergency.is_none_or(|e| e == Emergency::MinimumFuel)
+      {
+        events.push(
+          AircraftEvent::new(
+            self.id,
+            EventKind::Emergency(Emergency::EmergencyFuel),
+          )
+          .into(),
+        );
+      } else if self.fuel.endurance < MINIMUM_FUEL_MINUTES
+        && self.emergency.is_none()
+      {
+        events.push(
+          AircraftEvent::new(
+            self.id,
+            EventKind::Emergency(Emergency::MinimumFuel),
+          )
+          .into(),
+        );
+      }
+    }
+
+    burned
+  }
+
   pub fn update_position(&mut self, dt: f32) {
     let pos = move_point(
       self.pos,
@@ -216,15 +268,43 @@ impl Aircraft {
     {
       let waypoint = waypoints.last().cloned();
       if let Some(waypoint) = waypoint {
-        let heading = angle_between_points(self.pos, waypoint.data);
+        let distance = self.pos.distance(waypoint.data);
+
+        // Follow the path with a point that runs ahead of us, so that corners
+        // and curves are turned through smoothly instead of with a sudden
+        // change of heading. Only waypoints that we don't stop at, and that
+        // don't turn us around, are cut short.
+        let next = waypoints.iter().rev().nth(1).filter(|next| {
+          waypoint.behavior == NodeBehavior::GoTo
+            && delta_angle(
+              self.heading,
+              angle_between_points(waypoint.data, next.data),
+            )
+            .abs()
+              <= 90.0
+        });
+        let (aim, passed) = match next {
+          Some(next) if distance < TAXI_TURN_LOOKAHEAD => {
+            let passed = self.pos.distance(next.data) <= TAXI_TURN_LOOKAHEAD;
+
+            // Where the lookahead reaches the line to the next waypoint.
+            let direction = (next.data - waypoint.data).normalize_or_zero();
+            let offset = waypoint.data - self.pos;
+            let b = offset.dot(direction);
+            let c = offset.length_squared() - TAXI_TURN_LOOKAHEAD.powf(2.0);
+            let along = -b + (b * b - c).max(0.0).sqrt();
+
+            (waypoint.data + direction * along, passed)
+          }
+          _ => (waypoint.data, false),
+        };
+
+        let heading = angle_between_points(self.pos, aim);
 
         self.heading = heading;
         self.target.heading = heading;
 
-        let distance = self.pos.distance_squared(waypoint.data);
-        let movement_speed = speed_in_feet.powf(2.0);
-
-        if movement_speed >= distance {
+        if passed || speed_in_feet >= distance {
           if let Some(wp) = waypoints.pop() {
             *current = wp;
           }
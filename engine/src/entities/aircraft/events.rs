@@ -13,11 +13,12 @@ use crate::{
   geometry::{angle_between_points, delta_angle},
   heading_to_direction,
   pathfinder::{Node, NodeBehavior, NodeKind, Pathfinder, display_node_vec2},
-  wayfinder::{VORData, VORLimit, VORLimits, new_vor},
+  wayfinder::{Clearance, VORData, VORLimit, VORLimits, new_vor},
 };
 
 use super::{
-  Aircraft, AircraftState, FlightSegment, LandingState, TaxiingState,
+  Aircraft, AircraftState, Emergency, FlightSegment, Fuel, LandingState,
+  TaxiingState, Transponder, TransponderMode,
 };
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -52,11 +53,18 @@ pub enum EventKind {
 
   // Requests
   Ident,
+  Squawk(u16),
+  Clearance(Clearance),
+  Emergency(Emergency),
 
   // Callouts
   Callout(CommandWithFreq),
   CalloutTARA,
 
+  // Incidents
+  SeparationLoss(Intern<String>),
+  ResolutionAdvisory,
+
   // State
   Segment(FlightSegment, FlightSegment),
 
@@ -80,6 +88,18 @@ impl From<Task> for EventKind {
         EventKind::ResumeOwnNavigation { diversion: false }
       }
       Task::Speed(x) => EventKind::Speed(x),
+      Task::Squawk(x) => EventKind::Squawk(x),
+      Task::Clearance {
+        route,
+        initial_altitude,
+        squawk,
+        departure_frequency,
+      } => EventKind::Clearance(Clearance {
+        route,
+        initial_altitude,
+        squawk,
+        departure_frequency,
+      }),
       Task::Takeoff(x) => EventKind::Takeoff(x),
       Task::Taxi(x) => EventKind::Taxi(x),
       Task::TaxiContinue => EventKind::TaxiContinue,
@@ -124,6 +144,14 @@ pub fn handle_aircraft_event(
         aircraft.target.speed = *speed;
       }
     }
+    // Aircraft in an emergency refuse to be held, such as by being vectored
+    // away from where they are landing.
+    EventKind::Heading(heading)
+      if aircraft.emergency.is_some()
+        && is_vector_away(aircraft, *heading, world) =>
+    {
+      handle_unable_hold(aircraft, events);
+    }
     EventKind::Heading(heading) => {
       if let AircraftState::Flying = aircraft.state {
         aircraft.target.heading = *heading;
@@ -148,16 +176,14 @@ pub fn handle_aircraft_event(
         aircraft.target.altitude = *altitude;
       }
     }
+    // Aircraft with a radio failure can't change frequency.
+    EventKind::Frequency(..) | EventKind::NamedFrequency(..)
+      if aircraft.transponder.is_radio_failure() => {}
     EventKind::Frequency(frequency) => {
       aircraft.frequency = *frequency;
     }
     EventKind::NamedFrequency(frq) => {
-      if let Some(frequency) = world
-        .airports
-        .iter()
-        .find(|a| aircraft.airspace.is_some_and(|id| a.id == id))
-        .and_then(|x| x.frequencies.try_from_string(frq))
-      {
+      if let Some(frequency) = aircraft.named_frequency(frq, &world.airports) {
         aircraft.frequency = frequency;
       }
     }
@@ -264,6 +290,7 @@ pub fn handle_aircraft_event(
         }
       }
     }
+    EventKind::Direct(wp) if world.is_navaid_failed(*wp) => {}
     EventKind::Direct(wp) => {
       if let Some((index, _)) = aircraft
         .flight_plan
@@ -316,6 +343,12 @@ pub fn handle_aircraft_event(
     }
 
     // Taxiing
+    // Departures need a clearance before they can taxi.
+    EventKind::Taxi(..)
+      if aircraft.is_parked() && aircraft.flight_plan.clearance.is_none() =>
+    {
+      handle_unable_no_clearance(aircraft, events);
+    }
     EventKind::Taxi(waypoints) => {
       if let AircraftState::Taxiing { .. } | AircraftState::Parked { .. } =
         aircraft.state
@@ -390,6 +423,27 @@ pub fn handle_aircraft_event(
         .into(),
       );
     }
+    EventKind::Squawk(code) => {
+      handle_squawk_event(aircraft, *code, events);
+    }
+    EventKind::Clearance(clearance) => {
+      if aircraft.is_parked() && aircraft.flight_plan.clearance.is_none() {
+        if clearance.route == aircraft.flight_plan.arriving {
+          handle_clearance_event(aircraft, clearance, events, world);
+        } else {
+          handle_unable_wrong_route(aircraft, events);
+        }
+      }
+    }
+    EventKind::Emergency(emergency) => {
+      // Minimum fuel can only be upgraded to a fuel emergency.
+      let can_declare = aircraft.emergency.is_none_or(|e| {
+        e == Emergency::MinimumFuel && *emergency == Emergency::EmergencyFuel
+      });
+      if aircraft.segment.in_air() && can_declare {
+        handle_emergency_event(aircraft, *emergency, events, world);
+      }
+    }
 
     // Generic callouts are handled outside of the engine.
     EventKind::Callout(..) => {}
@@ -397,6 +451,9 @@ pub fn handle_aircraft_event(
       handle_callout_tara(aircraft, events);
     }
 
+    // Incidents are scored outside of the engine.
+    EventKind::SeparationLoss(..) | EventKind::ResolutionAdvisory => {}
+
     // State
     EventKind::Segment(prev, segment) => {
       // TODO: Remove this once we don't need the vis.
@@ -408,14 +465,23 @@ pub fn handle_aircraft_event(
       // );
 
       aircraft.segment = *segment;
+      // Every new segment starts with a check-in.
+      aircraft.comms.silence = 0.0;
 
       match segment {
         FlightSegment::Unknown => {}
         FlightSegment::Dormant => {
           aircraft.flight_time = None;
+          aircraft.flight_plan.clearance = None;
+          // Releases our code back to the pool.
+          aircraft.transponder = Transponder::default();
         }
         FlightSegment::Boarding => {}
         FlightSegment::Parked => {
+          if matches!(prev, FlightSegment::Dormant | FlightSegment::Boarding) {
+            handle_fuel_planning(aircraft, world);
+          }
+
           if *prev == FlightSegment::Boarding {
             handle_parked_transition(aircraft, events, world);
           } else if *prev == FlightSegment::TaxiArr {
@@ -428,7 +494,9 @@ pub fn handle_aircraft_event(
             );
           }
         }
-        FlightSegment::TaxiDep => {}
+        FlightSegment::TaxiDep => {
+          aircraft.transponder.mode = TransponderMode::ModeS;
+        }
         FlightSegment::Takeoff => {}
         FlightSegment::Departure => {}
         FlightSegment::Climb => {}
@@ -464,6 +532,7 @@ pub fn handle_land_event(
   ) {
     if let Some(runway) = aircraft
       .find_airport(&world.airports)
+      .filter(|x| !world.is_runway_closed(x.id, runway_id))
       .and_then(|x| x.runways.iter().find(|r| r.id == runway_id))
     {
       aircraft.state = AircraftState::Landing {
@@ -485,6 +554,7 @@ pub fn handle_touchdown_event(aircraft: &mut Aircraft) {
   aircraft.heading = runway.heading;
 
   aircraft.target.speed = 0.0;
+  aircraft.emergency = None;
 
   aircraft.state = AircraftState::Taxiing {
     current: Node {
@@ -615,6 +685,7 @@ pub fn handle_takeoff_event(
 ) {
   let runway = aircraft
     .find_airport(&world.airports)
+    .filter(|x| !world.is_runway_closed(x.id, runway_id))
     .and_then(|x| x.runways.iter().find(|r| r.id == runway_id));
 
   if let AircraftState::Taxiing {
@@ -651,6 +722,19 @@ pub fn handle_takeoff_event(
   }
 }
 
+pub fn handle_fuel_planning(aircraft: &mut Aircraft, world: &World) {
+  let departure = world.airport(aircraft.flight_plan.departing);
+  let arrival = world.airport(aircraft.flight_plan.arriving);
+
+  if let Some((departure, arrival)) = departure.zip(arrival) {
+    aircraft.fuel = Fuel::plan(
+      &aircraft.stats(),
+      departure.center.distance(arrival.center),
+      aircraft.flight_plan.speed,
+    );
+  }
+}
+
 pub fn handle_parked_transition(
   aircraft: &mut Aircraft,
   events: &mut Vec<Event>,
@@ -658,7 +742,7 @@ pub fn handle_parked_transition(
 ) {
   if let AircraftState::Parked { at } = &aircraft.state {
     if let Some(airport) = aircraft.find_airport(&world.airports) {
-      aircraft.frequency = airport.frequencies.ground;
+      aircraft.frequency = airport.frequencies.clearance_delivery();
 
       events.push(
         AircraftEvent {
@@ -666,8 +750,9 @@ pub fn handle_parked_transition(
           kind: EventKind::Callout(CommandWithFreq::new(
             aircraft.id.to_string(),
             aircraft.frequency,
-            CommandReply::ReadyForTaxi {
+            CommandReply::RequestClearance {
               gate: at.name.to_string(),
+              destination: aircraft.flight_plan.arriving.to_string(),
             },
             Vec::new(),
           )),
@@ -678,6 +763,119 @@ pub fn handle_parked_transition(
   }
 }
 
+pub fn handle_clearance_event(
+  aircraft: &mut Aircraft,
+  clearance: &Clearance,
+  events: &mut Vec<Event>,
+  world: &World,
+) {
+  aircraft.flight_plan.altitude = clearance.initial_altitude;
+  if let Some(code) = clearance.squawk {
+    aircraft.transponder.code = code;
+  }
+  aircraft.flight_plan.clearance = Some(Clearance {
+    squawk: Some(aircraft.transponder.code),
+    ..clearance.clone()
+  });
+
+  // Once cleared, call ground for taxi.
+  let ground = aircraft
+    .find_airport(&world.airports)
+    .map(|a| a.frequencies.ground);
+  if let (AircraftState::Parked { at }, Some(ground)) =
+    (&aircraft.state, ground)
+  {
+    aircraft.frequency = ground;
+
+    events.push(
+      AircraftEvent {
+        id: aircraft.id,
+        kind: EventKind::Callout(CommandWithFreq::new(
+          aircraft.id.to_string(),
+          aircraft.frequency,
+          CommandReply::ReadyForTaxi {
+            gate: at.name.to_string(),
+          },
+          Vec::new(),
+        )),
+      }
+      .into(),
+    );
+  }
+}
+
+/// Whether a heading would turn an aircraft more than 90 degrees away from
+/// the airport it is landing at.
+fn is_vector_away(aircraft: &Aircraft, heading: f32, world: &World) -> bool {
+  matches!(aircraft.state, AircraftState::Flying)
+    && world
+      .airport(aircraft.flight_plan.arriving)
+      .is_some_and(|airport| {
+        let bearing = angle_between_points(aircraft.pos, airport.center);
+        delta_angle(bearing, heading).abs() > 90.0
+      })
+}
+
+pub fn handle_unable_hold(aircraft: &mut Aircraft, events: &mut Vec<Event>) {
+  events.push(
+    AircraftEvent::new(
+      aircraft.id,
+      EventKind::Callout(CommandWithFreq::new(
+        aircraft.id.to_string(),
+        aircraft.frequency,
+        CommandReply::WithCallsign {
+          text: "Unable, we can't accept any delay".to_owned(),
+        },
+        Vec::new(),
+      )),
+    )
+    .into(),
+  );
+}
+
+pub fn handle_unable_wrong_route(
+  aircraft: &mut Aircraft,
+  events: &mut Vec<Event>,
+) {
+  events.push(
+    AircraftEvent::new(
+      aircraft.id,
+      EventKind::Callout(CommandWithFreq::new(
+        aircraft.id.to_string(),
+        aircraft.frequency,
+        CommandReply::WithCallsign {
+          text: format!(
+            "Unable, we're filed to {}",
+            aircraft.flight_plan.arriving
+          ),
+        },
+        Vec::new(),
+      )),
+    )
+    .into(),
+  );
+}
+
+pub fn handle_unable_no_clearance(
+  aircraft: &mut Aircraft,
+  events: &mut Vec<Event>,
+) {
+  events.push(
+    AircraftEvent::new(
+      aircraft.id,
+      EventKind::Callout(CommandWithFreq::new(
+        aircraft.id.to_string(),
+        aircraft.frequency,
+        CommandReply::WithCallsign {
+          text: "Unable, we haven't received our clearance yet".to_owned(),
+        },
+        Vec::new(),
+      )),
+    )
+    .into(),
+  );
+}
+
 // TODO: I think the [`Runner`] or [`Engine`] should handle this instead.
 pub fn handle_approach_transition(
   aircraft: &mut Aircraft,
@@ -702,7 +900,8 @@ pub fn handle_approach_transition(
       .get(&airport.id)
       .copied()
       .unwrap_or_default();
-    if !status.divert_arrivals {
+    // Aircraft in an emergency are never diverted away.
+    if !status.divert_arrivals || aircraft.emergency.is_some() {
       aircraft.target.heading =
         angle_between_points(aircraft.pos, airport.center);
 
@@ -748,6 +947,93 @@ pub fn handle_approach_transition(
   }
 }
 
+pub fn handle_squawk_event(
+  aircraft: &mut Aircraft,
+  code: u16,
+  events: &mut Vec<Event>,
+) {
+  aircraft.transponder.code = code;
+
+  if aircraft.transponder.is_hijack() {
+    tracing::warn!("{} is squawking {}", aircraft.id, aircraft.transponder);
+    events.push(
+      AircraftEvent::new(
+        aircraft.id,
+        EventKind::Callout(CommandWithFreq::new(
+          aircraft.id.to_string(),
+          aircraft.frequency,
+          CommandReply::WithCallsign {
+            text: format!("Squawking {}", aircraft.transponder),
+          },
+          Vec::new(),
+        )),
+      )
+      .into(),
+    );
+  } else if aircraft.transponder.is_radio_failure() {
+    // Without a radio, follow the flight plan as filed.
+    if let AircraftState::Flying = aircraft.state {
+      aircraft.flight_plan.course_offset = 0.0;
+      aircraft.flight_plan.start_following();
+    }
+  }
+}
+
+pub fn handle_emergency_event(
+  aircraft: &mut Aircraft,
+  emergency: Emergency,
+  events: &mut Vec<Event>,
+  world: &World,
+) {
+  aircraft.emergency = Some(emergency);
+  if emergency.prefix().is_some() {
+    aircraft.transponder.code = Transponder::EMERGENCY;
+  }
+
+  let stats = aircraft.stats();
+  let nearest =
+    world.nearest_suitable_runway(aircraft.pos, stats.landing_length);
+
+  // Divert to the nearest airport that can take us.
+  let divert = nearest
+    .map(|(airport, _)| airport)
+    .filter(|a| a.id != aircraft.flight_plan.arriving)
+    .filter(|_| matches!(aircraft.state, AircraftState::Flying));
+  if let Some(airport) = divert {
+    aircraft.flight_plan.arriving = airport.id;
+
+    aircraft.flight_plan.clear_waypoints();
+    aircraft.flight_plan.waypoints = vec![
+      new_vor(
+        Intern::from_ref("STAR"),
+        airport
+          .center
+          .move_towards(aircraft.pos, NAUTICALMILES_TO_FEET * 30.0),
+      )
+      .with_limits(
+        VORLimits::new()
+          .with_altitude(VORLimit::AtOrBelow(ARRIVAL_ALTITUDE))
+          .with_speed(VORLimit::AtOrBelow(250.0)),
+      ),
+    ];
+  }
+
+  let command = CommandWithFreq::new(
+    Intern::to_string(&aircraft.id),
+    aircraft.frequency,
+    CommandReply::DeclareEmergency {
+      kind: emergency,
+      runway: nearest.map(|(_, r)| r.id.to_string()).unwrap_or_default(),
+    },
+    Vec::new(),
+  );
+
+  events.push(Event::Aircraft(AircraftEvent::new(
+    aircraft.id,
+    EventKind::Callout(command),
+  )));
+}
+
 pub fn handle_callout_tara(aircraft: &mut Aircraft, events: &mut Vec<Event>) {
   let command = CommandWithFreq::new(
     Intern::to_string(&aircraft.id),
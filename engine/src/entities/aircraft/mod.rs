@@ -7,11 +7,16 @@ use glam::Vec2;
 use internment::Intern;
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
-use turborand::{TurboRand, rng::Rng};
+use turborand::rng::Rng;
 
 use crate::{
-  KNOT_TO_FEET_PER_SECOND, NAUTICALMILES_TO_FEET, geometry::delta_angle,
-  pathfinder::Node, wayfinder::FlightPlan,
+  AIRSPACE_RADIUS, ARRIVAL_ALTITUDE, EMERGENCY_DESCENT_ALTITUDE,
+  KNOT_TO_FEET_PER_SECOND, NAUTICALMILES_TO_FEET, RESERVE_FUEL_MINUTES,
+  TAXI_FUEL_MINUTES,
+  airlines::registry,
+  geometry::{angle_between_points, delta_angle, move_point},
+  pathfinder::Node,
+  wayfinder::{FlightPlan, VORLimit, VORLimits, new_vor},
 };
 
 use super::airport::{Airport, Gate, Runway};
@@ -151,13 +156,53 @@ pub struct AircraftStats {
   pub fuel_capacity: f32,
   /// Passenger capacity in capita
   pub seats: usize,
+
+  // Fuel
+  /// Fuel flow while taxiing in pounds per hour
+  pub taxi_fuel_flow: f32,
+  /// Fuel flow while taking off and climbing in pounds per hour
+  pub climb_fuel_flow: f32,
+  /// Fuel flow while cruising in pounds per hour
+  pub cruise_fuel_flow: f32,
+  /// Fuel flow while holding in pounds per hour
+  pub hold_fuel_flow: f32,
+}
+
+impl AircraftStats {
+  /// Returns these stats with the performance lost due to an emergency.
+  pub fn degraded(mut self, emergency: Emergency) -> Self {
+    match emergency {
+      Emergency::EngineFailure => {
+        self.thrust *= 0.5;
+        self.roc *= 0.5;
+        self.max_altitude = self.max_altitude.min(20000.0);
+        self.max_speed *= 0.7;
+        self.takeoff_length *= 1.5;
+        self.landing_length *= 1.2;
+      }
+      Emergency::Decompression => {
+        // Emergency descents are flown as quickly as possible.
+        self.rod *= 1.6;
+        self.max_altitude = self.max_altitude.min(EMERGENCY_DESCENT_ALTITUDE);
+      }
+      Emergency::Medical
+      | Emergency::MinimumFuel
+      | Emergency::EmergencyFuel => {}
+    }
+
+    self
+  }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(
+  Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize, TS,
+)]
 #[serde(rename_all = "lowercase")]
+#[ts(export)]
 pub enum AircraftKind {
   // Airbus
   /// https://contentzone.eurocontrol.int/aircraftperformance/details.aspx?ICAO=A21N
+  #[default]
   A21N,
   /// https://contentzone.eurocontrol.int/aircraftperformance/details.aspx?ICAO=A333
   A333,
@@ -198,13 +243,149 @@ impl AircraftKind {
         dry_weight: 103000.0,
         fuel_capacity: 58232.5,
         seats: 200,
+        taxi_fuel_flow: 1000.0,
+        climb_fuel_flow: 9000.0,
+        cruise_fuel_flow: 5300.0,
+        hold_fuel_flow: 4400.0,
+      },
+      AircraftKind::A333 => AircraftStats {
+        thrust: 316.0,
+        // TODO: placeholder
+        drag: 0.0,
+        turn_speed: 2.0,
+        roc: 1500.0,
+        rod: 2500.0,
+        max_altitude: 41000.0,
+        min_speed: 140.0,
+        max_speed: 470.0,
+        v2: 150.0,
+        takeoff_length: 8300.0,
+        landing_length: 6000.0,
+        max_takeoff_weight: 535000.0,
+        max_landing_weight: 412300.0,
+        dry_weight: 274500.0,
+        fuel_capacity: 172000.0,
+        seats: 300,
+        taxi_fuel_flow: 2000.0,
+        climb_fuel_flow: 16000.0,
+        cruise_fuel_flow: 12500.0,
+        hold_fuel_flow: 9000.0,
+      },
+      AircraftKind::B737 => AircraftStats {
+        thrust: 121.4,
+        // TODO: placeholder
+        drag: 0.0,
+        turn_speed: 2.0,
+        roc: 2000.0,
+        rod: 2500.0,
+        max_altitude: 41000.0,
+        min_speed: 140.0,
+        max_speed: 450.0,
+        v2: 150.0,
+        takeoff_length: 7600.0,
+        landing_length: 5400.0,
+        max_takeoff_weight: 174200.0,
+        max_landing_weight: 146300.0,
+        dry_weight: 91300.0,
+        fuel_capacity: 46000.0,
+        seats: 175,
+        taxi_fuel_flow: 1000.0,
+        climb_fuel_flow: 8000.0,
+        cruise_fuel_flow: 5500.0,
+        hold_fuel_flow: 4500.0,
+      },
+      AircraftKind::B747 => AircraftStats {
+        thrust: 222.0,
+        // TODO: placeholder
+        drag: 0.0,
+        turn_speed: 2.0,
+        roc: 1500.0,
+        rod: 2500.0,
+        max_altitude: 45000.0,
+        min_speed: 150.0,
+        max_speed: 490.0,
+        v2: 160.0,
+        takeoff_length: 8800.0,
+        landing_length: 6600.0,
+        max_takeoff_weight: 696000.0,
+        max_landing_weight: 450000.0,
+        dry_weight: 325000.0,
+        fuel_capacity: 330000.0,
+        seats: 280,
+        taxi_fuel_flow: 3000.0,
+        climb_fuel_flow: 28000.0,
+        cruise_fuel_flow: 22000.0,
+        hold_fuel_flow: 17000.0,
+      },
+      AircraftKind::B77L => AircraftStats {
+        thrust: 489.0,
+        // TODO: placeholder
+        drag: 0.0,
+        turn_speed: 2.0,
+        roc: 1500.0,
+        rod: 2500.0,
+        max_altitude: 43100.0,
+        min_speed: 150.0,
+        max_speed: 490.0,
+        v2: 165.0,
+        takeoff_length: 9800.0,
+        landing_length: 5300.0,
+        max_takeoff_weight: 766000.0,
+        max_landing_weight: 492000.0,
+        dry_weight: 320000.0,
+        fuel_capacity: 325000.0,
+        seats: 320,
+        taxi_fuel_flow: 2500.0,
+        climb_fuel_flow: 26000.0,
+        cruise_fuel_flow: 16000.0,
+        hold_fuel_flow: 13000.0,
+      },
+      AircraftKind::CRJ7 => AircraftStats {
+        thrust: 61.3,
+        // TODO: placeholder
+        drag: 0.0,
+        turn_speed: 2.0,
+        roc: 2500.0,
+        rod: 2500.0,
+        max_altitude: 41000.0,
+        min_speed: 130.0,
+        max_speed: 470.0,
+        v2: 140.0,
+        takeoff_length: 5500.0,
+        landing_length: 5000.0,
+        max_takeoff_weight: 75000.0,
+        max_landing_weight: 67000.0,
+        dry_weight: 44245.0,
+        fuel_capacity: 19600.0,
+        seats: 70,
+        taxi_fuel_flow: 600.0,
+        climb_fuel_flow: 4500.0,
+        cruise_fuel_flow: 2900.0,
+        hold_fuel_flow: 2200.0,
+      },
+      AircraftKind::E170 => AircraftStats {
+        thrust: 62.3,
+        // TODO: placeholder
+        drag: 0.0,
+        turn_speed: 2.0,
+        roc: 2500.0,
+        rod: 2500.0,
+        max_altitude: 41000.0,
+        min_speed: 130.0,
+        max_speed: 470.0,
+        v2: 140.0,
+        takeoff_length: 5400.0,
+        landing_length: 4100.0,
+        max_takeoff_weight: 82000.0,
+        max_landing_weight: 72300.0,
+        dry_weight: 46700.0,
+        fuel_capacity: 20500.0,
+        seats: 76,
+        taxi_fuel_flow: 600.0,
+        climb_fuel_flow: 4500.0,
+        cruise_fuel_flow: 3000.0,
+        hold_fuel_flow: 2300.0,
       },
-      AircraftKind::A333 => todo!(),
-      AircraftKind::B737 => todo!(),
-      AircraftKind::B747 => todo!(),
-      AircraftKind::B77L => todo!(),
-      AircraftKind::CRJ7 => todo!(),
-      AircraftKind::E170 => todo!(),
     }
   }
 }
@@ -307,6 +488,173 @@ impl TCAS {
   }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "kebab-case")]
+#[ts(export)]
+pub enum Emergency {
+  /// An engine has failed, reducing climb performance, speed and ceiling.
+  EngineFailure,
+  /// The cabin has lost pressure and requires an emergency descent.
+  Decompression,
+  /// A passenger requires urgent medical attention.
+  Medical,
+  /// Fuel is low enough that no delays can be accepted.
+  MinimumFuel,
+  /// Fuel is low enough that the aircraft must land immediately.
+  EmergencyFuel,
+}
+
+impl Emergency {
+  /// Emergencies that can happen at random. Fuel emergencies are raised by
+  /// the aircraft's fuel state instead.
+  pub const RANDOM: [Self; 3] =
+    [Self::EngineFailure, Self::Decompression, Self::Medical];
+
+  /// The distress (MAYDAY) or urgency (PAN) prefix used when declaring the
+  /// emergency, if any.
+  pub fn prefix(&self) -> Option<&'static str> {
+    match self {
+      Self::EngineFailure | Self::Decompression | Self::EmergencyFuel => {
+        Some("Mayday, mayday, mayday")
+      }
+      Self::Medical => Some("Pan-pan, pan-pan, pan-pan"),
+      Self::MinimumFuel => None,
+    }
+  }
+}
+
+impl core::fmt::Display for Emergency {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    match self {
+      Self::EngineFailure => write!(f, "engine failure"),
+      Self::Decompression => write!(f, "rapid decompression"),
+      Self::Medical => write!(f, "medical emergency on board"),
+      Self::MinimumFuel => write!(f, "minimum fuel"),
+      Self::EmergencyFuel => write!(f, "fuel emergency"),
+    }
+  }
+}
+
+#[derive(
+  Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize, TS,
+)]
+#[serde(rename_all = "kebab-case")]
+#[ts(export)]
+pub enum TransponderMode {
+  /// Not replying to interrogations.
+  #[default]
+  Standby,
+  /// Replying with the code and pressure altitude.
+  ModeC,
+  /// Replying with the code, pressure altitude and callsign.
+  ModeS,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct Transponder {
+  /// Squawk code, stored as its octal value.
+  pub code: u16,
+  pub mode: TransponderMode,
+}
+
+impl Default for Transponder {
+  fn default() -> Self {
+    Self {
+      code: Self::CONSPICUITY,
+      mode: TransponderMode::Standby,
+    }
+  }
+}
+
+impl Transponder {
+  pub const CONSPICUITY: u16 = 0o2000;
+  pub const HIJACK: u16 = 0o7500;
+  pub const RADIO_FAILURE: u16 = 0o7600;
+  pub const EMERGENCY: u16 = 0o7700;
+
+  /// Whether a code is reserved and should never be assigned.
+  pub fn is_special(code: u16) -> bool {
+    matches!(
+      code,
+      Self::CONSPICUITY | Self::HIJACK | Self::RADIO_FAILURE | Self::EMERGENCY
+    )
+  }
+
+  pub fn is_active(&self) -> bool {
+    self.mode != TransponderMode::Standby
+  }
+
+  pub fn is_hijack(&self) -> bool {
+    self.code == Self::HIJACK
+  }
+
+  pub fn is_radio_failure(&self) -> bool {
+    self.code == Self::RADIO_FAILURE
+  }
+
+  pub fn is_emergency(&self) -> bool {
+    self.code == Self::EMERGENCY
+  }
+}
+
+impl core::fmt::Display for Transponder {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    write!(f, "{:04o}", self.code)
+  }
+}
+
+#[derive(
+  Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize, TS,
+)]
+#[ts(export)]
+pub struct Comms {
+  /// Seconds since a transmission was last heard on our frequency
+  pub silence: f32,
+  /// Tick at which we will begin our approach without a radio
+  pub approach_at: Option<usize>,
+}
+
+#[derive(
+  Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize, TS,
+)]
+#[ts(export)]
+pub struct Fuel {
+  /// Fuel on board in pounds
+  pub on_board: f32,
+  /// Fuel burned since the last block fuel plan in pounds
+  pub burned: f32,
+  /// Current fuel flow in pounds per hour
+  pub flow: f32,
+  /// Remaining endurance in minutes
+  pub endurance: f32,
+}
+
+impl Fuel {
+  /// Plans block fuel for a route of `distance` feet flown at `speed` knots,
+  /// including taxi, contingency and reserve fuel.
+  pub fn plan(stats: &AircraftStats, distance: f32, speed: f32) -> Self {
+    let trip_hours = distance / NAUTICALMILES_TO_FEET / speed.max(1.0);
+
+    let taxi = stats.taxi_fuel_flow * TAXI_FUEL_MINUTES / 60.0;
+    let trip = stats.cruise_fuel_flow * trip_hours;
+    // Roughly 20 minutes of climb at a higher fuel flow than cruise.
+    let climb = (stats.climb_fuel_flow - stats.cruise_fuel_flow) / 3.0;
+    let contingency = trip * 0.05;
+    let reserve = stats.hold_fuel_flow * RESERVE_FUEL_MINUTES / 60.0;
+
+    let on_board =
+      (taxi + trip + climb + contingency + reserve).min(stats.fuel_capacity);
+
+    Self {
+      on_board,
+      burned: 0.0,
+      flow: 0.0,
+      endurance: on_board / stats.hold_fuel_flow * 60.0,
+    }
+  }
+}
+
 #[derive(Debug, Clone, PartialEq, Default)]
 pub struct SeparationMinima {
   pub separation_distance: f32,
@@ -336,6 +684,7 @@ impl SeparationMinima {
 pub struct Aircraft {
   #[ts(as = "String")]
   pub id: Intern<String>,
+  pub kind: AircraftKind,
 
   #[ts(as = "(f32, f32)")]
   pub pos: Vec2,
@@ -346,6 +695,10 @@ pub struct Aircraft {
   pub state: AircraftState,
   pub target: AircraftTargets,
   pub tcas: TCAS,
+  pub emergency: Option<Emergency>,
+  pub transponder: Transponder,
+  pub comms: Comms,
+  pub fuel: Fuel,
   pub flight_plan: FlightPlan,
 
   pub frequency: f32,
@@ -362,6 +715,24 @@ impl Aircraft {
     matches!(self.state, AircraftState::Parked { .. })
   }
 
+  /// Whether the aircraft has lost its radio and reached the time at which it
+  /// should begin its approach.
+  pub fn lost_comms_approach(&self, tick: usize) -> bool {
+    self.transponder.is_radio_failure()
+      && self.comms.approach_at.is_some_and(|t| tick >= t)
+  }
+
+  /// Whether the aircraft is being delayed on arrival, either by holding off
+  /// course or by being vectored off its route.
+  pub fn is_holding(&self) -> bool {
+    matches!(self.state, AircraftState::Flying)
+      && matches!(
+        self.segment,
+        FlightSegment::Arrival | FlightSegment::Approach
+      )
+      && (!self.flight_plan.follow || self.flight_plan.course_offset != 0.0)
+  }
+
   pub fn sync_targets_to_vals(&mut self) {
     self.target.heading = self.heading;
     self.target.speed = self.speed;
@@ -374,24 +745,14 @@ impl Aircraft {
   }
 
   pub fn random_callsign(rng: &mut Rng) -> String {
-    let mut string = String::new();
-    let airlines = ["AAL", "SKW", "JBU", "DAL", "UAL", "BAW", "SWA"];
-
-    let airline = rng.sample(&airlines).unwrap();
-
-    string.push_str(airline);
-
-    string.push_str(&rng.sample_iter(0..=9).unwrap().to_string());
-    string.push_str(&rng.sample_iter(0..=9).unwrap().to_string());
-    string.push_str(&rng.sample_iter(0..=9).unwrap().to_string());
-    string.push_str(&rng.sample_iter(0..=9).unwrap().to_string());
-
-    string
+    registry().random_callsign(rng, None).0
   }
 
   pub fn random_dormant(gate: &Gate, rng: &mut Rng, airport: &Airport) -> Self {
+    let (callsign, kind) = registry().random_callsign(rng, Some(airport.id));
     Self {
-      id: Intern::from(Self::random_callsign(rng)),
+      id: Intern::from(callsign),
+      kind,
 
       pos: gate.pos,
       speed: 0.0,
@@ -405,6 +766,10 @@ impl Aircraft {
         Intern::from(String::new()),
       ),
       tcas: TCAS::default(),
+      emergency: None,
+      transponder: Transponder::default(),
+      comms: Comms::default(),
+      fuel: Fuel::default(),
 
       frequency: airport.frequencies.ground,
       segment: FlightSegment::Dormant,
@@ -415,6 +780,54 @@ impl Aircraft {
     .with_synced_targets()
   }
 
+  /// Creates an arrival `distance` outside of the destination's airspace,
+  /// inbound from the origin on a STAR that enters the airspace at its
+  /// boundary.
+  pub fn arriving(
+    id: Intern<String>,
+    kind: AircraftKind,
+    origin: &Airport,
+    destination: &Airport,
+    distance: f32,
+  ) -> Self {
+    let bearing = angle_between_points(destination.center, origin.center);
+    let pos =
+      move_point(destination.center, bearing, AIRSPACE_RADIUS + distance);
+    let entry = new_vor(
+      Intern::from_ref("STAR"),
+      move_point(destination.center, bearing, AIRSPACE_RADIUS),
+    )
+    .with_limits(
+      VORLimits::new()
+        .with_altitude(VORLimit::AtOrBelow(ARRIVAL_ALTITUDE))
+        .with_speed(VORLimit::AtOrBelow(250.0)),
+    );
+
+    let mut aircraft = Self {
+      id,
+      kind,
+      pos,
+      speed: 250.0,
+      heading: angle_between_points(pos, destination.center),
+      altitude: ARRIVAL_ALTITUDE,
+      state: AircraftState::Flying,
+      flight_plan: FlightPlan::new(origin.id, destination.id)
+        .with_waypoints(vec![entry]),
+      frequency: destination.frequencies.center,
+      segment: FlightSegment::Arrival,
+      ..Default::default()
+    }
+    .with_synced_targets();
+    aircraft.fuel = Fuel::plan(
+      &aircraft.stats(),
+      pos.distance(destination.center),
+      aircraft.speed,
+    );
+    aircraft.transponder.mode = TransponderMode::ModeS;
+
+    aircraft
+  }
+
   pub fn flip_flight_plan(&mut self) {
     if self
       .airspace
@@ -436,10 +849,39 @@ impl Aircraft {
       .iter()
       .find(|a| self.airspace.is_some_and(|id| a.id == id))
   }
+
+  /// Resolves a named frequency ("tower", "departure", ...) at the
+  /// aircraft's current airport. Leaving the airport it departed from,
+  /// departure is the one given in its clearance.
+  pub fn named_frequency(
+    &self,
+    name: &str,
+    airports: &[Airport],
+  ) -> Option<f32> {
+    match (name, &self.flight_plan.clearance) {
+      ("departure", Some(clearance))
+        if self.airspace == Some(self.flight_plan.departing) =>
+      {
+        Some(clearance.departure_frequency)
+      }
+      _ => self
+        .find_airport(airports)
+        .and_then(|x| x.frequencies.try_from_string(name)),
+    }
+  }
 }
 
 // Performance stats
 impl Aircraft {
+  pub fn stats(&self) -> AircraftStats {
+    let stats = self.kind.stats();
+    if let Some(emergency) = self.emergency {
+      stats.degraded(emergency)
+    } else {
+      stats
+    }
+  }
+
   pub fn separation_minima(&self) -> SeparationMinima {
     let separation_distance = NAUTICALMILES_TO_FEET * 7.5;
     if matches!(self.segment, FlightSegment::Approach) {
@@ -467,12 +909,46 @@ impl Aircraft {
       0.0
     } else {
       // Flying
-      (2000.0_f32 / 60.0_f32).round()
+      (2000.0_f32 / 60.0_f32).round() * self.stats().roc
+        / AircraftKind::A21N.stats().roc
+    }
+  }
+
+  pub fn descent_speed(&self) -> f32 {
+    if self.speed < 140.0 {
+      0.0
+    } else {
+      (2000.0_f32 / 60.0_f32).round() * self.stats().rod
+        / AircraftKind::A21N.stats().rod
     }
   }
 
   pub fn turn_speed(&self) -> f32 {
-    AircraftKind::A21N.stats().turn_speed
+    self.stats().turn_speed
+  }
+
+  /// Current fuel flow in pounds per hour.
+  pub fn fuel_flow(&self) -> f32 {
+    let stats = self.stats();
+    match self.segment {
+      FlightSegment::Unknown
+      | FlightSegment::Dormant
+      | FlightSegment::Boarding
+      | FlightSegment::Parked => 0.0,
+      FlightSegment::TaxiDep | FlightSegment::TaxiArr => stats.taxi_fuel_flow,
+      FlightSegment::Takeoff
+      | FlightSegment::Departure
+      | FlightSegment::Climb => stats.climb_fuel_flow,
+      FlightSegment::Cruise => stats.cruise_fuel_flow,
+      FlightSegment::Arrival | FlightSegment::Approach => {
+        if self.is_holding() {
+          stats.hold_fuel_flow
+        } else {
+          stats.cruise_fuel_flow
+        }
+      }
+      FlightSegment::Landing => stats.hold_fuel_flow,
+    }
   }
 
   pub fn speed_speed(&self) -> f32 {
@@ -5,6 +5,7 @@ use ts_rs::TS;
 
 use crate::{
   AIRSPACE_RADIUS,
+  geo::LatLon,
   geometry::{Translate, move_point},
   line::Line,
   pathfinder::{Object, Pathfinder},
@@ -18,6 +19,10 @@ pub struct Frequencies {
   pub tower: f32,
   pub ground: f32,
   pub center: f32,
+  /// Clearance delivery, if the airport has it. Otherwise, ground delivers
+  /// clearances.
+  #[serde(default)]
+  pub clearance: Option<f32>,
 }
 
 impl Default for Frequencies {
@@ -28,11 +33,16 @@ impl Default for Frequencies {
       tower: 118.5,
       ground: 118.5,
       center: 118.5,
+      clearance: None,
     }
   }
 }
 
 impl Frequencies {
+  pub fn clearance_delivery(&self) -> f32 {
+    self.clearance.unwrap_or(self.ground)
+  }
+
   pub fn try_from_string(&self, s: &str) -> Option<f32> {
     match s {
       "approach" => Some(self.approach),
@@ -40,6 +50,7 @@ impl Frequencies {
       "tower" => Some(self.tower),
       "ground" => Some(self.ground),
       "center" => Some(self.center),
+      "clearance" => Some(self.clearance_delivery()),
 
       _ => None,
     }
@@ -55,6 +66,10 @@ pub struct Airport {
 
   #[ts(as = "(f32, f32)")]
   pub center: Vec2,
+  /// Where `center` is on the earth, for placing the airport among other
+  /// real airports.
+  #[serde(default)]
+  pub location: Option<LatLon>,
   pub runways: Vec<Runway>,
   pub taxiways: Vec<Taxiway>,
   pub terminals: Vec<Terminal>,
@@ -90,6 +105,7 @@ impl Airport {
     Self {
       id,
       center,
+      location: None,
       runways: Vec::new(),
       taxiways: Vec::new(),
       terminals: Vec::new(),
@@ -159,6 +175,11 @@ impl Runway {
   }
 }
 
+/// The most a rounded bend turns between the points it is drawn and followed
+/// through, in degrees.
+const BEND_STEP: f32 = 10.0;
+
+/// A taxiway from `a` to `b`, which may bend through `via` on the way.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export)]
 pub struct Taxiway {
@@ -168,27 +189,135 @@ pub struct Taxiway {
   pub a: Vec2,
   #[ts(as = "(f32, f32)")]
   pub b: Vec2,
+  /// Points between `a` and `b` that the taxiway bends at.
+  #[serde(default)]
+  #[ts(as = "Vec<(f32, f32)>")]
+  pub via: Vec<Vec2>,
+  /// The radius that bends are rounded off to, in feet. Bends are sharp
+  /// corners if this is zero.
+  #[serde(default)]
+  pub radius: f32,
 }
 
 impl Translate for Taxiway {
   fn translate(&mut self, offset: Vec2) -> &mut Self {
     self.a += offset;
     self.b += offset;
+    for point in self.via.iter_mut() {
+      *point += offset;
+    }
     self
   }
 }
 
 impl Taxiway {
   pub fn new(id: Intern<String>, a: Vec2, b: Vec2) -> Self {
-    Self { id, a, b }
+    Self {
+      id,
+      a,
+      b,
+      via: Vec::new(),
+      radius: 0.0,
+    }
+  }
+
+  pub fn with_via(mut self, via: Vec<Vec2>) -> Self {
+    self.via = via;
+    self
+  }
+
+  pub fn with_radius(mut self, radius: f32) -> Self {
+    self.radius = radius;
+    self
   }
 
   pub fn extend_ends_by(mut self, padding: f32) -> Self {
-    self.a = self.a.move_towards(self.b, -padding);
-    self.b = self.b.move_towards(self.a, -padding);
+    let after_a = self.via.first().copied().unwrap_or(self.b);
+    let before_b = self.via.last().copied().unwrap_or(self.a);
+    self.a = self.a.move_towards(after_a, -padding);
+    self.b = self.b.move_towards(before_b, -padding);
 
     self
   }
+
+  /// The points the taxiway runs through from `a` to `b`, with bends
+  /// rounded off into arcs.
+  pub fn path(&self) -> Vec<Vec2> {
+    let corners: Vec<Vec2> = core::iter::once(self.a)
+      .chain(self.via.iter().copied())
+      .chain(core::iter::once(self.b))
+      .collect();
+
+    let mut path = vec![self.a];
+    for window in corners.windows(3) {
+      let [before, corner, after] = [window[0], window[1], window[2]];
+      path.extend(round_bend(before, corner, after, self.radius));
+    }
+    path.push(self.b);
+
+    path
+  }
+
+  /// The straight lines that make up the taxiway.
+  pub fn segments(&self) -> Vec<Line> {
+    self
+      .path()
+      .windows(2)
+      .map(|w| Line::new(w[0], w[1]))
+      .collect()
+  }
+
+  /// Whether the taxiway is more than a single straight line.
+  pub fn is_bent(&self) -> bool {
+    !self.via.is_empty()
+  }
+}
+
+/// Points along an arc of `radius` that rounds off the corner between two
+/// lines, or just the corner if it can't be rounded.
+fn round_bend(
+  before: Vec2,
+  corner: Vec2,
+  after: Vec2,
+  radius: f32,
+) -> Vec<Vec2> {
+  let (Some(to_before), Some(to_after)) = (
+    (before - corner).try_normalize(),
+    (after - corner).try_normalize(),
+  ) else {
+    return vec![corner];
+  };
+
+  // The angle inside the corner, which is close to 180 degrees for a gentle
+  // bend.
+  let inside = to_before.angle_to(to_after).abs();
+  if radius <= 0.0
+    || inside < 1.0_f32.to_radians()
+    || inside > 179.0_f32.to_radians()
+  {
+    return vec![corner];
+  }
+
+  // Don't let the arc eat more than half of either line.
+  let half = inside * 0.5;
+  let tangent = (radius / half.tan())
+    .min(corner.distance(before) * 0.5)
+    .min(corner.distance(after) * 0.5);
+  let radius = tangent * half.tan();
+
+  let start = corner + to_before * tangent;
+  let end = corner + to_after * tangent;
+  let center =
+    corner + (to_before + to_after).normalize() * (radius / half.sin());
+
+  let sweep = (start - center).angle_to(end - center);
+  let steps = (sweep.abs().to_degrees() / BEND_STEP).ceil().max(1.0) as usize;
+  (0..=steps)
+    .map(|i| {
+      let angle = sweep * i as f32 / steps as f32;
+      center + Vec2::from_angle(angle).rotate(start - center)
+    })
+    .collect()
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
@@ -1,13 +1,16 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use glam::Vec2;
 use internment::Intern;
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 
-use crate::{AIRSPACE_RADIUS, pathfinder::Node};
+use crate::{AIRSPACE_RADIUS, economy::Economy, pathfinder::Node};
 
-use super::{aircraft::Aircraft, airport::Airport};
+use super::{
+  aircraft::{Aircraft, Transponder},
+  airport::{Airport, Runway},
+};
 
 pub fn calculate_airport_waypoints(airports: &mut [Airport]) {
   for airport in airports.iter_mut() {
@@ -45,6 +48,11 @@ impl AirportStatus {
     }
   }
 
+  /// Whether a player controls any part of the airport.
+  pub fn is_controlled(&self) -> bool {
+    !self.automate_air || !self.automate_ground
+  }
+
   pub fn all_diverted() -> Self {
     Self {
       divert_arrivals: true,
@@ -55,6 +63,17 @@ impl AirportStatus {
   }
 }
 
+#[derive(
+  Debug, Copy, Clone, Default, PartialEq, Serialize, Deserialize, TS,
+)]
+#[ts(export)]
+pub struct AirportStats {
+  /// Total fuel burned within the airspace in pounds
+  pub fuel_burned: f32,
+  /// Fuel burned while holding or being vectored for delay in pounds
+  pub holding_fuel: f32,
+}
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
 #[ts(export)]
 pub struct World {
@@ -63,6 +82,14 @@ pub struct World {
   pub waypoints: Vec<Node<Vec2>>,
   #[ts(as = "HashMap<String, AirportStatus>")]
   pub airport_statuses: HashMap<Intern<String>, AirportStatus>,
+  /// Runways that are closed, by airport and runway ID.
+  #[serde(default)]
+  #[ts(as = "Vec<(String, String)>")]
+  pub closed_runways: HashSet<(Intern<String>, Intern<String>)>,
+  /// Waypoints that have failed and can't be navigated to, by name.
+  #[serde(default)]
+  #[ts(as = "Vec<String>")]
+  pub failed_navaids: HashSet<Intern<String>>,
 }
 
 impl World {
@@ -96,10 +123,99 @@ impl World {
   pub fn airport(&self, airport_id: Intern<String>) -> Option<&Airport> {
     self.airports.iter().find(|a| a.id == airport_id)
   }
+
+  pub fn is_runway_closed(
+    &self,
+    airport_id: Intern<String>,
+    runway_id: Intern<String>,
+  ) -> bool {
+    self.closed_runways.contains(&(airport_id, runway_id))
+  }
+
+  pub fn is_navaid_failed(&self, name: Intern<String>) -> bool {
+    self.failed_navaids.contains(&name)
+  }
+
+  /// Finds the runway closest to a point that is at least `min_length` feet
+  /// long, along with the airport it belongs to.
+  pub fn nearest_suitable_runway(
+    &self,
+    point: Vec2,
+    min_length: f32,
+  ) -> Option<(&Airport, &Runway)> {
+    self
+      .airports
+      .iter()
+      .flat_map(|a| a.runways.iter().map(move |r| (a, r)))
+      .filter(|(a, r)| {
+        r.length >= min_length && !self.is_runway_closed(a.id, r.id)
+      })
+      .min_by(|(_, a), (_, b)| {
+        a.start
+          .distance_squared(point)
+          .partial_cmp(&b.start.distance_squared(point))
+          .unwrap_or(std::cmp::Ordering::Equal)
+      })
+  }
+}
+
+/// A block of discrete squawk codes handed out by an airport.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SquawkPool {
+  start: u16,
+  next: u16,
+}
+
+impl SquawkPool {
+  /// Number of codes in each block.
+  pub const SIZE: u16 = 0o100;
+  /// Number of blocks available, from 0100 to 7477.
+  pub const BLOCKS: u16 = 0o74;
+
+  pub fn new(block: usize) -> Self {
+    Self {
+      start: (block as u16 % Self::BLOCKS + 1) * Self::SIZE,
+      next: 0,
+    }
+  }
+
+  /// Assigns the next free code in the block, skipping any that are in use.
+  pub fn assign(&mut self, in_use: &HashSet<u16>) -> Option<u16> {
+    for i in 0..Self::SIZE {
+      let offset = (self.next + i) % Self::SIZE;
+      let code = self.start + offset;
+      if !Transponder::is_special(code) && !in_use.contains(&code) {
+        self.next = (offset + 1) % Self::SIZE;
+        return Some(code);
+      }
+    }
+
+    None
+  }
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Game {
   pub aircraft: Vec<Aircraft>,
   pub paused: bool,
+  pub airport_stats: HashMap<Intern<String>, AirportStats>,
+  pub squawk_pools: HashMap<Intern<String>, SquawkPool>,
+  pub separation_losses: HashSet<(Intern<String>, Intern<String>)>,
+  pub economies: HashMap<Intern<String>, Economy>,
+  /// Templates built at each airport, by airport and template ID.
+  pub built: HashSet<(Intern<String>, Intern<String>)>,
+}
+
+impl Game {
+  pub fn airport_stats(&self, airport_id: Intern<String>) -> AirportStats {
+    self
+      .airport_stats
+      .get(&airport_id)
+      .copied()
+      .unwrap_or_default()
+  }
+
+  pub fn economy(&self, airport_id: Intern<String>) -> Economy {
+    self.economies.get(&airport_id).copied().unwrap_or_default()
+  }
 }
@@ -0,0 +1,81 @@
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// WGS 84 semi-major axis in meters.
+const EARTH_RADIUS_EQUATOR: f64 = 6_378_137.0;
+/// WGS 84 first eccentricity squared.
+const EARTH_ECCENTRICITY_SQ: f64 = 6.694_379_990_14e-3;
+const METERS_TO_FEET: f64 = 3.280_839_895;
+
+/// A position on the earth in degrees.
+#[derive(
+  Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize, TS,
+)]
+#[ts(export)]
+pub struct LatLon {
+  pub lat: f64,
+  pub lon: f64,
+}
+
+impl LatLon {
+  pub fn new(lat: f64, lon: f64) -> Self {
+    Self { lat, lon }
+  }
+
+  /// Earth-centered, earth-fixed coordinates in meters, at sea level.
+  fn ecef(&self) -> [f64; 3] {
+    let (lat, lon) = (self.lat.to_radians(), self.lon.to_radians());
+    let n = EARTH_RADIUS_EQUATOR
+      / (1.0 - EARTH_ECCENTRICITY_SQ * lat.sin().powi(2)).sqrt();
+
+    [
+      n * lat.cos() * lon.cos(),
+      n * lat.cos() * lon.sin(),
+      n * (1.0 - EARTH_ECCENTRICITY_SQ) * lat.sin(),
+    ]
+  }
+
+  /// Projects the position onto a plane tangent to the earth at `origin`,
+  /// in feet east (x) and north (y) of it.
+  ///
+  /// Distortion stays well under a percent within a few hundred miles of the
+  /// origin, which covers a region of neighbouring airports.
+  pub fn project(&self, origin: LatLon) -> Vec2 {
+    let [x, y, z] = self.ecef();
+    let [ox, oy, oz] = origin.ecef();
+    let (dx, dy, dz) = (x - ox, y - oy, z - oz);
+    let (lat, lon) = (origin.lat.to_radians(), origin.lon.to_radians());
+
+    let east = -lon.sin() * dx + lon.cos() * dy;
+    let north =
+      -lat.sin() * lon.cos() * dx - lat.sin() * lon.sin() * dy + lat.cos() * dz;
+
+    Vec2::new(
+      (east * METERS_TO_FEET) as f32,
+      (north * METERS_TO_FEET) as f32,
+    )
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::NAUTICALMILES_TO_FEET;
+
+  #[test]
+  fn test_project() {
+    let ksfo = LatLon::new(37.6189, -122.3750);
+    assert_eq!(ksfo.project(ksfo), Vec2::ZERO);
+
+    // One minute of latitude is roughly a nautical mile.
+    let north = LatLon::new(ksfo.lat + 1.0 / 60.0, ksfo.lon).project(ksfo);
+    assert!(north.x.abs() < 1.0);
+    assert!((north.y / NAUTICALMILES_TO_FEET - 1.0).abs() < 0.01);
+
+    // KOAK is about 9.5nm to the north-east.
+    let koak = LatLon::new(37.7213, -122.2208).project(ksfo);
+    assert!(koak.x > 0.0 && koak.y > 0.0);
+    assert!((koak.length() / NAUTICALMILES_TO_FEET - 9.5).abs() < 0.5);
+  }
+}
@@ -4,14 +4,20 @@ use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 
+pub mod airlines;
 pub mod assets;
 pub mod command;
 pub mod compile;
+pub mod coordination;
+pub mod economy;
 pub mod engine;
 pub mod entities;
+pub mod geo;
 pub mod geometry;
 pub mod line;
 pub mod pathfinder;
+pub mod scoring;
+pub mod validate;
 pub mod wayfinder;
 pub mod wordify;
 
@@ -39,8 +45,18 @@ pub const WEST_CRUISE_ALTITUDE: f32 = 38000.0;
 pub const TRANSITION_ALTITUDE: f32 = 18000.0;
 pub const ARRIVAL_ALTITUDE: f32 = 10000.0;
 pub const APPROACH_ALTITUDE: f32 = 3000.0;
+pub const EMERGENCY_DESCENT_ALTITUDE: f32 = 10000.0;
 
 pub const MAX_TAXI_SPEED: f32 = 20.0;
+/// How far ahead of a turn taxiing aircraft start to turn, in feet.
+pub const TAXI_TURN_LOOKAHEAD: f32 = 100.0;
+
+pub const TAXI_FUEL_MINUTES: f32 = 15.0;
+pub const RESERVE_FUEL_MINUTES: f32 = 45.0;
+pub const MINIMUM_FUEL_MINUTES: f32 = 30.0;
+pub const EMERGENCY_FUEL_MINUTES: f32 = 20.0;
+
+pub const LOST_COMMS_TIMEOUT_SECONDS: f32 = 300.0;
 
 #[derive(Serialize, Deserialize, TS)]
 #[ts(export)]
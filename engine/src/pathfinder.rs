@@ -1,7 +1,10 @@
+use std::collections::HashMap;
+
 use glam::Vec2;
 use internment::Intern;
 use petgraph::{
-  Graph, Undirected, algo::simple_paths, visit::IntoNodeReferences,
+  Graph, Undirected, algo::simple_paths, graph::NodeIndex,
+  visit::IntoNodeReferences,
 };
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
@@ -222,6 +225,76 @@ impl From<Object> for Line {
   }
 }
 
+impl Object {
+  /// The straight lines that make up the object, where it can be crossed.
+  pub fn segments(&self) -> Vec<Line> {
+    match self {
+      Object::Taxiway(value) => value.segments(),
+      _ => vec![self.into()],
+    }
+  }
+}
+
+/// Everywhere two objects cross. A bent taxiway can cross the same object
+/// more than once, and a crossing at the corner between two of its segments
+/// is only counted once.
+fn find_object_intersections(a: &Object, b: &Object) -> Vec<Vec2> {
+  let b = b.segments();
+  let mut intersections: Vec<Vec2> = Vec::new();
+  for a in a.segments() {
+    for intersection in b.iter().filter_map(|b| find_line_intersection(a, *b)) {
+      if !intersections.iter().any(|i| i.distance(intersection) < 1.0) {
+        intersections.push(intersection);
+      }
+    }
+  }
+
+  intersections
+}
+
+/// The points that a path along `path` from `from` to `to` bends through,
+/// in order.
+fn points_between(path: &[Vec2], from: Vec2, to: Vec2) -> Vec<Vec2> {
+  // How far along the path a point is.
+  let mut lengths = Vec::with_capacity(path.len());
+  let mut length = 0.0;
+  for (i, point) in path.iter().enumerate() {
+    if i > 0 {
+      length += path[i - 1].distance(*point);
+    }
+    lengths.push(length);
+  }
+
+  let along = |pos: Vec2| {
+    path
+      .windows(2)
+      .zip(lengths.iter())
+      .map(|(w, start)| {
+        let line = w[1] - w[0];
+        let t = ((pos - w[0]).dot(line)
+          / line.length_squared().max(f32::EPSILON))
+        .clamp(0.0, 1.0);
+        (pos.distance(w[0] + line * t), start + line.length() * t)
+      })
+      .min_by(|a, b| a.0.total_cmp(&b.0))
+      .map(|(_, along)| along)
+      .unwrap_or_default()
+  };
+
+  let (from, to) = (along(from), along(to));
+  let mut points: Vec<Vec2> = path
+    .iter()
+    .zip(lengths.iter())
+    .filter(|(_, l)| **l > from.min(to) && **l < from.max(to))
+    .map(|(p, _)| *p)
+    .collect();
+  if to < from {
+    points.reverse();
+  }
+
+  points
+}
+
 impl From<Object> for Node<Line> {
   fn from(value: Object) -> Self {
     match value {
@@ -258,6 +331,16 @@ pub fn total_distance_squared(path: &[Node<Vec2>], current_pos: Vec2) -> f32 {
   distance
 }
 
+/// The number of objects a path goes onto, not counting the points it bends
+/// through along each of them.
+fn legs(path: &[Node<Vec2>]) -> usize {
+  path
+    .iter()
+    .enumerate()
+    .filter(|(i, n)| *i == 0 || !path[i - 1].name_and_kind_eq(*n))
+    .count()
+}
+
 pub fn display_node_vec2<T>(n: &Node<T>) -> String {
   let exclamation = if n.behavior == NodeBehavior::Park
     || n.behavior == NodeBehavior::HoldShort
@@ -297,12 +380,18 @@ pub struct PathfinderPath {
 #[derive(Debug, Clone, Default)]
 pub struct Pathfinder {
   pub graph: WaypointGraph,
+  /// The path along each taxiway node that bends, so that aircraft can
+  /// follow it between the places it is entered and left. Keyed by node
+  /// rather than by name, since a taxiway can have several branches that
+  /// share a name.
+  pub bends: HashMap<NodeIndex, Vec<Vec2>>,
 }
 
 impl Pathfinder {
   pub fn new() -> Self {
     Self {
       graph: WaypointGraph::new_undirected(),
+      bends: HashMap::new(),
     }
   }
 
@@ -313,25 +402,37 @@ impl Pathfinder {
       return;
     }
 
-    while let Some(current) = segments.pop() {
-      let current_node = graph
+    let mut bends = HashMap::new();
+    let mut find_or_add = |graph: &mut WaypointGraph, object: &Object| {
+      graph
         .node_references()
-        .find(|(_, n)| **n == Node::from(current.clone()))
+        .find(|(_, n)| **n == Node::from(object.clone()))
         .map(|(i, _)| i)
-        .unwrap_or_else(|| graph.add_node(current.clone().into()));
+        .unwrap_or_else(|| {
+          let index = graph.add_node(object.clone().into());
+          if let Object::Taxiway(taxiway) = object
+            && taxiway.is_bent()
+          {
+            bends.insert(index, taxiway.path());
+          }
 
-      for segment in segments.iter() {
-        let line: Line = segment.into();
+          index
+        })
+    };
 
-        let intersection = find_line_intersection(line, current.clone().into());
-        if let Some(intersection) = intersection {
-          let segment_node = graph
-            .node_references()
-            .find(|(_, n)| **n == Node::from(segment.clone()))
-            .map(|(i, _)| i)
-            .unwrap_or_else(|| graph.add_node(segment.clone().into()));
+    while let Some(current) = segments.pop() {
+      let current_node = find_or_add(&mut graph, &current);
 
-          graph.add_edge(current_node, segment_node, intersection);
+      for segment in segments.iter() {
+        let intersections = find_object_intersections(segment, &current);
+        if !intersections.is_empty() {
+          let segment_node = find_or_add(&mut graph, segment);
+
+          // Each crossing gets its own edge, so that a taxiway that crosses
+          // another twice can be turned off of at either.
+          for intersection in intersections {
+            graph.add_edge(current_node, segment_node, intersection);
+          }
         }
       }
 
@@ -347,6 +448,7 @@ impl Pathfinder {
     }
 
     self.graph = graph;
+    self.bends = bends;
   }
 
   pub fn path_to(
@@ -393,14 +495,30 @@ impl Pathfinder {
         .map(|path| {
           let mut waypoints: Vec<Node<Vec2>> = Vec::with_capacity(path.len());
 
+          let mut entered = pos;
           let mut first = path.first().unwrap();
           for next in path.iter().skip(1) {
+            // If the objects cross more than once, turn at the crossing
+            // closest to where we entered.
             let edge = self
               .graph
               .edges_connecting(first.0, next.0)
-              .next()
-              .unwrap()
-              .weight();
+              .map(|e| e.weight())
+              .min_by(|a, b| {
+                a.distance_squared(entered)
+                  .total_cmp(&b.distance_squared(entered))
+              })
+              .unwrap();
+
+            // Follow any bends along the way to the next object.
+            if let Some(bends) = self.bends.get(&first.0) {
+              waypoints.extend(
+                points_between(bends, entered, *edge).into_iter().map(|p| {
+                  Node::new(first.1.name, first.1.kind, NodeBehavior::GoTo, p)
+                }),
+              );
+            }
+            entered = *edge;
 
             waypoints.push(Node::new(
               next.1.name,
@@ -486,7 +604,7 @@ impl Pathfinder {
       //     .partial_cmp(&total_distance(b, pos))
       //     .unwrap()
       // });
-      paths.sort_by_key(|p| p.path.len());
+      paths.sort_by_key(|p| legs(&p.path));
 
       // for path in paths.iter() {
       //   println!(
@@ -642,6 +760,164 @@ mod tests {
       }
     }
 
+    #[test]
+    fn calculate_bent_taxiway() {
+      let mut pathfinder = Pathfinder::new();
+
+      // A turns north at (100, 0), and B only crosses it after the turn.
+      let taxiway_a = Taxiway::new(
+        Intern::from_ref("A"),
+        Vec2::new(0.0, 0.0),
+        Vec2::new(100.0, 100.0),
+      )
+      .with_via(vec![Vec2::new(100.0, 0.0)]);
+
+      let taxiway_b = Taxiway::new(
+        Intern::from_ref("B"),
+        Vec2::new(50.0, 50.0),
+        Vec2::new(150.0, 50.0),
+      );
+
+      pathfinder.calculate(vec![
+        Object::Taxiway(taxiway_a),
+        Object::Taxiway(taxiway_b),
+      ]);
+
+      let path = pathfinder
+        .path_to(
+          Node::new(
+            Intern::from_ref("A"),
+            NodeKind::Taxiway,
+            NodeBehavior::GoTo,
+            (),
+          ),
+          Node::new(
+            Intern::from_ref("B"),
+            NodeKind::Taxiway,
+            NodeBehavior::GoTo,
+            (),
+          ),
+          Vec2::new(0.0, 0.0),
+          90.0,
+        )
+        .unwrap();
+
+      // The aircraft follows A around its bend before turning onto B.
+      assert_eq!(path.path.len(), 2);
+      assert_eq!(path.path[0].name, Intern::from_ref("A"));
+      assert_eq!(path.path[0].data, Vec2::new(100.0, 0.0));
+      assert_eq!(path.path[1].name, Intern::from_ref("B"));
+      assert_eq!(path.path[1].data, Vec2::new(100.0, 50.0));
+    }
+
+    #[test]
+    fn calculate_bent_taxiway_branches() {
+      let mut pathfinder = Pathfinder::new();
+
+      // Two branches of A that bend differently, where only the first one
+      // crosses B.
+      let taxiway_a = Taxiway::new(
+        Intern::from_ref("A"),
+        Vec2::new(0.0, 0.0),
+        Vec2::new(100.0, 100.0),
+      )
+      .with_via(vec![Vec2::new(100.0, 0.0)]);
+
+      let taxiway_a_branch = Taxiway::new(
+        Intern::from_ref("A"),
+        Vec2::new(300.0, 0.0),
+        Vec2::new(400.0, 100.0),
+      )
+      .with_via(vec![Vec2::new(300.0, 100.0)]);
+
+      let taxiway_b = Taxiway::new(
+        Intern::from_ref("B"),
+        Vec2::new(50.0, 50.0),
+        Vec2::new(150.0, 50.0),
+      );
+
+      pathfinder.calculate(vec![
+        Object::Taxiway(taxiway_a),
+        Object::Taxiway(taxiway_a_branch),
+        Object::Taxiway(taxiway_b),
+      ]);
+
+      let path = pathfinder
+        .path_to(
+          Node::new(
+            Intern::from_ref("A"),
+            NodeKind::Taxiway,
+            NodeBehavior::GoTo,
+            (),
+          ),
+          Node::new(
+            Intern::from_ref("B"),
+            NodeKind::Taxiway,
+            NodeBehavior::GoTo,
+            (),
+          ),
+          Vec2::new(0.0, 0.0),
+          90.0,
+        )
+        .unwrap();
+
+      // The aircraft follows the bend of the branch it is on, not the other.
+      assert_eq!(path.path.len(), 2);
+      assert_eq!(path.path[0].data, Vec2::new(100.0, 0.0));
+      assert_eq!(path.path[1].name, Intern::from_ref("B"));
+      assert_eq!(path.path[1].data, Vec2::new(100.0, 50.0));
+    }
+
+    #[test]
+    fn calculate_taxiway_crossed_twice() {
+      let mut pathfinder = Pathfinder::new();
+
+      let taxiway_a = Taxiway::new(
+        Intern::from_ref("A"),
+        Vec2::new(0.0, 0.0),
+        Vec2::new(200.0, 0.0),
+      );
+
+      // B loops north and crosses A at both (50, 0) and (150, 0).
+      let taxiway_b = Taxiway::new(
+        Intern::from_ref("B"),
+        Vec2::new(50.0, -50.0),
+        Vec2::new(150.0, -50.0),
+      )
+      .with_via(vec![Vec2::new(50.0, 50.0), Vec2::new(150.0, 50.0)]);
+
+      pathfinder.calculate(vec![
+        Object::Taxiway(taxiway_a),
+        Object::Taxiway(taxiway_b),
+      ]);
+
+      assert_eq!(pathfinder.graph.edge_count(), 2);
+
+      let path = pathfinder
+        .path_to(
+          Node::new(
+            Intern::from_ref("A"),
+            NodeKind::Taxiway,
+            NodeBehavior::GoTo,
+            (),
+          ),
+          Node::new(
+            Intern::from_ref("B"),
+            NodeKind::Taxiway,
+            NodeBehavior::GoTo,
+            (),
+          ),
+          Vec2::new(120.0, 0.0),
+          90.0,
+        )
+        .unwrap();
+
+      // The crossing behind the aircraft is skipped for the one ahead.
+      assert_eq!(path.path.len(), 1);
+      assert_eq!(path.path[0].name, Intern::from_ref("B"));
+      assert_eq!(path.path[0].data, Vec2::new(150.0, 0.0));
+    }
+
     #[test]
     fn calculate_two_taxiways_2() {
       let mut pathfinder = Pathfinder::new();
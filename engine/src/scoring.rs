@@ -0,0 +1,324 @@
+use std::collections::HashMap;
+
+use internment::Intern;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::{
+  command::{CommandWithFreq, Task},
+  engine::Event,
+  entities::{
+    aircraft::{
+      FlightSegment,
+      events::{AircraftEvent, EventKind},
+    },
+    world::{Game, World},
+  },
+};
+
+/// Seconds a controller has to catch a readback error before it is missed.
+pub const READBACK_WINDOW_SECONDS: f32 = 30.0;
+
+pub const MOVEMENT_POINTS: f32 = 10.0;
+pub const SEPARATION_LOSS_PENALTY: f32 = 50.0;
+pub const RESOLUTION_ADVISORY_PENALTY: f32 = 100.0;
+pub const GO_AROUND_PENALTY: f32 = 20.0;
+pub const TAXI_STOP_PENALTY: f32 = 10.0;
+pub const DIVERSION_PENALTY: f32 = 30.0;
+/// Penalty for each minute of departure delay.
+pub const DELAY_PENALTY: f32 = 1.0;
+/// Penalty for each second a handoff takes beyond the grace period.
+pub const HANDOFF_PENALTY: f32 = 0.1;
+pub const HANDOFF_GRACE_SECONDS: f32 = 30.0;
+pub const READBACK_CAUGHT_POINTS: f32 = 5.0;
+pub const READBACK_MISSED_PENALTY: f32 = 20.0;
+
+#[derive(
+  Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize, TS,
+)]
+#[ts(export)]
+pub struct Scorecard {
+  pub score: f32,
+
+  pub departures: usize,
+  pub arrivals: usize,
+
+  pub separation_losses: usize,
+  pub resolution_advisories: usize,
+  pub go_arounds: usize,
+  pub taxi_stops: usize,
+  pub diversions: usize,
+
+  /// Total departure delay against the scheduled flight time in seconds
+  pub departure_delay: f32,
+  pub handoffs: usize,
+  /// Total time taken to complete handoffs in seconds
+  pub handoff_latency: f32,
+  /// Time spent on handoffs beyond the grace period in seconds
+  pub handoff_overrun: f32,
+  pub readback_errors: usize,
+  pub readback_errors_caught: usize,
+  pub readback_errors_missed: usize,
+}
+
+impl Scorecard {
+  fn calculate_score(&self) -> f32 {
+    (self.departures + self.arrivals) as f32 * MOVEMENT_POINTS
+      + self.readback_errors_caught as f32 * READBACK_CAUGHT_POINTS
+      - self.separation_losses as f32 * SEPARATION_LOSS_PENALTY
+      - self.resolution_advisories as f32 * RESOLUTION_ADVISORY_PENALTY
+      - self.go_arounds as f32 * GO_AROUND_PENALTY
+      - self.taxi_stops as f32 * TAXI_STOP_PENALTY
+      - self.diversions as f32 * DIVERSION_PENALTY
+      - self.departure_delay / 60.0 * DELAY_PENALTY
+      - self.handoff_overrun * HANDOFF_PENALTY
+      - self.readback_errors_missed as f32 * READBACK_MISSED_PENALTY
+  }
+
+  pub fn average_departure_delay(&self) -> f32 {
+    self.departure_delay / self.departures.max(1) as f32
+  }
+
+  pub fn average_handoff_latency(&self) -> f32 {
+    self.handoff_latency / self.handoffs.max(1) as f32
+  }
+}
+
+impl core::fmt::Display for Scorecard {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    write!(
+      f,
+      "score {:.0}: {} departures (avg. delay {:.0}s), {} arrivals, \
+       {} separation losses, {} RAs, {} go-arounds, {} taxi stops, \
+       {} diversions, {} handoffs (avg. {:.0}s), {}/{} readback errors caught",
+      self.score,
+      self.departures,
+      self.average_departure_delay(),
+      self.arrivals,
+      self.separation_losses,
+      self.resolution_advisories,
+      self.go_arounds,
+      self.taxi_stops,
+      self.diversions,
+      self.handoffs,
+      self.average_handoff_latency(),
+      self.readback_errors_caught,
+      self.readback_errors
+    )
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ScoreReport {
+  pub session: Scorecard,
+  #[ts(as = "HashMap<String, Scorecard>")]
+  pub airports: HashMap<Intern<String>, Scorecard>,
+}
+
+/// Scores controller performance at controlled airports from engine events
+/// and the commands issued by controllers.
+#[derive(Debug, Clone, Default)]
+pub struct Scoring {
+  pub report: ScoreReport,
+
+  /// Aircraft awaiting a handoff, with their airport and the tick it was due.
+  handoffs: HashMap<Intern<String>, (Intern<String>, usize)>,
+  /// Aircraft that misread an altitude, with their airport, the tick they
+  /// read it back and the altitude they were given.
+  readback_errors: HashMap<Intern<String>, (Intern<String>, usize, f32)>,
+}
+
+impl Scoring {
+  fn tally<F>(&mut self, airport: Intern<String>, f: F)
+  where
+    F: Fn(&mut Scorecard),
+  {
+    let session = &mut self.report.session;
+    f(session);
+    session.score = session.calculate_score();
+
+    let card = self.report.airports.entry(airport).or_default();
+    f(card);
+    card.score = card.calculate_score();
+  }
+
+  fn complete_handoff(&mut self, id: Intern<String>, tick: usize, tps: usize) {
+    if let Some((airport, due)) = self.handoffs.remove(&id) {
+      let latency = tick.saturating_sub(due) as f32 / tps as f32;
+      self.tally(airport, |c| {
+        c.handoffs += 1;
+        c.handoff_latency += latency;
+        c.handoff_overrun += (latency - HANDOFF_GRACE_SECONDS).max(0.0);
+      });
+    }
+  }
+
+  /// Records the events returned by a tick of the engine.
+  pub fn record_events(
+    &mut self,
+    events: &[Event],
+    game: &Game,
+    world: &World,
+    tick: usize,
+    tps: usize,
+  ) {
+    for event in events.iter() {
+      let Event::Aircraft(AircraftEvent { id, kind }) = event else {
+        continue;
+      };
+      let Some(aircraft) = game.aircraft.iter().find(|a| a.id == *id) else {
+        continue;
+      };
+      let Some(airport) = aircraft.airspace else {
+        continue;
+      };
+
+      let status = world.airport_status(airport);
+      match kind {
+        EventKind::SeparationLoss(..) if !status.automate_air => {
+          self.tally(airport, |c| c.separation_losses += 1);
+        }
+        EventKind::ResolutionAdvisory if !status.automate_air => {
+          self.tally(airport, |c| c.resolution_advisories += 1);
+        }
+        EventKind::GoAround if !status.automate_air => {
+          self.tally(airport, |c| c.go_arounds += 1);
+        }
+        EventKind::ResumeOwnNavigation { diversion: true }
+          if !status.automate_air =>
+        {
+          self.tally(airport, |c| c.diversions += 1);
+        }
+        EventKind::TaxiHold { and_state: false } if !status.automate_ground => {
+          self.tally(airport, |c| c.taxi_stops += 1);
+        }
+        EventKind::Segment(_, segment) => {
+          // Any change in segment ends a pending handoff.
+          self.complete_handoff(*id, tick, tps);
+
+          match segment {
+            FlightSegment::Takeoff if !status.automate_ground => {
+              let delay = aircraft
+                .flight_time
+                .map(|t| tick.saturating_sub(t) as f32 / tps as f32)
+                .unwrap_or_default();
+              self.tally(airport, |c| {
+                c.departures += 1;
+                c.departure_delay += delay;
+              });
+            }
+            // Tower hands departures to departure control.
+            FlightSegment::Departure if !status.automate_air => {
+              self.handoffs.insert(*id, (airport, tick));
+            }
+            // Tower hands arrivals to ground.
+            FlightSegment::TaxiArr if !status.automate_ground => {
+              self.tally(airport, |c| c.arrivals += 1);
+              self.handoffs.insert(*id, (airport, tick));
+            }
+            _ => {}
+          }
+        }
+        _ => {}
+      }
+    }
+
+    self.expire_readback_errors(tick, tps);
+  }
+
+  /// Records a command issued by a controller.
+  pub fn record_command(
+    &mut self,
+    command: &CommandWithFreq,
+    tick: usize,
+    tps: usize,
+  ) {
+    let id = Intern::from_ref(&command.id);
+    for task in command.tasks.iter() {
+      match task {
+        Task::Frequency(..) | Task::NamedFrequency(..) => {
+          self.complete_handoff(id, tick, tps);
+        }
+        // Re-issuing the altitude they were given corrects a misread one.
+        Task::Altitude(altitude) => {
+          let caught = self
+            .readback_errors
+            .get(&id)
+            .filter(|(_, _, intended)| intended == altitude)
+            .map(|(airport, ..)| *airport);
+          if let Some(airport) = caught {
+            self.readback_errors.remove(&id);
+            self.tally(airport, |c| c.readback_errors_caught += 1);
+          }
+        }
+        _ => {}
+      }
+    }
+  }
+
+  /// Forgets what is pending for an aircraft that has been removed.
+  pub fn forget(&mut self, id: Intern<String>) {
+    self.handoffs.remove(&id);
+    self.readback_errors.remove(&id);
+  }
+
+  /// Records that a pilot read back an altitude other than the one they were
+  /// given.
+  pub fn record_readback_error(
+    &mut self,
+    id: Intern<String>,
+    airport: Intern<String>,
+    tick: usize,
+    intended: f32,
+  ) {
+    self.tally(airport, |c| c.readback_errors += 1);
+    self.readback_errors.insert(id, (airport, tick, intended));
+  }
+
+  fn expire_readback_errors(&mut self, tick: usize, tps: usize) {
+    let window = (READBACK_WINDOW_SECONDS * tps as f32) as usize;
+    let expired: Vec<Intern<String>> = self
+      .readback_errors
+      .iter()
+      .filter(|(_, (_, t, _))| tick.saturating_sub(*t) > window)
+      .map(|(id, _)| *id)
+      .collect();
+
+    for id in expired {
+      if let Some((airport, ..)) = self.readback_errors.remove(&id) {
+        self.tally(airport, |c| c.readback_errors_missed += 1);
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::command::CommandReply;
+
+  use super::*;
+
+  #[test]
+  fn test_readback_error_caught_by_intended_altitude() {
+    let mut scoring = Scoring::default();
+    let (id, airport) = (Intern::from_ref("AAL1"), Intern::from_ref("KSFO"));
+    scoring.record_readback_error(id, airport, 0, 5000.0);
+
+    let altitude = |altitude| {
+      CommandWithFreq::new(
+        id.to_string(),
+        118.6,
+        CommandReply::Empty,
+        vec![Task::Altitude(altitude)],
+      )
+    };
+
+    // Some other altitude doesn't correct the error.
+    scoring.record_command(&altitude(6000.0), 1, 1);
+    assert_eq!(scoring.report.session.readback_errors_caught, 0);
+
+    scoring.record_command(&altitude(5000.0), 2, 1);
+    assert_eq!(scoring.report.session.readback_errors_caught, 1);
+  }
+}
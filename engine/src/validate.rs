@@ -0,0 +1,327 @@
+use std::collections::HashSet;
+
+use internment::Intern;
+use petgraph::{graph::NodeIndex, visit::Bfs};
+
+use crate::{
+  entities::airport::{Airport, Frequencies, Runway},
+  geometry::delta_angle,
+  pathfinder::NodeKind,
+};
+
+/// The VHF air band, in MHz.
+const AIR_BAND: core::ops::RangeInclusive<f32> = 118.0..=136.975;
+
+/// A problem with an airport's layout or setup.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AirportIssue {
+  /// More than one runway, taxiway or gate has this ID.
+  DuplicateId(Intern<String>),
+  /// A taxiway that doesn't cross anything else.
+  DisconnectedTaxiway(Intern<String>),
+  /// A gate that can't be taxied to from any runway.
+  UnreachableGate(Intern<String>),
+  /// A runway that no taxiway crosses, so it can't be entered or exited.
+  RunwayWithoutTaxiway(Intern<String>),
+  /// Opposite ends of a runway that don't line up.
+  MismatchedReciprocal(Intern<String>, Intern<String>),
+  /// A frequency outside of the air band.
+  InvalidFrequency(&'static str, f32),
+  /// Two positions share a frequency, so whoever holds one holds both.
+  SharedFrequency(&'static str, &'static str, f32),
+}
+
+impl AirportIssue {
+  /// Whether the airport will misbehave, rather than just be unusual.
+  pub fn is_error(&self) -> bool {
+    !matches!(self, Self::SharedFrequency(..))
+  }
+}
+
+impl core::fmt::Display for AirportIssue {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    match self {
+      Self::DuplicateId(id) => write!(f, "{id} is used more than once"),
+      Self::DisconnectedTaxiway(id) => {
+        write!(f, "taxiway {id} doesn't connect to anything")
+      }
+      Self::UnreachableGate(id) => {
+        write!(f, "gate {id} can't be reached from any runway")
+      }
+      Self::RunwayWithoutTaxiway(id) => {
+        write!(f, "runway {id} has no taxiway to enter or exit it")
+      }
+      Self::MismatchedReciprocal(a, b) => write!(
+        f,
+        "runways {a} and {b} don't have opposite headings and equal lengths"
+      ),
+      Self::InvalidFrequency(name, frequency) => {
+        write!(f, "{name} frequency {frequency} is outside of the air band")
+      }
+      Self::SharedFrequency(a, b, frequency) => {
+        write!(f, "{a} and {b} share frequency {frequency}")
+      }
+    }
+  }
+}
+
+/// The ID of the other end of a runway, such as "01L" for "19R".
+pub fn reciprocal_id(id: &str) -> Option<String> {
+  let split = id.find(|c: char| !c.is_ascii_digit()).unwrap_or(id.len());
+  let (number, side) = id.split_at(split);
+  let number: u8 = number.parse().ok().filter(|n| (1..=36).contains(n))?;
+  let side = match side {
+    "L" => "R",
+    "R" => "L",
+    "C" | "" => side,
+    _ => return None,
+  };
+
+  Some(format!("{:02}{side}", (number + 17) % 36 + 1))
+}
+
+impl Airport {
+  /// Checks the airport for problems. The airport's waypoints must already be
+  /// calculated.
+  pub fn validate(&self) -> Vec<AirportIssue> {
+    let mut issues = Vec::new();
+
+    issues.extend(
+      self
+        .duplicate_ids()
+        .into_iter()
+        .map(AirportIssue::DuplicateId),
+    );
+    issues.extend(self.graph_issues());
+    issues.extend(self.reciprocal_issues());
+    issues.extend(frequency_issues(&self.frequencies));
+
+    issues
+  }
+
+  fn duplicate_ids(&self) -> Vec<Intern<String>> {
+    let mut seen = HashSet::new();
+    let mut duplicates = Vec::new();
+    let ids = self
+      .runways
+      .iter()
+      .map(|r| r.id)
+      .chain(self.taxiways.iter().map(|t| t.id))
+      .chain(
+        self
+          .terminals
+          .iter()
+          .flat_map(|t| t.gates.iter().map(|g| g.id)),
+      );
+    for id in ids {
+      if !seen.insert(id) && !duplicates.contains(&id) {
+        duplicates.push(id);
+      }
+    }
+
+    duplicates
+  }
+
+  fn graph_issues(&self) -> Vec<AirportIssue> {
+    let graph = &self.pathfinder.graph;
+    let find = |id: Intern<String>, kind: NodeKind| -> Option<NodeIndex> {
+      graph
+        .node_indices()
+        .find(|i| graph[*i].name == id && graph[*i].kind == kind)
+    };
+
+    let mut issues = Vec::new();
+    for taxiway in self.taxiways.iter() {
+      let connected = find(taxiway.id, NodeKind::Taxiway)
+        .is_some_and(|i| graph.neighbors(i).next().is_some());
+      if !connected {
+        issues.push(AirportIssue::DisconnectedTaxiway(taxiway.id));
+      }
+    }
+
+    let mut from_runways: HashSet<NodeIndex> = HashSet::new();
+    for runway in self.runways.iter() {
+      let Some(node) = find(runway.id, NodeKind::Runway) else {
+        issues.push(AirportIssue::RunwayWithoutTaxiway(runway.id));
+        continue;
+      };
+      if !graph
+        .neighbors(node)
+        .any(|i| graph[i].kind == NodeKind::Taxiway)
+      {
+        issues.push(AirportIssue::RunwayWithoutTaxiway(runway.id));
+      }
+
+      let mut bfs = Bfs::new(graph, node);
+      while let Some(i) = bfs.next(graph) {
+        from_runways.insert(i);
+      }
+    }
+
+    for gate in self.terminals.iter().flat_map(|t| t.gates.iter()) {
+      let reachable = find(gate.id, NodeKind::Gate)
+        .is_some_and(|i| from_runways.contains(&i));
+      if !reachable {
+        issues.push(AirportIssue::UnreachableGate(gate.id));
+      }
+    }
+
+    issues
+  }
+
+  fn reciprocal_issues(&self) -> Vec<AirportIssue> {
+    let mut issues = Vec::new();
+    for (i, runway) in self.runways.iter().enumerate() {
+      let Some(reciprocal) = reciprocal_id(&runway.id)
+        .and_then(|id| self.runways.iter().skip(i + 1).find(|r| *r.id == id))
+      else {
+        continue;
+      };
+
+      if !is_reciprocal(runway, reciprocal) {
+        issues
+          .push(AirportIssue::MismatchedReciprocal(runway.id, reciprocal.id));
+      }
+    }
+
+    issues
+  }
+}
+
+fn is_reciprocal(a: &Runway, b: &Runway) -> bool {
+  delta_angle(a.heading, b.heading).abs() >= 179.0
+    && (a.length - b.length).abs() <= 1.0
+}
+
+fn frequency_issues(frequencies: &Frequencies) -> Vec<AirportIssue> {
+  // Grouped by the position that holds them.
+  let positions: [&[(&'static str, Option<f32>)]; 4] = [
+    &[
+      ("ground", Some(frequencies.ground)),
+      ("clearance", frequencies.clearance),
+    ],
+    &[("tower", Some(frequencies.tower))],
+    &[
+      ("approach", Some(frequencies.approach)),
+      ("departure", Some(frequencies.departure)),
+    ],
+    &[("center", Some(frequencies.center))],
+  ];
+
+  let mut issues = Vec::new();
+  for (i, position) in positions.iter().enumerate() {
+    for (name, frequency) in position.iter() {
+      let Some(frequency) = *frequency else {
+        continue;
+      };
+
+      if !AIR_BAND.contains(&frequency) {
+        issues.push(AirportIssue::InvalidFrequency(name, frequency));
+      }
+
+      for (other, other_frequency) in
+        positions.iter().skip(i + 1).flat_map(|p| p.iter())
+      {
+        if *other_frequency == Some(frequency) {
+          issues.push(AirportIssue::SharedFrequency(name, other, frequency));
+        }
+      }
+    }
+  }
+
+  issues
+}
+
+#[cfg(test)]
+mod tests {
+  use glam::Vec2;
+
+  use super::*;
+  use crate::{
+    entities::airport::{Gate, Taxiway, Terminal},
+    line::Line,
+  };
+
+  #[test]
+  fn test_reciprocal_id() {
+    assert_eq!(reciprocal_id("19R").as_deref(), Some("01L"));
+    assert_eq!(reciprocal_id("28L").as_deref(), Some("10R"));
+    assert_eq!(reciprocal_id("18").as_deref(), Some("36"));
+    assert_eq!(reciprocal_id("09C").as_deref(), Some("27C"));
+    assert_eq!(reciprocal_id("A"), None);
+  }
+
+  #[test]
+  fn test_validate() {
+    let mut airport = Airport::new(Intern::from_ref("KTST"), Vec2::ZERO);
+    airport.frequencies = Frequencies {
+      approach: 124.0,
+      departure: 124.0,
+      tower: 120.5,
+      ground: 121.8,
+      center: 132.5,
+      clearance: None,
+    };
+    airport.runways.extend([
+      Runway {
+        id: Intern::from_ref("18"),
+        start: Vec2::new(0.0, 4000.0),
+        heading: 180.0,
+        length: 8000.0,
+      },
+      Runway {
+        id: Intern::from_ref("36"),
+        start: Vec2::new(0.0, -4000.0),
+        heading: 0.0,
+        length: 7000.0,
+      },
+    ]);
+    airport.taxiways.extend([
+      Taxiway::new(
+        Intern::from_ref("A"),
+        Vec2::new(-500.0, 0.0),
+        Vec2::new(2000.0, 0.0),
+      ),
+      Taxiway::new(
+        Intern::from_ref("B"),
+        Vec2::new(5000.0, 5000.0),
+        Vec2::new(6000.0, 5000.0),
+      ),
+    ]);
+    airport.terminals.push(Terminal {
+      id: Intern::from_ref("T1"),
+      a: Vec2::ZERO,
+      b: Vec2::ZERO,
+      c: Vec2::ZERO,
+      d: Vec2::ZERO,
+      gates: vec![Gate {
+        id: Intern::from_ref("A"),
+        pos: Vec2::new(5500.0, 5500.0),
+        heading: 0.0,
+        available: true,
+      }],
+      apron: Line::new(Vec2::new(5500.0, 4000.0), Vec2::new(5500.0, 6000.0)),
+    });
+    airport.calculate_waypoints();
+
+    let issues = airport.validate();
+    assert!(issues.contains(&AirportIssue::DuplicateId(Intern::from_ref("A"))));
+    assert!(
+      !issues
+        .contains(&AirportIssue::DisconnectedTaxiway(Intern::from_ref("A")))
+    );
+    assert!(
+      issues.contains(&AirportIssue::UnreachableGate(Intern::from_ref("A")))
+    );
+    assert!(issues.contains(&AirportIssue::MismatchedReciprocal(
+      Intern::from_ref("18"),
+      Intern::from_ref("36")
+    )));
+    // Approach and departure are held by the same position.
+    assert!(
+      !issues
+        .iter()
+        .any(|i| matches!(i, AirportIssue::SharedFrequency(..)))
+    );
+  }
+}
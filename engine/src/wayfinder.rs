@@ -11,6 +11,19 @@ use crate::{
   sign3,
 };
 
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct Clearance {
+  /// The clearance limit.
+  #[ts(as = "String")]
+  pub route: Intern<String>,
+  pub initial_altitude: f32,
+  /// The code to squawk, or `None` to be assigned one from the departure
+  /// airport's pool.
+  pub squawk: Option<u16>,
+  pub departure_frequency: f32,
+}
+
 #[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
 pub enum VORLimit {
   #[default]
@@ -169,6 +182,7 @@ pub struct FlightPlan {
   // Initial Clearance
   pub speed: f32,
   pub altitude: f32,
+  pub clearance: Option<Clearance>,
 }
 
 impl Default for FlightPlan {
@@ -185,6 +199,7 @@ impl Default for FlightPlan {
 
       speed: 450.0,
       altitude: TRANSITION_ALTITUDE,
+      clearance: None,
     }
   }
 }
@@ -1,14 +1,17 @@
-pub fn wordify_airline<'a>(airline: String) -> &'a str {
-  match airline.as_str() {
-    "AAL" => "American",
-    "JBU" => "JetBlue",
-    "SKW" => "SkyWest",
-    "DAL" => "Delta",
-    "UAL" => "United",
-    "BAW" => "Speedbird",
-    "SWA" => "Southwest",
-    _ => "Unknown",
-  }
+use itertools::Itertools;
+
+use crate::{
+  airlines::{is_tail_number, registry},
+  nato_phonetic,
+};
+
+/// Speaks an airline designator by its telephony, or spells it out if the
+/// airline is unknown.
+pub fn wordify_airline(airline: &str) -> String {
+  registry()
+    .airline(airline)
+    .map(|a| a.telephony.clone())
+    .unwrap_or_else(|| nato_phonetic(airline))
 }
 
 fn wordify_digit<'a>(digit: char) -> &'a str {
@@ -69,12 +72,18 @@ fn wordify_pair(pair: (char, char)) -> String {
   }
 }
 
-pub fn wordify_flight_number(flight_number: String) -> String {
-  let chunks = flight_number.chars().collect::<Vec<char>>();
-  let chunks = chunks.chunks(2).collect::<Vec<&[char]>>();
+fn wordify_digits(digits: &str) -> String {
+  let chars = digits.chars().collect::<Vec<char>>();
+  // Odd numbers of digits lead with a single digit, such as "one
+  // twenty-three".
+  let (head, pairs) = chars.split_at(chars.len() % 2);
 
   let mut string = String::new();
-  for chunk in chunks {
+  for digit in head {
+    string.push_str(wordify_digit(*digit));
+    string.push(' ');
+  }
+  for chunk in pairs.chunks(2) {
     string.push_str(&wordify_pair((chunk[0], chunk[1])));
     string.push(' ');
   }
@@ -82,15 +91,46 @@ pub fn wordify_flight_number(flight_number: String) -> String {
   string.trim().to_owned()
 }
 
+/// Speaks a flight number, with digits grouped in pairs and any letters
+/// spelled out, such as "twelve kilo" for "12K".
+pub fn wordify_flight_number(flight_number: String) -> String {
+  let chunks = flight_number
+    .chars()
+    .chunk_by(|c| c.is_ascii_digit())
+    .into_iter()
+    .map(|(is_digit, chunk)| {
+      let chunk = chunk.collect::<String>();
+      if is_digit {
+        wordify_digits(&chunk)
+      } else {
+        nato_phonetic(chunk)
+      }
+    })
+    .collect::<Vec<String>>();
+
+  chunks.join(" ")
+}
+
 pub fn wordify<T: AsRef<str>>(text: T) -> String {
   let text = text.as_ref();
-  let airline = text[0..3].to_string();
-  let flight_number = text.chars().skip(3).collect::<String>();
+  // Tail numbers are spelled out in full.
+  if is_tail_number(text) {
+    return nato_phonetic(text);
+  }
+
+  let split = text
+    .char_indices()
+    .find_map(|(i, c)| c.is_ascii_digit().then_some(i))
+    .unwrap_or(text.len());
+  let (airline, flight_number) = text.split_at(split);
+  if flight_number.is_empty() {
+    return nato_phonetic(text);
+  }
 
   format!(
     "{} {}",
     wordify_airline(airline),
-    wordify_flight_number(flight_number)
+    wordify_flight_number(flight_number.to_owned())
   )
 }
 
@@ -100,10 +140,10 @@ mod tests {
 
   #[test]
   fn test_wordify_airline() {
-    assert_eq!(wordify_airline("AAL".to_owned()), "American");
-    assert_eq!(wordify_airline("JBU".to_owned()), "JetBlue");
-    assert_eq!(wordify_airline("SKW".to_owned()), "SkyWest");
-    assert_eq!(wordify_airline("XYZ".to_owned()), "Unknown");
+    assert_eq!(wordify_airline("AAL"), "American");
+    assert_eq!(wordify_airline("JBU"), "JetBlue");
+    assert_eq!(wordify_airline("SKW"), "SkyWest");
+    assert_eq!(wordify_airline("XYZ"), "X-ray Yankee Zulu");
   }
 
   #[test]
@@ -137,4 +177,21 @@ mod tests {
   fn wordify_0000() {
     assert_eq!(wordify("AAL0000"), "American zero zero zero zero");
   }
+
+  #[test]
+  fn wordify_short_flight_numbers() {
+    assert_eq!(wordify("AAL123"), "American one twenty-three");
+    assert_eq!(wordify("AAL12"), "American twelve");
+    assert_eq!(wordify("AAL1"), "American one");
+  }
+
+  #[test]
+  fn wordify_alphanumeric() {
+    assert_eq!(wordify("BAW12K"), "Speedbird twelve Kilo");
+  }
+
+  #[test]
+  fn wordify_tail_number() {
+    assert_eq!(wordify("N123AB"), "November One Two Three Alfa Bravo");
+  }
 }
@@ -0,0 +1,389 @@
+//! A headless harness for running the engine against a small fixture airport,
+//! with commands scripted by tick.
+
+// Each test binary uses a different part of the harness.
+#![allow(dead_code)]
+
+use engine::{
+  DEFAULT_TICK_RATE_TPS, NAUTICALMILES_TO_FEET,
+  engine::{Engine, Event},
+  entities::{
+    aircraft::{
+      Aircraft, AircraftState, Fuel, TaxiingState, TransponderMode,
+      events::{AircraftEvent, EventKind},
+    },
+    airport::{Airport, Gate, Runway, Taxiway, Terminal},
+    world::AirportStatus,
+  },
+  geometry::move_point,
+  line::Line,
+  pathfinder::{Node, NodeBehavior, NodeKind},
+  wayfinder::FlightPlan,
+};
+use glam::Vec2;
+use internment::Intern;
+
+pub const AIRPORT: &str = "KTST";
+
+/// A single runway airport with a parallel taxiway, two connectors and one
+/// terminal:
+///
+/// - Runway 18 runs north to south along x = 0.
+/// - Taxiway A runs alongside it at x = 1000.
+/// - A1 (south) and A2 (north) connect the runway to A.
+/// - Terminal T1 has an apron east of A, with gate G1.
+pub fn fixture_airport() -> Airport {
+  let mut airport = Airport::new(Intern::from_ref(AIRPORT), Vec2::ZERO);
+
+  airport.runways.push(Runway {
+    id: Intern::from_ref("18"),
+    start: Vec2::new(0.0, 4000.0),
+    heading: 180.0,
+    length: 8000.0,
+  });
+
+  airport.taxiways.extend([
+    Taxiway::new(
+      Intern::from_ref("A"),
+      Vec2::new(1000.0, 4000.0),
+      Vec2::new(1000.0, -4000.0),
+    ),
+    Taxiway::new(
+      Intern::from_ref("A1"),
+      Vec2::new(-200.0, -3500.0),
+      Vec2::new(1200.0, -3500.0),
+    ),
+    Taxiway::new(
+      Intern::from_ref("A2"),
+      Vec2::new(-200.0, 3500.0),
+      Vec2::new(1200.0, 3500.0),
+    ),
+  ]);
+
+  airport.terminals.push(Terminal {
+    id: Intern::from_ref("T1"),
+    a: Vec2::new(900.0, 1000.0),
+    b: Vec2::new(2500.0, 1000.0),
+    c: Vec2::new(2500.0, -1000.0),
+    d: Vec2::new(900.0, -1000.0),
+    gates: vec![Gate {
+      id: Intern::from_ref("G1"),
+      pos: Vec2::new(1800.0, 200.0),
+      heading: 180.0,
+      available: true,
+    }],
+    apron: Line::new(Vec2::new(800.0, 0.0), Vec2::new(2000.0, 0.0)),
+  });
+
+  airport.extend_all();
+  airport.calculate_waypoints();
+
+  airport
+}
+
+/// An aircraft on a straight-in final to runway 18, `nm` out.
+pub fn on_final(id: &str, nm: f32, altitude: f32) -> Aircraft {
+  let runway = fixture_airport().runways[0].clone();
+  let pos = move_point(runway.start, 0.0, nm * NAUTICALMILES_TO_FEET);
+
+  arriving(id, pos, altitude, 180.0, 160.0)
+}
+
+/// An aircraft flying towards the fixture airport.
+pub fn arriving(
+  id: &str,
+  pos: Vec2,
+  altitude: f32,
+  heading: f32,
+  speed: f32,
+) -> Aircraft {
+  let mut aircraft = Aircraft {
+    id: Intern::from_ref(id),
+    pos,
+    altitude,
+    heading,
+    speed,
+    state: AircraftState::Flying,
+    flight_plan: FlightPlan::new(
+      Intern::from_ref("KDEP"),
+      Intern::from_ref(AIRPORT),
+    ),
+    flight_time: Some(0),
+    ..Default::default()
+  };
+  aircraft.fuel =
+    Fuel::plan(&aircraft.kind.stats(), NAUTICALMILES_TO_FEET * 100.0, 250.0);
+  aircraft.transponder.mode = TransponderMode::ModeS;
+
+  aircraft.with_synced_targets()
+}
+
+/// An aircraft stopped on runway 18 after landing.
+pub fn on_runway(id: &str, pos: Vec2) -> Aircraft {
+  let mut aircraft = arriving(id, pos, 0.0, 180.0, 0.0);
+  aircraft.state = AircraftState::Taxiing {
+    current: Node::new(
+      Intern::from_ref("18"),
+      NodeKind::Runway,
+      NodeBehavior::GoTo,
+      pos,
+    ),
+    waypoints: Vec::new(),
+    state: TaxiingState::Override,
+  };
+
+  aircraft.with_synced_targets()
+}
+
+/// An aircraft parked at gate G1, departing the fixture airport.
+pub fn at_gate(id: &str) -> Aircraft {
+  let pos = Vec2::new(1800.0, 200.0);
+  let mut aircraft = arriving(id, pos, 0.0, 180.0, 0.0);
+  aircraft.flight_plan =
+    FlightPlan::new(Intern::from_ref(AIRPORT), Intern::from_ref("KDEP"));
+  aircraft.state = AircraftState::Parked {
+    at: Node::new(
+      Intern::from_ref("G1"),
+      NodeKind::Gate,
+      NodeBehavior::Park,
+      pos,
+    ),
+  };
+
+  aircraft.with_synced_targets()
+}
+
+pub fn taxiway(name: &str) -> Node<()> {
+  Node::new(
+    Intern::from_ref(name),
+    NodeKind::Taxiway,
+    NodeBehavior::GoTo,
+    (),
+  )
+}
+
+pub fn gate(name: &str) -> Node<()> {
+  Node::new(
+    Intern::from_ref(name),
+    NodeKind::Gate,
+    NodeBehavior::Park,
+    (),
+  )
+}
+
+/// Runs an engine headlessly, sending scripted commands on the tick they are
+/// due and keeping every event it produces.
+pub struct Harness {
+  pub engine: Engine,
+  script: Vec<(usize, AircraftEvent)>,
+  pub log: Vec<(usize, Event)>,
+}
+
+impl Harness {
+  pub fn new() -> Self {
+    let mut engine = Engine::default();
+    engine.world.airports.push(fixture_airport());
+
+    Self {
+      engine,
+      script: Vec::new(),
+      log: Vec::new(),
+    }
+  }
+
+  pub fn with_status(mut self, status: AirportStatus) -> Self {
+    self
+      .engine
+      .world
+      .airport_statuses
+      .insert(Intern::from_ref(AIRPORT), status);
+    self
+  }
+
+  pub fn spawn(&mut self, mut aircraft: Aircraft) {
+    aircraft.update_airspace(&self.engine.world);
+    self.engine.add_aircraft(aircraft);
+  }
+
+  /// Sends a command to an aircraft at a tick.
+  pub fn command(&mut self, tick: usize, id: &str, kind: EventKind) {
+    self
+      .script
+      .push((tick, AircraftEvent::new(Intern::from_ref(id), kind)));
+  }
+
+  pub fn ticks(&self) -> usize {
+    self.engine.tick_counter
+  }
+
+  pub fn tick(&mut self) {
+    let now = self.ticks();
+    let (due, waiting) = self.script.drain(..).partition(|(t, _)| *t <= now);
+    self.script = waiting;
+    self
+      .engine
+      .events
+      .extend(due.into_iter().map(|(_, e)| Event::from(e)));
+
+    let events = self.engine.tick();
+    let deleted: Vec<Intern<String>> = events
+      .iter()
+      .filter_map(|e| match e {
+        Event::Aircraft(AircraftEvent {
+          id,
+          kind: EventKind::Delete,
+        }) => Some(*id),
+        _ => None,
+      })
+      .collect();
+    self
+      .engine
+      .game
+      .aircraft
+      .retain(|a| !deleted.contains(&a.id));
+    self.log.extend(events.into_iter().map(|e| (now, e)));
+  }
+
+  /// Ticks until the predicate holds, for at most `seconds` of game time.
+  /// Returns whether it did.
+  pub fn run_until(
+    &mut self,
+    seconds: f32,
+    mut predicate: impl FnMut(&Self) -> bool,
+  ) -> bool {
+    let ticks = (seconds * DEFAULT_TICK_RATE_TPS as f32) as usize;
+    for _ in 0..ticks {
+      self.tick();
+      if predicate(self) {
+        return true;
+      }
+    }
+
+    false
+  }
+
+  pub fn aircraft(&self, id: &str) -> Option<&Aircraft> {
+    let id = Intern::from_ref(id);
+    self.engine.game.aircraft.iter().find(|a| a.id == id)
+  }
+
+  /// Events for an aircraft, and the tick they happened on.
+  pub fn events_for<'a>(
+    &'a self,
+    id: &'a str,
+  ) -> impl Iterator<Item = (usize, &'a EventKind)> {
+    filter_aircraft(self.log.iter(), id)
+  }
+
+  /// Events for an aircraft from the last tick.
+  pub fn last_events_for<'a>(
+    &'a self,
+    id: &'a str,
+  ) -> impl Iterator<Item = (usize, &'a EventKind)> {
+    let last = self.ticks().saturating_sub(1);
+    filter_aircraft(
+      self
+        .log
+        .iter()
+        .rev()
+        .take_while(move |(tick, _)| *tick == last),
+      id,
+    )
+  }
+
+  /// Asserts that an aircraft touches down on a runway within some seconds,
+  /// without going around.
+  pub fn assert_lands(&mut self, id: &str, runway: &str, within: f32) {
+    let mut landing_on = None;
+    let landed = self.run_until(within, |h| {
+      if let Some(AircraftState::Landing { runway, .. }) =
+        h.aircraft(id).map(|a| &a.state)
+      {
+        landing_on = Some(runway.id);
+      }
+
+      h.last_events_for(id)
+        .any(|(_, e)| *e == EventKind::Touchdown)
+    });
+
+    assert!(
+      self.events_for(id).all(|(_, e)| *e != EventKind::GoAround),
+      "{id} went around"
+    );
+    assert!(landed, "{id} did not land within {within}s");
+    assert_eq!(
+      landing_on.map(|r| r.to_string()),
+      Some(runway.to_owned()),
+      "{id} landed on the wrong runway"
+    );
+  }
+
+  /// Asserts that an aircraft taxis via a taxiway and parks at a gate within
+  /// some seconds.
+  pub fn assert_taxis_via(
+    &mut self,
+    id: &str,
+    taxiway: &str,
+    gate: &str,
+    within: f32,
+  ) {
+    let mut route: Vec<String> = Vec::new();
+    let parked =
+      self.run_until(within, |h| match h.aircraft(id).map(|a| &a.state) {
+        Some(AircraftState::Taxiing { current, .. }) => {
+          if route.last().is_none_or(|n| *n != current.name.as_str()) {
+            route.push(current.name.to_string());
+          }
+          false
+        }
+        Some(AircraftState::Parked { .. }) => true,
+        _ => false,
+      });
+
+    assert!(parked, "{id} did not park within {within}s (via {route:?})");
+    assert!(
+      route.iter().any(|n| n == taxiway),
+      "{id} did not taxi via {taxiway} (via {route:?})"
+    );
+    assert!(
+      matches!(
+        self.aircraft(id).map(|a| &a.state),
+        Some(AircraftState::Parked { at }) if at.name.as_str() == gate
+      ),
+      "{id} did not park at {gate}"
+    );
+  }
+
+  pub fn separation_losses(&self) -> Vec<(usize, Intern<String>)> {
+    self
+      .log
+      .iter()
+      .filter_map(|(tick, e)| match e {
+        Event::Aircraft(AircraftEvent {
+          id,
+          kind: EventKind::SeparationLoss(_),
+        }) => Some((*tick, *id)),
+        _ => None,
+      })
+      .collect()
+  }
+
+  pub fn assert_never_loses_separation(&self) {
+    let losses = self.separation_losses();
+    assert!(losses.is_empty(), "lost separation: {losses:?}");
+  }
+}
+
+fn filter_aircraft<'a>(
+  log: impl Iterator<Item = &'a (usize, Event)>,
+  id: &'a str,
+) -> impl Iterator<Item = (usize, &'a EventKind)> {
+  log.filter_map(move |(tick, e)| match e {
+    Event::Aircraft(AircraftEvent { id: aircraft, kind })
+      if aircraft.as_str() == id =>
+    {
+      Some((*tick, kind))
+    }
+    _ => None,
+  })
+}
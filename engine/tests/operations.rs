@@ -0,0 +1,180 @@
+mod harness;
+
+use engine::{
+  NAUTICALMILES_TO_FEET,
+  entities::aircraft::{Emergency, Transponder, events::EventKind},
+  wayfinder::Clearance,
+};
+use glam::Vec2;
+use harness::{Harness, arriving, at_gate, gate, on_final, on_runway, taxiway};
+use internment::Intern;
+
+#[test]
+fn lands_on_18() {
+  let mut harness = Harness::new();
+  harness.spawn(on_final("AAL1", 10.0, 4000.0));
+  harness.command(0, "AAL1", EventKind::Land(Intern::from_ref("18")));
+
+  harness.assert_lands("AAL1", "18", 600.0);
+}
+
+#[test]
+fn lands_with_crossing_traffic_separated() {
+  let mut harness = Harness::new();
+  harness.spawn(on_final("AAL1", 10.0, 4000.0));
+  // Crosses the final course above AAL1, about when AAL1 gets there.
+  harness.spawn(arriving(
+    "UAL2",
+    Vec2::new(NAUTICALMILES_TO_FEET * 10.0, NAUTICALMILES_TO_FEET * 4.0),
+    6000.0,
+    270.0,
+    250.0,
+  ));
+  harness.command(0, "AAL1", EventKind::Land(Intern::from_ref("18")));
+
+  let converges = harness.run_until(300.0, |h| {
+    let (a, b) = (h.aircraft("AAL1").unwrap(), h.aircraft("UAL2").unwrap());
+    a.pos.distance(b.pos) < NAUTICALMILES_TO_FEET * 3.0
+  });
+  assert!(converges, "UAL2 never converged with AAL1");
+
+  harness.assert_lands("AAL1", "18", 600.0);
+  harness.assert_never_loses_separation();
+}
+
+#[test]
+fn refuses_holds_in_an_emergency() {
+  let mut harness = Harness::new();
+  let mut aircraft = arriving(
+    "AAL1",
+    Vec2::new(0.0, NAUTICALMILES_TO_FEET * 20.0),
+    8000.0,
+    180.0,
+    250.0,
+  );
+  aircraft.emergency = Some(Emergency::Medical);
+  harness.spawn(aircraft);
+
+  // Turning away from the airport would hold them up.
+  harness.command(0, "AAL1", EventKind::Heading(0.0));
+  harness.run_until(1.0, |_| false);
+  assert_eq!(harness.aircraft("AAL1").unwrap().target.heading, 180.0);
+
+  // But they can still be vectored towards it.
+  harness.command(harness.ticks(), "AAL1", EventKind::Heading(200.0));
+  harness.run_until(1.0, |_| false);
+  assert_eq!(harness.aircraft("AAL1").unwrap().target.heading, 200.0);
+}
+
+#[test]
+fn detects_head_on_conflict() {
+  let mut harness = Harness::new();
+  harness.spawn(arriving(
+    "AAL1",
+    Vec2::new(0.0, NAUTICALMILES_TO_FEET * 5.0),
+    8000.0,
+    180.0,
+    250.0,
+  ));
+  harness.spawn(arriving(
+    "UAL2",
+    Vec2::new(0.0, -NAUTICALMILES_TO_FEET * 5.0),
+    8000.0,
+    0.0,
+    250.0,
+  ));
+
+  assert!(harness.run_until(120.0, |h| !h.separation_losses().is_empty()));
+}
+
+#[test]
+fn taxis_via_a_to_g1() {
+  let mut harness = Harness::new();
+  harness.spawn(on_runway("AAL1", Vec2::new(0.0, -3000.0)));
+  harness.command(0, "AAL1", EventKind::Taxi(vec![taxiway("A"), gate("G1")]));
+
+  harness.assert_taxis_via("AAL1", "A", "G1", 600.0);
+}
+
+#[test]
+fn assigns_squawk_at_clearance() {
+  let mut harness = Harness::new();
+  harness.spawn(at_gate("AAL1"));
+  harness.spawn(at_gate("UAL2"));
+
+  let clearance = Clearance {
+    route: Intern::from_ref("KDEP"),
+    initial_altitude: 5000.0,
+    squawk: None,
+    departure_frequency: 118.6,
+  };
+  for id in ["AAL1", "UAL2"] {
+    harness.command(0, id, EventKind::Clearance(clearance.clone()));
+  }
+  harness.run_until(1.0, |_| false);
+
+  let codes: Vec<u16> = ["AAL1", "UAL2"]
+    .iter()
+    .map(|id| harness.aircraft(id).unwrap().transponder.code)
+    .collect();
+  assert!(codes.iter().all(|code| !Transponder::is_special(*code)));
+  assert_ne!(codes[0], codes[1]);
+  assert_eq!(
+    harness
+      .aircraft("AAL1")
+      .unwrap()
+      .flight_plan
+      .clearance
+      .as_ref()
+      .unwrap()
+      .squawk,
+    Some(codes[0])
+  );
+}
+
+#[test]
+fn checks_clearance_route() {
+  let mut harness = Harness::new();
+  harness.spawn(at_gate("AAL1"));
+
+  let clearance = Clearance {
+    route: Intern::from_ref("KXXX"),
+    initial_altitude: 5000.0,
+    squawk: None,
+    departure_frequency: 124.3,
+  };
+  harness.command(0, "AAL1", EventKind::Clearance(clearance.clone()));
+  harness.run_until(1.0, |_| false);
+  assert!(
+    harness
+      .aircraft("AAL1")
+      .unwrap()
+      .flight_plan
+      .clearance
+      .is_none()
+  );
+
+  let clearance = Clearance {
+    route: Intern::from_ref("KDEP"),
+    ..clearance
+  };
+  harness.command(harness.ticks(), "AAL1", EventKind::Clearance(clearance));
+  harness.run_until(1.0, |_| false);
+  assert!(
+    harness
+      .aircraft("AAL1")
+      .unwrap()
+      .flight_plan
+      .clearance
+      .is_some()
+  );
+
+  // Departure is the frequency they were cleared with.
+  harness.command(
+    harness.ticks(),
+    "AAL1",
+    EventKind::NamedFrequency("departure".to_owned()),
+  );
+  harness.run_until(1.0, |_| false);
+  assert_eq!(harness.aircraft("AAL1").unwrap().frequency, 124.3);
+}
@@ -1,14 +1,19 @@
 use core::net::Ipv6Addr;
 use std::{
   net::{IpAddr, Ipv4Addr, SocketAddr},
-  path::Path,
+  path::{Path, PathBuf},
 };
 
 use engine::entities::{airport::Frequencies, world::AirportStatus};
 use serde::{Deserialize, Serialize};
 
+use crate::{http::auth::Role, traffic::TrafficConfig};
+
 #[derive(Clone, Default, Serialize, Deserialize)]
 pub struct Config {
+  /// The directory to read game assets from.
+  #[serde(default)]
+  assets: Option<PathBuf>,
   #[serde(default)]
   frequencies: Option<Frequencies>,
   #[serde(default)]
@@ -33,6 +38,10 @@ impl Config {
     }
   }
 
+  pub fn assets(&self) -> Option<&Path> {
+    self.assets.as_deref()
+  }
+
   pub fn frequencies(&self) -> Option<&Frequencies> {
     self.frequencies.as_ref()
   }
@@ -80,16 +89,48 @@ impl From<AirportStatusConfig> for AirportStatus {
   }
 }
 
-#[derive(Clone, Default, Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct WorldConfig {
   #[serde(default)]
   seed: WorldSeed,
   #[serde(default)]
   airport: Option<String>,
+  /// Real airports to place around the main airport at their true positions.
+  #[serde(default)]
+  airports: Vec<String>,
   #[serde(default)]
   paused: bool,
   #[serde(default)]
   status: AirportStatusConfig,
+  #[serde(default)]
+  schedule: Option<PathBuf>,
+  /// A scenario script to load in place of the generated world.
+  #[serde(default)]
+  scenario: Option<PathBuf>,
+  #[serde(default = "default_true")]
+  random_traffic: bool,
+  #[serde(default)]
+  traffic: TrafficConfig,
+}
+
+impl Default for WorldConfig {
+  fn default() -> Self {
+    Self {
+      seed: WorldSeed::default(),
+      airport: None,
+      airports: Vec::new(),
+      paused: false,
+      status: AirportStatusConfig::default(),
+      schedule: None,
+      scenario: None,
+      random_traffic: true,
+      traffic: TrafficConfig::default(),
+    }
+  }
+}
+
+fn default_true() -> bool {
+  true
 }
 
 impl WorldConfig {
@@ -101,6 +142,10 @@ impl WorldConfig {
     self.airport.as_deref()
   }
 
+  pub fn airports(&self) -> &[String] {
+    &self.airports
+  }
+
   pub fn paused(&self) -> bool {
     self.paused
   }
@@ -108,6 +153,22 @@ impl WorldConfig {
   pub fn status(&self) -> AirportStatus {
     self.status.clone().into()
   }
+
+  pub fn schedule(&self) -> Option<&Path> {
+    self.schedule.as_deref()
+  }
+
+  pub fn scenario(&self) -> Option<&Path> {
+    self.scenario.as_deref()
+  }
+
+  pub fn random_traffic(&self) -> bool {
+    self.random_traffic
+  }
+
+  pub fn traffic(&self) -> &TrafficConfig {
+    &self.traffic
+  }
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -116,6 +177,22 @@ pub struct ServerConfig {
   pub address_ipv4: SocketAddr,
   #[serde(default = "default_ipv6")]
   pub address_ipv6: SocketAddr,
+  /// API tokens. If there are none, the API is open to everyone.
+  #[serde(default)]
+  pub tokens: Vec<TokenConfig>,
+  /// Origins allowed to make cross-origin requests. If unset, any origin is
+  /// allowed.
+  #[serde(default)]
+  pub cors_origins: Option<Vec<String>>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct TokenConfig {
+  /// Who the token belongs to, for the logs.
+  #[serde(default)]
+  pub name: Option<String>,
+  pub token: String,
+  pub role: Role,
 }
 
 impl Default for ServerConfig {
@@ -123,6 +200,8 @@ impl Default for ServerConfig {
     Self {
       address_ipv4: default_ipv4(),
       address_ipv6: default_ipv6(),
+      tokens: Vec::new(),
+      cors_origins: None,
     }
   }
 }
@@ -0,0 +1,140 @@
+use std::{collections::HashMap, sync::Arc};
+
+use axum::{
+  extract::{Request, State},
+  http::{self, HeaderMap, Method, header},
+  middleware::Next,
+  response::Response,
+};
+use serde::{Deserialize, Serialize};
+
+/// What a token is allowed to do. Each role can do everything the roles
+/// before it can.
+#[derive(
+  Debug,
+  Clone,
+  Copy,
+  PartialEq,
+  Eq,
+  PartialOrd,
+  Ord,
+  Hash,
+  Serialize,
+  Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+  /// Read-only access to the game state.
+  Observer,
+  /// Talking to aircraft and coordinating with other positions.
+  Controller,
+  /// Running the session: pausing, airport status, traffic, building and
+  /// live scenario changes.
+  Instructor,
+  Admin,
+}
+
+/// The role that a request needs, by its method and path (without the
+/// `/api` prefix).
+pub fn required_role(method: &Method, path: &str) -> Role {
+  if method == Method::GET
+    || method == Method::HEAD
+    || method == Method::OPTIONS
+  {
+    return Role::Observer;
+  }
+
+  let path = path.strip_prefix("/api").unwrap_or(path);
+  if path.starts_with("/comms/")
+    || path.starts_with("/positions/")
+    || path == "/coordination"
+  {
+    Role::Controller
+  } else if path == "/pause"
+    || path.starts_with("/status/")
+    || path.starts_with("/traffic/")
+    || path.starts_with("/economy/")
+    || path == "/instructor"
+  {
+    Role::Instructor
+  } else {
+    Role::Admin
+  }
+}
+
+/// API tokens and their roles. Without any tokens, auth is disabled and
+/// every request is let through, as for a local singleplayer game.
+#[derive(Debug, Clone, Default)]
+pub struct Auth {
+  tokens: Arc<HashMap<String, Role>>,
+}
+
+impl Auth {
+  pub fn new(tokens: impl IntoIterator<Item = (String, Role)>) -> Self {
+    Self {
+      tokens: Arc::new(tokens.into_iter().collect()),
+    }
+  }
+
+  pub fn is_enabled(&self) -> bool {
+    !self.tokens.is_empty()
+  }
+
+  /// The role of the bearer token in the headers, if any.
+  pub fn role(&self, headers: &HeaderMap) -> Option<Role> {
+    if !self.is_enabled() {
+      return Some(Role::Admin);
+    }
+
+    let token = headers
+      .get(header::AUTHORIZATION)
+      .and_then(|v| v.to_str().ok())
+      .and_then(|v| v.strip_prefix("Bearer "))?;
+    self.tokens.get(token.trim()).copied()
+  }
+}
+
+pub async fn require_role(
+  State(auth): State<Auth>,
+  request: Request,
+  next: Next,
+) -> Result<Response, http::StatusCode> {
+  let required = required_role(request.method(), request.uri().path());
+  match auth.role(request.headers()) {
+    Some(role) if role >= required => Ok(next.run(request).await),
+    Some(_) => Err(http::StatusCode::FORBIDDEN),
+    None => Err(http::StatusCode::UNAUTHORIZED),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_required_role() {
+    assert_eq!(required_role(&Method::GET, "/world"), Role::Observer);
+    assert_eq!(
+      required_role(&Method::POST, "/comms/text"),
+      Role::Controller
+    );
+    assert_eq!(required_role(&Method::POST, "/api/pause"), Role::Instructor);
+    assert_eq!(
+      required_role(&Method::POST, "/instructor"),
+      Role::Instructor
+    );
+    assert_eq!(required_role(&Method::DELETE, "/world"), Role::Admin);
+  }
+
+  #[test]
+  fn test_role() {
+    let mut headers = HeaderMap::new();
+    assert_eq!(Auth::default().role(&headers), Some(Role::Admin));
+
+    let auth = Auth::new([("secret".to_owned(), Role::Controller)]);
+    assert_eq!(auth.role(&headers), None);
+
+    headers.insert(header::AUTHORIZATION, "Bearer secret".parse().unwrap());
+    assert_eq!(auth.role(&headers), Some(Role::Controller));
+  }
+}
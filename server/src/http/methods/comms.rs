@@ -8,9 +8,10 @@ use async_openai::{
 use axum::{
   body::Bytes,
   extract::{Query, State},
+  http::{self, HeaderMap},
 };
 use engine::{
-  command::{CommandReply, CommandWithFreq},
+  command::{CommandReply, CommandWithFreq, Task},
   duration_now,
 };
 use internment::Intern;
@@ -18,9 +19,9 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
   CLI,
-  http::shared::{AppState, GetSender},
+  http::shared::{AppState, GetSender, session},
   job::JobReq,
-  parser::parse_commands,
+  parser::{parse_commands, parse_readback_altitude},
   prompter::Prompter,
   runner::{ArgReqKind, ResKind, TinyReqKind},
 };
@@ -105,23 +106,31 @@ pub struct CommsFrequencyQuery {
 pub async fn comms_text(
   State(mut state): State<AppState>,
   Query(query): Query<CommsFrequencyQuery>,
+  headers: HeaderMap,
   text: String,
-) {
+) -> Result<(), http::StatusCode> {
   let time = Instant::now();
+  let controller = session(&headers);
 
   tracing::info!("Received comms text request: {} chars", text.len());
 
-  let _ = JobReq::send(
-    ArgReqKind::CommandATC(CommandWithFreq::new(
-      "ATC".to_string(),
-      query.frequency,
-      CommandReply::Blank { text: text.clone() },
-      Vec::new(),
-    )),
+  let res = JobReq::send(
+    ArgReqKind::CommandATC(
+      CommandWithFreq::new(
+        "ATC".to_string(),
+        query.frequency,
+        CommandReply::Blank { text: text.clone() },
+        Vec::new(),
+      ),
+      controller,
+    ),
     &mut state.big_sender,
   )
   .recv()
   .await;
+  if !matches!(res, Ok(ResKind::Any)) {
+    return Err(http::StatusCode::FORBIDDEN);
+  }
 
   let commands = parse_commands(text.clone(), query.frequency);
   let commands = if commands.is_empty() {
@@ -132,13 +141,13 @@ pub async fn comms_text(
             query.frequency,
             CommandReply::Blank { text: "Failed to parse shorthand. Unable to use AI features: OpenAI API key not provided.".to_owned() },
             Vec::new(),
-          )),
+          ), controller),
           &mut state.big_sender,
         )
         .recv()
         .await;
 
-      return;
+      return Ok(());
     } else {
       complete_atc_request(
         &mut state.tiny_sender,
@@ -156,20 +165,59 @@ pub async fn comms_text(
     commands
   };
 
+  let rejected = send_commands(&mut state, &commands, controller).await;
+
+  let duration = time.elapsed();
+  tracing::info!(
+    "Completed text request in {:.2} seconds",
+    duration.as_secs_f32()
+  );
+
+  if rejected {
+    Err(http::StatusCode::FORBIDDEN)
+  } else {
+    Ok(())
+  }
+}
+
+/// The altitude a pilot was given, if they read back a different one.
+fn misread_altitude(command: &CommandWithFreq) -> Option<f32> {
+  let CommandReply::WithCallsign { text } = &command.reply else {
+    return None;
+  };
+  let given = command.tasks.iter().find_map(|t| match t {
+    Task::Altitude(altitude) => Some(*altitude),
+    _ => None,
+  })?;
+
+  parse_readback_altitude(text)
+    .filter(|read| *read != given)
+    .map(|_| given)
+}
+
+/// Sends commands to be executed, returning whether any of them were rejected.
+async fn send_commands(
+  state: &mut AppState,
+  commands: &[CommandWithFreq],
+  controller: Option<Intern<String>>,
+) -> bool {
+  let mut rejected = false;
   for command in commands.iter() {
-    let _ = JobReq::send(
-      ArgReqKind::CommandReply(command.clone()),
+    let misread = misread_altitude(command);
+    if misread.is_some() {
+      tracing::info!("{} read back the wrong altitude", command.id);
+    }
+
+    let res = JobReq::send(
+      ArgReqKind::CommandReply(command.clone(), controller, misread),
       &mut state.big_sender,
     )
     .recv()
     .await;
+    rejected |= !matches!(res, Ok(ResKind::Any));
   }
 
-  let duration = time.elapsed();
-  tracing::info!(
-    "Completed text request in {:.2} seconds",
-    duration.as_secs_f32()
-  );
+  rejected
 }
 
 fn write_wav_data(bytes: &Bytes) {
@@ -231,23 +279,29 @@ fn write_json_data(command: &CommandWithFreq) {
 pub async fn comms_voice(
   State(mut state): State<AppState>,
   Query(query): Query<CommsFrequencyQuery>,
+  headers: HeaderMap,
   bytes: Bytes,
-) {
+) -> Result<(), http::StatusCode> {
   let time = Instant::now();
+  let controller = session(&headers);
 
   tracing::info!("Received comms voice request: {} bytes", bytes.len());
 
+  let mut rejected = false;
   if std::env::var("OPENAI_API_KEY").is_err() {
     let _ = JobReq::send(
-      ArgReqKind::CommandATC(CommandWithFreq::new(
-        "ATC".to_string(),
-        query.frequency,
-        CommandReply::Blank {
-          text: "Failed to transcribe voice. Unable to use AI features: OpenAI API key not provided."
-            .to_owned(),
-        },
-        Vec::new(),
-      )),
+      ArgReqKind::CommandATC(
+        CommandWithFreq::new(
+          "ATC".to_string(),
+          query.frequency,
+          CommandReply::Blank {
+            text: "Failed to transcribe voice. Unable to use AI features: OpenAI API key not provided."
+              .to_owned(),
+          },
+          Vec::new(),
+        ),
+        controller,
+      ),
       &mut state.big_sender,
     )
     .recv()
@@ -255,17 +309,23 @@ pub async fn comms_voice(
   } else {
     match transcribe_voice(bytes).await {
       Ok(text) => {
-        let _ = JobReq::send(
-          ArgReqKind::CommandATC(CommandWithFreq::new(
-            "ATC".to_string(),
-            query.frequency,
-            CommandReply::Blank { text: text.clone() },
-            Vec::new(),
-          )),
+        let res = JobReq::send(
+          ArgReqKind::CommandATC(
+            CommandWithFreq::new(
+              "ATC".to_string(),
+              query.frequency,
+              CommandReply::Blank { text: text.clone() },
+              Vec::new(),
+            ),
+            controller,
+          ),
           &mut state.big_sender,
         )
         .recv()
         .await;
+        if !matches!(res, Ok(ResKind::Any)) {
+          return Err(http::StatusCode::FORBIDDEN);
+        }
 
         let commands = complete_atc_request(
           &mut state.tiny_sender,
@@ -276,14 +336,8 @@ pub async fn comms_voice(
 
         for command in commands.iter() {
           write_json_data(command);
-
-          let _ = JobReq::send(
-            ArgReqKind::CommandReply(command.clone()),
-            &mut state.big_sender,
-          )
-          .recv()
-          .await;
         }
+        rejected = send_commands(&mut state, &commands, controller).await;
       }
       Err(e) => tracing::error!("Transcription failed: {}", e),
     }
@@ -294,4 +348,10 @@ pub async fn comms_voice(
     "Completed voice request in {:.2} seconds",
     duration.as_secs_f32()
   );
+
+  if rejected {
+    Err(http::StatusCode::FORBIDDEN)
+  } else {
+    Ok(())
+  }
 }
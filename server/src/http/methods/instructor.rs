@@ -0,0 +1,56 @@
+use axum::{Json, extract::State, http};
+
+use crate::{
+  http::shared::AppState,
+  instructor::InstructorAction,
+  job::JobReq,
+  runner::{ArgReqKind, ResKind, TinyReqKind},
+};
+
+pub async fn get_session_log(
+  State(mut state): State<AppState>,
+) -> Result<String, http::StatusCode> {
+  let res = JobReq::send(TinyReqKind::SessionLog, &mut state.tiny_sender)
+    .recv()
+    .await;
+  if let Ok(ResKind::SessionLog(log)) = res {
+    if let Ok(string) = serde_json::to_string(&log) {
+      Ok(string)
+    } else {
+      Err(http::StatusCode::BAD_REQUEST)
+    }
+  } else {
+    Err(http::StatusCode::INTERNAL_SERVER_ERROR)
+  }
+}
+
+pub async fn post_instructor(
+  State(mut state): State<AppState>,
+  Json(action): Json<InstructorAction>,
+) -> Result<(), http::StatusCode> {
+  let res = JobReq::send(ArgReqKind::Instructor(action), &mut state.big_sender)
+    .recv()
+    .await;
+  if let Ok(ResKind::Any) = res {
+    Ok(())
+  } else {
+    Err(http::StatusCode::BAD_REQUEST)
+  }
+}
+
+pub async fn get_scenario(
+  State(mut state): State<AppState>,
+) -> Result<String, http::StatusCode> {
+  let res = JobReq::send(TinyReqKind::Scenario, &mut state.tiny_sender)
+    .recv()
+    .await;
+  if let Ok(ResKind::Scenario(report)) = res {
+    if let Ok(string) = serde_json::to_string(&report) {
+      Ok(string)
+    } else {
+      Err(http::StatusCode::BAD_REQUEST)
+    }
+  } else {
+    Err(http::StatusCode::INTERNAL_SERVER_ERROR)
+  }
+}
@@ -1,4 +1,6 @@
 pub mod aircraft;
 pub mod comms;
+pub mod instructor;
 pub mod misc;
+pub mod positions;
 pub mod state;
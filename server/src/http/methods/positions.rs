@@ -0,0 +1,114 @@
+use axum::{
+  Json,
+  extract::State,
+  http::{self, HeaderMap},
+};
+use engine::coordination::{Coordination, CoordinationKind};
+use internment::Intern;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  http::shared::{AppState, session},
+  job::JobReq,
+  positions::Controller,
+  runner::{ResKind, TinyReqKind},
+};
+
+pub async fn get_controllers(
+  State(mut state): State<AppState>,
+) -> Result<String, http::StatusCode> {
+  let res = JobReq::send(TinyReqKind::Controllers, &mut state.tiny_sender)
+    .recv()
+    .await;
+  if let Ok(ResKind::Controllers(controllers)) = res {
+    if let Ok(string) = serde_json::to_string(&controllers) {
+      Ok(string)
+    } else {
+      Err(http::StatusCode::BAD_REQUEST)
+    }
+  } else {
+    Err(http::StatusCode::INTERNAL_SERVER_ERROR)
+  }
+}
+
+pub async fn post_login(
+  State(mut state): State<AppState>,
+  Json(controller): Json<Controller>,
+) -> Result<String, http::StatusCode> {
+  let res =
+    JobReq::send(TinyReqKind::Login(controller), &mut state.tiny_sender)
+      .recv()
+      .await;
+  if let Ok(ResKind::Session(id)) = res {
+    Ok(id.to_string())
+  } else {
+    Err(http::StatusCode::CONFLICT)
+  }
+}
+
+pub async fn post_logout(
+  State(mut state): State<AppState>,
+  headers: HeaderMap,
+) -> Result<(), http::StatusCode> {
+  let Some(id) = session(&headers) else {
+    return Err(http::StatusCode::UNAUTHORIZED);
+  };
+
+  let res = JobReq::send(TinyReqKind::Logout(id), &mut state.tiny_sender)
+    .recv()
+    .await;
+  if let Ok(ResKind::Any) = res {
+    Ok(())
+  } else {
+    Err(http::StatusCode::BAD_REQUEST)
+  }
+}
+
+pub async fn get_coordination(
+  State(mut state): State<AppState>,
+) -> Result<String, http::StatusCode> {
+  let res = JobReq::send(TinyReqKind::Coordination, &mut state.tiny_sender)
+    .recv()
+    .await;
+  if let Ok(ResKind::Coordination(coordination)) = res {
+    if let Ok(string) = serde_json::to_string(&coordination) {
+      Ok(string)
+    } else {
+      Err(http::StatusCode::BAD_REQUEST)
+    }
+  } else {
+    Err(http::StatusCode::INTERNAL_SERVER_ERROR)
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CoordinationRequest {
+  aircraft: String,
+  from: f32,
+  to: f32,
+  kind: CoordinationKind,
+}
+
+pub async fn post_coordination(
+  State(mut state): State<AppState>,
+  headers: HeaderMap,
+  Json(req): Json<CoordinationRequest>,
+) -> Result<(), http::StatusCode> {
+  let coordination = Coordination::new(
+    Intern::from(req.aircraft.to_uppercase()),
+    req.from,
+    req.to,
+    req.kind,
+  );
+  let res = JobReq::send(
+    TinyReqKind::Coordinate(coordination, session(&headers)),
+    &mut state.tiny_sender,
+  )
+  .recv()
+  .await;
+  if let Ok(ResKind::Any) = res {
+    Ok(())
+  } else {
+    Err(http::StatusCode::BAD_REQUEST)
+  }
+}
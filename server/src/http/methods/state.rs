@@ -10,6 +10,7 @@ use crate::{
   http::shared::AppState,
   job::JobReq,
   runner::{ResKind, TinyReqKind},
+  traffic::DemandPoint,
 };
 
 pub async fn get_messages(
@@ -67,6 +68,124 @@ pub async fn get_airport_status(
   }
 }
 
+pub async fn get_airport_stats(
+  State(mut state): State<AppState>,
+  Path(id): Path<String>,
+) -> Result<String, http::StatusCode> {
+  let res = JobReq::send(
+    TinyReqKind::AirportStats(Intern::from(id)),
+    &mut state.tiny_sender,
+  )
+  .recv()
+  .await;
+  if let Ok(ResKind::AirportStats(stats)) = res {
+    if let Ok(string) = serde_json::to_string(&stats) {
+      Ok(string)
+    } else {
+      Err(http::StatusCode::BAD_REQUEST)
+    }
+  } else {
+    Err(http::StatusCode::INTERNAL_SERVER_ERROR)
+  }
+}
+
+pub async fn get_score(
+  State(mut state): State<AppState>,
+) -> Result<String, http::StatusCode> {
+  let res = JobReq::send(TinyReqKind::Score, &mut state.tiny_sender)
+    .recv()
+    .await;
+  if let Ok(ResKind::Score(report)) = res {
+    if let Ok(string) = serde_json::to_string(&report) {
+      Ok(string)
+    } else {
+      Err(http::StatusCode::BAD_REQUEST)
+    }
+  } else {
+    Err(http::StatusCode::INTERNAL_SERVER_ERROR)
+  }
+}
+
+pub async fn get_airport_score(
+  State(mut state): State<AppState>,
+  Path(id): Path<String>,
+) -> Result<String, http::StatusCode> {
+  let res = JobReq::send(
+    TinyReqKind::AirportScore(Intern::from(id)),
+    &mut state.tiny_sender,
+  )
+  .recv()
+  .await;
+  if let Ok(ResKind::AirportScore(card)) = res {
+    if let Ok(string) = serde_json::to_string(&card) {
+      Ok(string)
+    } else {
+      Err(http::StatusCode::BAD_REQUEST)
+    }
+  } else {
+    Err(http::StatusCode::INTERNAL_SERVER_ERROR)
+  }
+}
+
+pub async fn get_economy(
+  State(mut state): State<AppState>,
+  Path(id): Path<String>,
+) -> Result<String, http::StatusCode> {
+  let res = JobReq::send(
+    TinyReqKind::Economy(Intern::from(id)),
+    &mut state.tiny_sender,
+  )
+  .recv()
+  .await;
+  if let Ok(ResKind::Economy(economy)) = res {
+    if let Ok(string) = serde_json::to_string(&economy) {
+      Ok(string)
+    } else {
+      Err(http::StatusCode::BAD_REQUEST)
+    }
+  } else {
+    Err(http::StatusCode::INTERNAL_SERVER_ERROR)
+  }
+}
+
+pub async fn get_templates(
+  State(mut state): State<AppState>,
+  Path(id): Path<String>,
+) -> Result<String, http::StatusCode> {
+  let res = JobReq::send(
+    TinyReqKind::Templates(Intern::from(id)),
+    &mut state.tiny_sender,
+  )
+  .recv()
+  .await;
+  if let Ok(ResKind::Templates(templates)) = res {
+    if let Ok(string) = serde_json::to_string(&templates) {
+      Ok(string)
+    } else {
+      Err(http::StatusCode::BAD_REQUEST)
+    }
+  } else {
+    Err(http::StatusCode::INTERNAL_SERVER_ERROR)
+  }
+}
+
+pub async fn post_purchase(
+  State(mut state): State<AppState>,
+  Path((id, template)): Path<(String, String)>,
+) -> Result<(), http::StatusCode> {
+  let res = JobReq::send(
+    TinyReqKind::Purchase(Intern::from(id), Intern::from(template)),
+    &mut state.tiny_sender,
+  )
+  .recv()
+  .await;
+  if let Ok(ResKind::Any) = res {
+    Ok(())
+  } else {
+    Err(http::StatusCode::BAD_REQUEST)
+  }
+}
+
 pub async fn post_airport_status(
   State(mut state): State<AppState>,
   Path(id): Path<String>,
@@ -84,3 +203,42 @@ pub async fn post_airport_status(
     Err(http::StatusCode::INTERNAL_SERVER_ERROR)
   }
 }
+
+pub async fn get_demand(
+  State(mut state): State<AppState>,
+  Path(id): Path<String>,
+) -> Result<String, http::StatusCode> {
+  let res = JobReq::send(
+    TinyReqKind::Demand(Intern::from(id)),
+    &mut state.tiny_sender,
+  )
+  .recv()
+  .await;
+  if let Ok(ResKind::Demand(curve)) = res {
+    if let Ok(string) = serde_json::to_string(&curve) {
+      Ok(string)
+    } else {
+      Err(http::StatusCode::BAD_REQUEST)
+    }
+  } else {
+    Err(http::StatusCode::INTERNAL_SERVER_ERROR)
+  }
+}
+
+pub async fn post_demand(
+  State(mut state): State<AppState>,
+  Path(id): Path<String>,
+  Json(points): Json<Vec<DemandPoint>>,
+) -> Result<(), http::StatusCode> {
+  let res = JobReq::send(
+    TinyReqKind::SetDemand(Intern::from(id), points),
+    &mut state.tiny_sender,
+  )
+  .recv()
+  .await;
+  if let Ok(ResKind::Any) = res {
+    Ok(())
+  } else {
+    Err(http::StatusCode::BAD_REQUEST)
+  }
+}
@@ -1,39 +1,92 @@
+pub mod auth;
 pub mod methods;
 pub mod shared;
 
-use std::{fs, net::SocketAddr};
+use std::{fs, net::SocketAddr, path::PathBuf};
 
+use auth::{Auth, require_role};
 use axum::{
   Router,
+  http::{HeaderName, HeaderValue, Method, header},
+  middleware,
   response::Html,
   routing::{get, post},
 };
 use methods::{
   aircraft::{get_aircraft, get_one_aircraft},
   comms::{comms_text, comms_voice},
+  instructor::{get_scenario, get_session_log, post_instructor},
   misc::{ping_pong, post_pause},
-  state::{get_airport_status, get_messages, get_world, post_airport_status},
+  positions::{
+    get_controllers, get_coordination, post_coordination, post_login,
+    post_logout,
+  },
+  state::{
+    get_airport_score, get_airport_stats, get_airport_status, get_demand,
+    get_economy, get_messages, get_score, get_templates, get_world,
+    post_airport_status, post_demand, post_purchase,
+  },
 };
 use serde::{Deserialize, Serialize};
-use shared::{AppState, GetSender, PostSender};
+use shared::{AppState, SESSION_HEADER};
 
-use engine::engine::UICommand;
+use engine::{assets::assets_root, engine::UICommand};
 use tower_http::{
-  compression::CompressionLayer, cors::CorsLayer, services::ServeDir,
+  compression::CompressionLayer,
+  cors::{AllowOrigin, CorsLayer},
+  services::ServeDir,
 };
 
+fn client_path() -> PathBuf {
+  assets_root().join("client-web")
+}
+
+/// Allows any origin if `origins` is `None`, otherwise only those listed.
+fn cors_layer(origins: Option<&[String]>) -> CorsLayer {
+  let Some(origins) = origins else {
+    return CorsLayer::very_permissive();
+  };
+
+  let origins: Vec<HeaderValue> = origins
+    .iter()
+    .filter_map(|o| match o.parse() {
+      Ok(origin) => Some(origin),
+      Err(_) => {
+        tracing::warn!("Ignoring invalid CORS origin: {o}");
+        None
+      }
+    })
+    .collect();
+
+  CorsLayer::new()
+    .allow_origin(AllowOrigin::list(origins))
+    .allow_methods([Method::GET, Method::POST])
+    .allow_headers([
+      header::AUTHORIZATION,
+      header::CONTENT_TYPE,
+      HeaderName::from_static(SESSION_HEADER),
+    ])
+}
+
+/// Who may reach the API: the tokens it accepts and the origins browsers may
+/// call it from.
+pub struct Access {
+  pub auth: Auth,
+  pub cors_origins: Option<Vec<String>>,
+}
+
 pub async fn run(
   no_api: bool,
   no_client: bool,
   no_server: bool,
   address_ipv4: SocketAddr,
   address_ipv6: SocketAddr,
-  get_sender: GetSender,
-  post_sender: PostSender,
+  state: AppState,
+  access: Access,
 ) {
-  let cors = CorsLayer::very_permissive();
+  let Access { auth, cors_origins } = access;
 
-  let mut app = Router::new().layer(CompressionLayer::new()).layer(cors);
+  let mut app = Router::new();
   if !no_server {
     let mut api = Router::new()
       // Misc
@@ -44,7 +97,19 @@ pub async fn run(
       // State
       .route("/messages", get(get_messages))
       .route("/world", get(get_world))
-      .route("/status/{id}", get(get_airport_status));
+      .route("/status/{id}", get(get_airport_status))
+      .route("/stats/{id}", get(get_airport_stats))
+      .route("/score", get(get_score))
+      .route("/score/{id}", get(get_airport_score))
+      .route("/economy/{id}", get(get_economy))
+      .route("/economy/{id}/templates", get(get_templates))
+      .route("/traffic/{id}", get(get_demand))
+      // Positions
+      .route("/positions", get(get_controllers))
+      .route("/coordination", get(get_coordination))
+      // Instructor
+      .route("/instructor/log", get(get_session_log))
+      .route("/scenario", get(get_scenario));
 
     if !no_api {
       api = api
@@ -55,7 +120,15 @@ pub async fn run(
         .route("/comms/text", post(comms_text))
         .route("/comms/voice", post(comms_voice))
         // State
-        .route("/status/{id}", post(post_airport_status));
+        .route("/status/{id}", post(post_airport_status))
+        .route("/economy/{id}/build/{template}", post(post_purchase))
+        .route("/traffic/{id}", post(post_demand))
+        // Positions
+        .route("/positions/login", post(post_login))
+        .route("/positions/logout", post(post_logout))
+        .route("/coordination", post(post_coordination))
+        // Instructor
+        .route("/instructor", post(post_instructor));
       tracing::info!("Serving API.");
     } else {
       api =
@@ -63,20 +136,27 @@ pub async fn run(
       tracing::info!("Serving API in readonly mode.");
     }
 
+    if auth.is_enabled() {
+      tracing::info!("API requires a token.");
+    }
+
     app = app.nest(
       "/api",
-      api.with_state(AppState::new(get_sender, post_sender)),
+      api
+        .route_layer(middleware::from_fn_with_state(auth, require_role))
+        .with_state(state),
     );
   }
 
   if !no_client {
-    app = app.fallback_service(ServeDir::new("assets/client-web"));
+    app = app.fallback_service(ServeDir::new(client_path()));
     tracing::info!("Serving web client.");
   }
 
   if no_api {
     async fn serve_demo() -> Result<Html<String>, ()> {
-      if let Ok(content) = fs::read_to_string("assets/client-web/index.html") {
+      if let Ok(content) = fs::read_to_string(client_path().join("index.html"))
+      {
         Ok(Html(content.replace("class=\"\"", "class=\"demo\"")))
       } else {
         Err(())
@@ -86,6 +166,10 @@ pub async fn run(
     app = app.route("/", get(serve_demo));
   }
 
+  let app = app
+    .layer(CompressionLayer::new())
+    .layer(cors_layer(cors_origins.as_deref()));
+
   let listener4 = tokio::net::TcpListener::bind(address_ipv4).await.unwrap();
   let listener6 = tokio::net::TcpListener::bind(address_ipv6).await.unwrap();
 
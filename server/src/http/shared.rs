@@ -1,3 +1,5 @@
+use axum::http::HeaderMap;
+use internment::Intern;
 use tokio::sync::mpsc;
 
 use crate::{
@@ -8,6 +10,16 @@ use crate::{
 pub type GetSender = mpsc::UnboundedSender<JobReq<TinyReqKind, ResKind>>;
 pub type PostSender = mpsc::UnboundedSender<JobReq<ArgReqKind, ResKind>>;
 
+/// The header that carries a controller's session ID.
+pub const SESSION_HEADER: &str = "x-airwave-session";
+
+pub fn session(headers: &HeaderMap) -> Option<Intern<String>> {
+  headers
+    .get(SESSION_HEADER)
+    .and_then(|v| v.to_str().ok())
+    .map(Intern::from_ref)
+}
+
 #[derive(Debug, Clone)]
 pub struct AppState {
   pub tiny_sender: GetSender,
@@ -0,0 +1,83 @@
+use engine::entities::aircraft::{AircraftKind, Emergency, FlightSegment};
+use serde::{Deserialize, Serialize};
+
+/// Where and how a spawned aircraft starts out.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+#[serde(tag = "type")]
+pub enum SpawnState {
+  /// Parked at a gate (or any free gate) at the origin, ready for taxi.
+  Gate {
+    #[serde(default)]
+    gate: Option<String>,
+  },
+  /// Inbound at the edge of the destination's airspace.
+  Arrival,
+  /// In the air at a position, on the way to the destination.
+  Airborne {
+    pos: (f32, f32),
+    altitude: f32,
+    heading: f32,
+    speed: f32,
+  },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpawnRequest {
+  /// A random callsign is generated if not given.
+  #[serde(default)]
+  pub callsign: Option<String>,
+  #[serde(rename = "type", default)]
+  pub kind: AircraftKind,
+  pub origin: String,
+  pub destination: String,
+  pub state: SpawnState,
+}
+
+/// An action taken by an instructor during a session.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+#[serde(tag = "type", content = "value")]
+pub enum InstructorAction {
+  Spawn(SpawnRequest),
+  Delete {
+    id: String,
+  },
+  Teleport {
+    id: String,
+    pos: (f32, f32),
+    #[serde(default)]
+    altitude: Option<f32>,
+    #[serde(default)]
+    heading: Option<f32>,
+  },
+  SetSegment {
+    id: String,
+    segment: FlightSegment,
+  },
+  Emergency {
+    id: String,
+    emergency: Emergency,
+  },
+  /// Has the pilot call up with a request, such as "request higher".
+  PilotRequest {
+    id: String,
+    text: String,
+  },
+  CloseRunway {
+    airport: String,
+    runway: String,
+    closed: bool,
+  },
+  FailNavaid {
+    name: String,
+    failed: bool,
+  },
+}
+
+/// An instructor action, and the tick it was taken on.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LogEntry {
+  pub tick: usize,
+  pub action: InstructorAction,
+}
@@ -10,12 +10,17 @@ use union_find::{QuickUnionUf, UnionBySize, UnionFind};
 
 pub mod config;
 pub mod http;
+pub mod instructor;
 pub mod job;
 pub mod parser;
+pub mod positions;
 pub mod prompter;
 pub mod ring;
 pub mod runner;
+pub mod scenario;
+pub mod schedule;
 pub mod signal_gen;
+pub mod traffic;
 
 pub static CLI: LazyLock<Cli> = LazyLock::new(Cli::parse);
 pub static PROJECT_DIRS: LazyLock<ProjectDirs> = LazyLock::new(|| {
@@ -48,6 +53,10 @@ pub struct Cli {
   /// The path to the config file.
   #[arg(short, long, default_value = None)]
   pub config_path: Option<PathBuf>,
+  /// The directory to read game assets from. Overrides `assets` in the
+  /// config.
+  #[arg(long, default_value = None)]
+  pub assets_path: Option<PathBuf>,
 
   /// Overrides the directory path to store log files.
   #[arg(long)]
@@ -66,6 +75,21 @@ pub struct Cli {
   pub logs_file_min_level: LogLevel,
 }
 
+/// Finds the assets directory when none is configured: next to the binary,
+/// then in the user's data directory, then in the working directory.
+pub fn default_assets_root() -> PathBuf {
+  let next_to_binary = std::env::current_exe()
+    .ok()
+    .and_then(|exe| exe.parent().map(|dir| dir.join("assets")));
+  let data_dir = Some(PROJECT_DIRS.data_dir().join("assets"));
+
+  next_to_binary
+    .into_iter()
+    .chain(data_dir)
+    .find(|dir| dir.is_dir())
+    .unwrap_or_else(|| PathBuf::from("assets"))
+}
+
 pub fn merge_points(points: &[Vec2], min_distance: f32) -> Vec<Vec2> {
   let n = points.len();
   let mut uf = QuickUnionUf::<UnionBySize>::new(points.len());
@@ -1,18 +1,28 @@
 use core::str::FromStr;
-use std::{fs, path::PathBuf, time::Instant};
+use std::{
+  fs,
+  path::PathBuf,
+  time::{Duration, Instant},
+};
 
 use tokio::sync::mpsc;
 use tracing_appender::rolling::Rotation;
 use tracing_subscriber::prelude::*;
 use turborand::{SeededCore, rng::Rng};
 
-use engine::entities::airport::Airport;
+use engine::{
+  assets::{assets_root, set_assets_root},
+  entities::airport::Airport,
+};
 use server::{
   CLI, Cli, PROJECT_DIRS,
   config::Config,
-  http,
+  default_assets_root,
+  http::{self, auth::Auth, shared::AppState},
   job::JobReq,
   runner::{ArgReqKind, ResKind, Runner, TinyReqKind},
+  scenario::Scenario,
+  schedule::Schedule,
 };
 
 #[tokio::main]
@@ -25,6 +35,7 @@ async fn main() {
     address_ipv6,
     ref audio_path,
     ref config_path,
+    ref assets_path,
     ref logs_path,
     logs_max_files,
     logs_rotation,
@@ -90,11 +101,22 @@ async fn main() {
     Config::default()
   };
 
+  let root = assets_path
+    .clone()
+    .or_else(|| config.assets().map(|p| p.to_owned()))
+    .unwrap_or_else(default_assets_root);
+  tracing::info!("Reading assets from {}.", root.display());
+  if let Err(e) = set_assets_root(root) {
+    tracing::error!("{e}");
+    std::process::exit(1);
+  }
+
   let (get_tx, get_rx) =
     mpsc::unbounded_channel::<JobReq<TinyReqKind, ResKind>>();
   let (post_tx, post_rx) =
     mpsc::unbounded_channel::<JobReq<ArgReqKind, ResKind>>();
 
+  let mut runner_handle = None;
   if !no_server {
     let seed = config.world().seed();
 
@@ -102,97 +124,173 @@ async fn main() {
 
     let rng = Rng::with_seed(seed);
     let mut world_rng = Rng::with_seed(0);
-    let mut runner = Runner::new(
-      get_rx,
-      post_rx,
-      Some(PathBuf::from_str("assets/world.json").unwrap()),
-      rng,
-    );
-
-    runner.engine.load_assets();
-
-    let mut main_airport: Airport = match config.world().airport() {
-      Some(id) => match runner.engine.airport(id) {
-        Some(airport) => {
-          tracing::info!(r#"Using airport: "{}""#, airport.id);
-          airport.clone()
-        }
-        None => {
-          tracing::error!(
-            r#"Failed to load airport "{id}": Could not find assets "{id}.json" or "{id}.lua" (assets are case-sensetive)."#
-          );
-          std::process::exit(1);
-        }
-      },
-      None => match runner.engine.default_airport() {
-        Some(airport) => {
-          tracing::info!(r#"Using default airport: "{}""#, airport.id);
-          airport.clone()
-        }
-        None => {
-          tracing::error!("Could not find default airport");
-          std::process::exit(1);
-        }
-      },
-    };
-    if let Some(frequencies) = config.frequencies() {
-      main_airport.frequencies = frequencies.clone();
-    }
-
-    let main_frequencies = main_airport.frequencies.clone();
-    let main_id = main_airport.id;
+    let mut runner =
+      Runner::new(get_rx, post_rx, Some(assets_root().join("world.json")), rng);
 
-    runner.engine.world.airports.push(main_airport);
+    if let Err(e) = runner.engine.load_assets() {
+      tracing::error!("{e}");
+      std::process::exit(1);
+    }
 
-    runner.generate_airports(&mut world_rng, &main_frequencies);
-    runner.generate_waypoints();
+    runner.set_traffic(config.world().traffic().clone());
+    if let Some(path) = config.world().scenario() {
+      let scenario = Scenario::from_path(path).and_then(|scenario| {
+        runner.load_scenario(&scenario)?;
+        Ok(scenario)
+      });
+      match scenario {
+        Ok(scenario) => tracing::info!(
+          r#"Loaded scenario "{}" with {} aircraft"#,
+          scenario.name,
+          scenario.aircraft.len()
+        ),
+        Err(e) => {
+          tracing::error!("{e}");
+          std::process::exit(1);
+        }
+      }
+    } else {
+      let mut main_airport: Airport = match config.world().airport() {
+        Some(id) => match runner.engine.airport(id) {
+          Some(airport) => {
+            tracing::info!(r#"Using airport: "{}""#, airport.id);
+            airport.clone()
+          }
+          None => {
+            tracing::error!(
+              r#"Failed to load airport "{id}": Could not find assets "{id}.json" or "{id}.lua" (assets are case-sensetive)."#
+            );
+            std::process::exit(1);
+          }
+        },
+        None => match runner.engine.default_airport() {
+          Some(airport) => {
+            tracing::info!(r#"Using default airport: "{}""#, airport.id);
+            airport.clone()
+          }
+          None => {
+            tracing::error!("Could not find default airport");
+            std::process::exit(1);
+          }
+        },
+      };
+      if let Some(frequencies) = config.frequencies() {
+        main_airport.frequencies = frequencies.clone();
+      }
 
-    runner
-      .engine
-      .world
-      .airport_statuses
-      .insert(main_id, config.world().status());
+      let main_frequencies = main_airport.frequencies.clone();
+      let main_id = main_airport.id;
 
-    runner.fill_gates();
+      runner.engine.world.airports.push(main_airport);
+      for name in config.world().airports() {
+        if let Err(e) = runner.add_real_airport(main_id, name) {
+          tracing::error!(r#"Failed to add airport "{name}": {e}"#);
+          std::process::exit(1);
+        }
+      }
 
-    //
+      runner.generate_airports(&mut world_rng, &main_frequencies);
+      runner.generate_waypoints();
+
+      runner
+        .engine
+        .world
+        .airport_statuses
+        .insert(main_id, config.world().status());
+
+      runner.fill_gates();
+
+      runner.random_traffic = config.world().random_traffic();
+      if let Some(path) = config.world().schedule() {
+        match Schedule::from_path(path) {
+          Ok(schedule) => {
+            tracing::info!(
+              "Loaded {} scheduled flights from {:?}",
+              schedule.flights.len(),
+              path
+            );
+            runner.set_schedule(schedule);
+          }
+          Err(e) => {
+            tracing::error!("{e}");
+            std::process::exit(1);
+          }
+        }
+      }
 
-    tracing::info!("Quick start loop (this may take a minute)...");
-    let start = Instant::now();
-    let ticks_ran = runner.quick_start();
-    let duration = start.elapsed();
-    let simulated_seconds =
-      ticks_ran as f32 / runner.engine.tick_rate_tps as f32;
-    let simulated_minutes = (simulated_seconds / 60.0).floor();
-    tracing::info!(
-      "Simulated {} ticks (relative time: {:.0}m{:.0}s) in {:.2} secs (approx. {:.2}x speed).",
-      ticks_ran,
-      simulated_minutes,
-      simulated_seconds % 60.0,
-      duration.as_secs_f32(),
-      simulated_seconds / duration.as_secs_f32()
-    );
+      //
+
+      if config.world().traffic().en_route {
+        tracing::info!("Quick start loop (this may take a minute)...");
+        let start = Instant::now();
+        let ticks_ran = runner.quick_start();
+        let duration = start.elapsed();
+        let simulated_seconds =
+          ticks_ran as f32 / runner.engine.tick_rate_tps as f32;
+        let simulated_minutes = (simulated_seconds / 60.0).floor();
+        tracing::info!(
+          "Simulated {} ticks (relative time: {:.0}m{:.0}s) in {:.2} secs (approx. {:.2}x speed).",
+          ticks_ran,
+          simulated_minutes,
+          simulated_seconds % 60.0,
+          duration.as_secs_f32(),
+          simulated_seconds / duration.as_secs_f32()
+        );
+      } else {
+        tracing::info!("Skipping quick start, arrivals spawn at the airspace.");
+      }
+    }
 
     tracing::info!("Starting game loop...");
 
     runner.reset_signal_gens();
+    // Start the books fresh, without the quick start's operating costs.
+    runner.engine.game.economies.clear();
     runner.engine.game.paused = config.world().paused();
-    tokio::task::spawn_blocking(move || runner.begin_loop());
+    runner_handle =
+      Some(tokio::task::spawn_blocking(move || runner.begin_loop()));
   }
 
   let address_ipv4 = address_ipv4.unwrap_or(config.server().address_ipv4);
   let address_ipv6 = address_ipv6.unwrap_or(config.server().address_ipv6);
 
-  let _ = tokio::spawn(http::run(
+  let tokens = &config.server().tokens;
+  for token in tokens.iter() {
+    let name = token.name.as_deref().unwrap_or("unnamed");
+    tracing::info!("Loaded API token for {name} ({:?})", token.role);
+  }
+  let auth = Auth::new(tokens.iter().map(|t| (t.token.clone(), t.role)));
+
+  let http_handle = tokio::spawn(http::run(
     no_api,
     no_client,
     no_server,
     address_ipv4,
     address_ipv6,
-    get_tx,
-    post_tx,
-  ))
-  .await;
+    AppState::new(get_tx, post_tx),
+    http::Access {
+      auth,
+      cors_origins: config.server().cors_origins.clone(),
+    },
+  ));
+  let abort_handle = http_handle.abort_handle();
+
+  tokio::select! {
+    _ = http_handle => {}
+    _ = tokio::signal::ctrl_c() => {
+      tracing::info!("Shutting down...");
+    }
+  }
+
+  // Dropping the server's senders ends the session, which lets the runner
+  // log its final summary before exiting.
+  abort_handle.abort();
+  // Open connections may hold on to senders, so don't wait forever.
+  if let Some(handle) = runner_handle {
+    let _ = tokio::time::timeout(Duration::from_secs(5), handle).await;
+  }
+
+  std::process::exit(0);
 }
 
 fn setup_logging(
@@ -34,6 +34,46 @@ fn parse_altitude(mut parts: Iter<&str>) -> Option<Task> {
   None
 }
 
+fn parse_clearance(mut parts: Iter<&str>) -> Option<Task> {
+  let aliases = ["clr", "clear", "cleared"];
+  if parts.next().map(|f| aliases.contains(f)) == Some(true) {
+    let route = parts.next().map(|a| Intern::from(a.to_uppercase()));
+    let initial_altitude = parts
+      .next()
+      .and_then(|a| a.parse::<f32>().ok())
+      .map(|a| a * 100.0);
+    let departure_frequency = parts.next().and_then(|a| a.parse::<f32>().ok());
+    // The squawk is optional, and assigned from a pool if left out.
+    let squawk = match parts.next() {
+      Some(a) if a.len() == 4 && a.chars().all(|c| c.is_ascii_digit()) => {
+        u16::from_str_radix(a, 8).ok().map(Some)
+      }
+      Some(_) => None,
+      None => Some(None),
+    };
+
+    // End of input.
+    if parts.next().is_none() {
+      if let (
+        Some(route),
+        Some(initial_altitude),
+        Some(departure_frequency),
+        Some(squawk),
+      ) = (route, initial_altitude, departure_frequency, squawk)
+      {
+        return Some(Task::Clearance {
+          route,
+          initial_altitude,
+          squawk,
+          departure_frequency,
+        });
+      }
+    }
+  }
+
+  None
+}
+
 fn parse_direct(mut parts: Iter<&str>) -> Option<Task> {
   let aliases = ["d", "dt", "direct"];
   if parts.next().map(|f| aliases.contains(f)) == Some(true) {
@@ -165,6 +205,24 @@ fn parse_speed(mut parts: Iter<&str>) -> Option<Task> {
   None
 }
 
+fn parse_squawk(mut parts: Iter<&str>) -> Option<Task> {
+  let aliases = ["sq", "squawk"];
+  if parts.next().map(|f| aliases.contains(f)) == Some(true) {
+    let code = parts
+      .next()
+      .filter(|a| a.len() == 4 && a.chars().all(|c| c.is_ascii_digit()))
+      .and_then(|a| u16::from_str_radix(a, 8).ok())
+      .map(Task::Squawk);
+
+    // End of input.
+    if parts.next().is_none() {
+      return code;
+    }
+  }
+
+  None
+}
+
 fn parse_taxi(mut parts: Iter<&str>) -> Option<Task> {
   let aliases = ["tx", "taxi"];
 
@@ -328,6 +386,7 @@ where
 
   let parsers = [
     parse_altitude,
+    parse_clearance,
     parse_direct,
     parse_frequency,
     parse_go_around,
@@ -336,6 +395,7 @@ where
     parse_land,
     parse_resume_own_navigation,
     parse_speed,
+    parse_squawk,
     parse_taxi,
     parse_taxi_continue,
     parse_taxi_hold,
@@ -391,6 +451,117 @@ where
   commands
 }
 
+/// The value of a spoken number word, and whether it is a multiple of ten
+/// that a digit can be added to (as in "twenty five").
+fn number_word(word: &str) -> Option<(f32, bool)> {
+  let digits = [
+    "zero", "one", "two", "three", "four", "five", "six", "seven", "eight",
+    "nine",
+  ];
+  let teens = [
+    "ten",
+    "eleven",
+    "twelve",
+    "thirteen",
+    "fourteen",
+    "fifteen",
+    "sixteen",
+    "seventeen",
+    "eighteen",
+    "nineteen",
+  ];
+  let tens = [
+    "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty",
+    "ninety",
+  ];
+
+  if word == "niner" {
+    Some((9.0, false))
+  } else if let Some(i) = digits.iter().position(|d| *d == word) {
+    Some((i as f32, false))
+  } else if let Some(i) = teens.iter().position(|d| *d == word) {
+    Some((10.0 + i as f32, false))
+  } else {
+    tens
+      .iter()
+      .position(|d| *d == word)
+      .map(|i| (20.0 + i as f32 * 10.0, true))
+  }
+}
+
+/// Parses the altitude, in feet, that a pilot reads back, such as "four
+/// thousand feet", "one zero thousand" or "flight level two seven zero".
+pub fn parse_readback_altitude<T>(readback: T) -> Option<f32>
+where
+  T: AsRef<str>,
+{
+  // Keep "5,000" as one number, but let other commas end one.
+  let readback = Regex::new(r"(\d),(\d)")
+    .unwrap()
+    .replace_all(&readback.as_ref().to_lowercase(), "$1$2")
+    .replace('-', " ")
+    .replace(',', " , ");
+  let words: Vec<&str> = readback
+    .split_whitespace()
+    .map(|w| w.trim_matches(|c: char| !c.is_ascii_alphanumeric()))
+    .collect();
+
+  let mut i = 0;
+  while i < words.len() {
+    let flight_level = (words[i] == "flight"
+      && words.get(i + 1) == Some(&"level"))
+      || words[i] == "fl";
+    if flight_level {
+      i += if words[i] == "fl" { 1 } else { 2 };
+    }
+
+    // Read the number starting here, if there is one.
+    let (mut total, mut current) = (0.0, 0.0);
+    let (mut after_tens, mut scaled, mut len) = (false, false, 0);
+    for word in words.iter().skip(i) {
+      if let Ok(number) = word.parse::<f32>() {
+        current = number;
+        scaled |= number >= 1000.0;
+      } else if let Some((value, tens)) = number_word(word) {
+        current = if after_tens {
+          current + value
+        } else if value >= 10.0 {
+          current * 100.0 + value
+        } else {
+          current * 10.0 + value
+        };
+        after_tens = tens;
+      } else if *word == "thousand" || *word == "hundred" {
+        let scale = if *word == "thousand" { 1000.0 } else { 100.0 };
+        total += current * scale;
+        current = 0.0;
+        after_tens = false;
+        scaled = true;
+      } else {
+        break;
+      }
+
+      len += 1;
+    }
+    let number = total + current;
+
+    if len > 0 {
+      if flight_level {
+        return Some(number * 100.0);
+      }
+
+      let feet = matches!(words.get(i + len), Some(&"feet") | Some(&"ft"));
+      if scaled || feet {
+        return Some(number);
+      }
+    }
+
+    i += len.max(1);
+  }
+
+  None
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -475,6 +646,52 @@ mod tests {
     assert_eq!(parse_tasks("direct ABCD EFGH"), vec![]);
   }
 
+  #[test]
+  fn parse_clearance() {
+    let clearance = Task::Clearance {
+      route: Intern::from_ref("KLAX"),
+      initial_altitude: 5000.0,
+      squawk: Some(0o4521),
+      departure_frequency: 118.6,
+    };
+
+    // Alias variants.
+    assert_eq!(
+      parse_tasks("clr KLAX 50 118.6 4521"),
+      vec![clearance.clone()]
+    );
+    assert_eq!(
+      parse_tasks("clear KLAX 50 118.6 4521"),
+      vec![clearance.clone()]
+    );
+    assert_eq!(
+      parse_tasks("cleared KLAX 50 118.6 4521"),
+      vec![clearance.clone()]
+    );
+
+    // Argument variants.
+    assert_eq!(
+      parse_tasks("clr klax 50 118.6 4521"),
+      vec![clearance.clone()]
+    );
+    assert_eq!(
+      parse_tasks("clr KLAX 50 118.6"),
+      vec![Task::Clearance {
+        route: Intern::from_ref("KLAX"),
+        initial_altitude: 5000.0,
+        squawk: None,
+        departure_frequency: 118.6,
+      }]
+    );
+
+    // Invalid.
+    assert_eq!(parse_tasks("clr"), vec![]);
+    assert_eq!(parse_tasks("clr KLAX 50"), vec![]);
+    assert_eq!(parse_tasks("clr KLAX 50 118.6 4581"), vec![]);
+    assert_eq!(parse_tasks("clr KLAX ABCD 118.6 4521"), vec![]);
+    assert_eq!(parse_tasks("clr KLAX 50 118.6 4521 ABCD"), vec![]);
+  }
+
   #[test]
   fn parse_frequency() {
     // Alias variants.
@@ -611,6 +828,25 @@ mod tests {
     assert_eq!(parse_tasks("speed ABCD"), vec![]);
   }
 
+  #[test]
+  fn parse_squawk() {
+    // Alias variants.
+    assert_eq!(parse_tasks("sq 4521"), vec![Task::Squawk(0o4521)]);
+    assert_eq!(parse_tasks("squawk 4521"), vec![Task::Squawk(0o4521)]);
+
+    // Argument variants.
+    assert_eq!(parse_tasks("sq 0012"), vec![Task::Squawk(0o0012)]);
+    assert_eq!(parse_tasks("sq 7700"), vec![Task::Squawk(0o7700)]);
+
+    // Invalid.
+    assert_eq!(parse_tasks("sq"), vec![]);
+    assert_eq!(parse_tasks("sq 4581"), vec![]);
+    assert_eq!(parse_tasks("sq 452"), vec![]);
+    assert_eq!(parse_tasks("sq +452"), vec![]);
+    assert_eq!(parse_tasks("sq 45210"), vec![]);
+    assert_eq!(parse_tasks("sq 4521 ABCD"), vec![]);
+  }
+
   #[test]
   fn parse_taxi() {
     // Argument variants.
@@ -848,4 +1084,42 @@ mod tests {
     assert_eq!(parse_tasks("delete 27L ABCD"), vec![]);
     assert_eq!(parse_tasks("delete ABCD"), vec![]);
   }
+
+  #[test]
+  fn parse_readback_altitude_spoken() {
+    assert_eq!(
+      parse_readback_altitude("Descend and maintain four-thousand feet."),
+      Some(4000.0)
+    );
+    assert_eq!(
+      parse_readback_altitude("Climb and maintain one zero thousand."),
+      Some(10000.0)
+    );
+    assert_eq!(
+      parse_readback_altitude("Maintain eleven thousand five hundred."),
+      Some(11500.0)
+    );
+    assert_eq!(
+      parse_readback_altitude("Flight level two seven zero."),
+      Some(27000.0)
+    );
+    assert_eq!(
+      parse_readback_altitude("Maintain 5,000 feet."),
+      Some(5000.0)
+    );
+    assert_eq!(
+      parse_readback_altitude("Left heading two seven zero, four thousand."),
+      Some(4000.0)
+    );
+
+    // No altitude.
+    assert_eq!(
+      parse_readback_altitude("Left turn heading two seven zero."),
+      None
+    );
+    assert_eq!(
+      parse_readback_altitude("Cleared for takeoff, runway two two."),
+      None
+    );
+  }
 }
@@ -0,0 +1,327 @@
+use std::{
+  collections::HashMap,
+  time::{Duration, Instant},
+};
+
+use engine::entities::{
+  airport::Frequencies,
+  world::{AirportStatus, World},
+};
+use internment::Intern;
+use serde::{Deserialize, Serialize};
+
+#[derive(
+  Debug,
+  Clone,
+  Copy,
+  PartialEq,
+  Eq,
+  Hash,
+  PartialOrd,
+  Ord,
+  Serialize,
+  Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+  /// Ground, including clearance delivery.
+  Ground,
+  Tower,
+  /// Approach and departure.
+  Approach,
+  Center,
+}
+
+impl Role {
+  pub fn frequencies(&self, frequencies: &Frequencies) -> Vec<f32> {
+    match self {
+      Self::Ground => core::iter::once(frequencies.ground)
+        .chain(frequencies.clearance)
+        .collect(),
+      Self::Tower => vec![frequencies.tower],
+      Self::Approach => vec![frequencies.approach, frequencies.departure],
+      Self::Center => vec![frequencies.center],
+    }
+  }
+
+  pub fn is_air(&self) -> bool {
+    !matches!(self, Self::Ground)
+  }
+}
+
+/// A controller position at an airport.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Position {
+  pub airport: Intern<String>,
+  pub role: Role,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Controller {
+  pub name: String,
+  pub positions: Vec<Position>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PositionError {
+  UnknownAirport(Intern<String>),
+  Taken(Position),
+}
+
+impl core::fmt::Display for PositionError {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    match self {
+      Self::UnknownAirport(id) => write!(f, "unknown airport {id}"),
+      Self::Taken(position) => write!(
+        f,
+        "{:?} at {} is already taken",
+        position.role, position.airport
+      ),
+    }
+  }
+}
+
+/// How long a session lasts without being used.
+pub const SESSION_TIMEOUT: Duration = Duration::from_secs(60 * 60);
+
+/// Controllers logged in to the session, by their session ID.
+///
+/// A frequency that belongs to a held position can only be used by the
+/// controller holding it. Frequencies that no one holds are open to anyone
+/// who isn't logged in, so a single player doesn't need to log in at all.
+#[derive(Debug, Default)]
+pub struct Positions {
+  controllers: HashMap<Intern<String>, Controller>,
+  /// When each session was last used.
+  last_seen: HashMap<Intern<String>, Instant>,
+  /// The status of each airport from before anyone held a position there.
+  base: HashMap<Intern<String>, AirportStatus>,
+}
+
+impl Positions {
+  pub fn controllers(&self) -> impl Iterator<Item = &Controller> {
+    self.controllers.values()
+  }
+
+  pub fn holder(&self, position: &Position) -> Option<Intern<String>> {
+    self
+      .controllers
+      .iter()
+      .find(|(_, c)| c.positions.contains(position))
+      .map(|(id, _)| *id)
+  }
+
+  /// Logs in a controller, returning their session ID.
+  pub fn login(
+    &mut self,
+    world: &World,
+    controller: Controller,
+  ) -> Result<Intern<String>, PositionError> {
+    for position in controller.positions.iter() {
+      if world.airport(position.airport).is_none() {
+        return Err(PositionError::UnknownAirport(position.airport));
+      }
+      if self.holder(position).is_some() {
+        return Err(PositionError::Taken(*position));
+      }
+    }
+
+    let id = session_id();
+    self.controllers.insert(id, controller);
+    self.last_seen.insert(id, Instant::now());
+
+    Ok(id)
+  }
+
+  pub fn logout(&mut self, id: Intern<String>) -> Option<Controller> {
+    self.last_seen.remove(&id);
+    self.controllers.remove(&id)
+  }
+
+  /// Marks a session as used.
+  pub fn touch(&mut self, id: Intern<String>, now: Instant) {
+    if let Some(last_seen) = self.last_seen.get_mut(&id) {
+      *last_seen = now;
+    }
+  }
+
+  /// Logs out the controllers whose sessions have timed out.
+  pub fn expire(&mut self, now: Instant) -> Vec<Controller> {
+    let expired: Vec<Intern<String>> = self
+      .last_seen
+      .iter()
+      .filter(|(_, t)| now.saturating_duration_since(**t) > SESSION_TIMEOUT)
+      .map(|(id, _)| *id)
+      .collect();
+
+    expired
+      .into_iter()
+      .filter_map(|id| self.logout(id))
+      .collect()
+  }
+
+  /// Whether the controller (or anyone not logged in, if `None`) may transmit
+  /// on a frequency.
+  pub fn can_transmit(
+    &self,
+    world: &World,
+    controller: Option<Intern<String>>,
+    frequency: f32,
+  ) -> bool {
+    let mut owners = self
+      .controllers
+      .iter()
+      .filter(|(_, c)| {
+        c.positions.iter().any(|p| {
+          world.airport(p.airport).is_some_and(|a| {
+            p.role.frequencies(&a.frequencies).contains(&frequency)
+          })
+        })
+      })
+      .map(|(id, _)| *id)
+      .peekable();
+
+    match controller {
+      Some(controller) => owners.any(|id| id == controller),
+      None => owners.peek().is_none(),
+    }
+  }
+
+  /// Works out the automation for an airport from the positions held there.
+  /// Once no one holds a position, the airport returns to the status it had
+  /// before.
+  pub fn status(
+    &mut self,
+    airport: Intern<String>,
+    current: AirportStatus,
+  ) -> AirportStatus {
+    let held: Vec<Role> = self
+      .controllers
+      .values()
+      .flat_map(|c| c.positions.iter())
+      .filter(|p| p.airport == airport)
+      .map(|p| p.role)
+      .collect();
+    if held.is_empty() {
+      return self.base.remove(&airport).unwrap_or(current);
+    }
+
+    self.base.entry(airport).or_insert(current);
+    AirportStatus {
+      automate_air: !held.iter().any(Role::is_air),
+      automate_ground: !held.contains(&Role::Ground),
+      ..current
+    }
+  }
+}
+
+/// A session ID that can't be guessed, from the system's secure source of
+/// randomness.
+fn session_id() -> Intern<String> {
+  let mut bytes = [0u8; 16];
+  getrandom::getrandom(&mut bytes).expect("no secure source of randomness");
+
+  Intern::from(bytes.iter().map(|b| format!("{b:02x}")).collect::<String>())
+}
+
+#[cfg(test)]
+mod tests {
+  use engine::entities::airport::Airport;
+  use glam::Vec2;
+
+  use super::*;
+
+  fn world() -> World {
+    let mut airport = Airport::new(Intern::from_ref("KSFO"), Vec2::ZERO);
+    airport.frequencies = Frequencies {
+      approach: 124.0,
+      departure: 124.0,
+      tower: 120.5,
+      ground: 121.8,
+      center: 132.5,
+      clearance: None,
+    };
+
+    World {
+      airports: vec![airport],
+      ..Default::default()
+    }
+  }
+
+  #[test]
+  fn test_can_transmit() {
+    let world = world();
+    let mut positions = Positions::default();
+    let tower = positions
+      .login(
+        &world,
+        Controller {
+          name: "Tower".to_owned(),
+          positions: vec![Position {
+            airport: Intern::from_ref("KSFO"),
+            role: Role::Tower,
+          }],
+        },
+      )
+      .unwrap();
+
+    assert!(positions.can_transmit(&world, Some(tower), 120.5));
+    assert!(!positions.can_transmit(&world, None, 120.5));
+    assert!(!positions.can_transmit(&world, Some(tower), 121.8));
+    assert!(positions.can_transmit(&world, None, 121.8));
+  }
+
+  #[test]
+  fn test_status() {
+    let world = world();
+    let id = Intern::from_ref("KSFO");
+    let mut positions = Positions::default();
+    let before = AirportStatus::default();
+
+    let ground = positions
+      .login(
+        &world,
+        Controller {
+          name: "Ground".to_owned(),
+          positions: vec![Position {
+            airport: id,
+            role: Role::Ground,
+          }],
+        },
+      )
+      .unwrap();
+    let status = positions.status(id, before);
+    assert!(status.automate_air);
+    assert!(!status.automate_ground);
+
+    positions.logout(ground);
+    assert_eq!(positions.status(id, status), before);
+  }
+
+  #[test]
+  fn test_expire() {
+    let world = world();
+    let mut positions = Positions::default();
+    let controller = Controller {
+      name: "Tower".to_owned(),
+      positions: vec![Position {
+        airport: Intern::from_ref("KSFO"),
+        role: Role::Tower,
+      }],
+    };
+    let a = positions.login(&world, controller.clone()).unwrap();
+    let b = positions
+      .logout(a)
+      .and_then(|c| positions.login(&world, c).ok());
+    assert_ne!(Some(a), b);
+
+    let now = Instant::now();
+    positions.touch(b.unwrap(), now);
+    assert!(positions.expire(now + SESSION_TIMEOUT / 2).is_empty());
+    assert_eq!(
+      positions.expire(now + SESSION_TIMEOUT * 2),
+      vec![controller]
+    );
+    assert!(!positions.can_transmit(&world, b, 120.5));
+  }
+}
@@ -14,12 +14,18 @@ use serde_json::Value;
 use thiserror::Error;
 
 use engine::{
+  airlines::registry,
+  assets::assets_root,
   command::Tasks,
   entities::aircraft::{Aircraft, AircraftState},
 };
 
 use crate::parser::parse_tasks;
 
+fn prompt_path(name: &str) -> PathBuf {
+  assets_root().join("prompts").join(format!("{name}.json"))
+}
+
 pub async fn send_chatgpt_request(
   prompt: String,
   message: String,
@@ -164,12 +170,14 @@ impl Prompter {
   fn load_prompt(path: PathBuf) -> Result<Vec<String>, LoadPromptError> {
     let prompt = fs::read_to_string(path.clone())
       .map_err(|_| LoadPromptError::FS(path.to_str().unwrap().into()))?;
+    // Imports are relative to the prompt that imports them.
+    let dir = path.parent().map(|p| p.to_owned()).unwrap_or_default();
     let object: PromptObject = serde_json::from_str(&prompt)
       .map_err(|e| LoadPromptError::Deserialize(e, path, prompt))?;
     let mut full_prompt: Vec<String> = Vec::new();
 
-    for path in object.imports {
-      let lines = Self::load_prompt(path.into())?;
+    for import in object.imports {
+      let lines = Self::load_prompt(dir.join(import))?;
       full_prompt.extend(lines);
     }
 
@@ -187,8 +195,9 @@ impl Prompter {
   pub async fn split_request(
     message: String,
   ) -> Result<Vec<CallsignAndRequest>, Error> {
-    let prompt =
-      Self::load_prompt_as_string("assets/prompts/splitter.json".into())?;
+    let mut prompt = Self::load_prompt_as_string(prompt_path("splitter"))?;
+    prompt.push('\n');
+    prompt.push_str(&registry().describe());
     let result = send_chatgpt_request(prompt.clone(), message).await?;
     if let Some(result) = result {
       tracing::warn!("{result}");
@@ -216,8 +225,7 @@ impl Prompter {
   }
 
   pub async fn generate_readback(message: String) -> Result<String, Error> {
-    let prompt =
-      Self::load_prompt_as_string("assets/prompts/readback.json".into())?;
+    let prompt = Self::load_prompt_as_string(prompt_path("readback"))?;
     let result = send_chatgpt_request(prompt.clone(), message).await?;
     if let Some(result) = result {
       Ok(result)
@@ -244,8 +252,7 @@ impl Prompter {
       return Err(Error::NoResult("Unknown aircraft state".into()));
     };
 
-    let path = format!("assets/prompts/{mode}.json");
-    let prompt = Self::load_prompt_as_string(path.clone().into())?;
+    let prompt = Self::load_prompt_as_string(prompt_path(mode))?;
 
     let result =
       send_chatgpt_request(prompt.clone(), split.request.clone()).await?;
@@ -267,19 +274,12 @@ impl Prompter {
 
   /// This is a debug function to dump the prompts into a file for each mode.
   pub fn export_prompts() {
-    use std::path::Path;
-
-    let prompt = Prompter::load_prompt_as_string(
-      Path::new("assets/prompts/air.json").into(),
-    )
-    .unwrap();
+    let prompt = Prompter::load_prompt_as_string(prompt_path("air")).unwrap();
 
     std::fs::write("prompt.air.txt", prompt).unwrap();
 
-    let prompt = Prompter::load_prompt_as_string(
-      Path::new("assets/prompts/ground.json").into(),
-    )
-    .unwrap();
+    let prompt =
+      Prompter::load_prompt_as_string(prompt_path("ground")).unwrap();
 
     std::fs::write("prompt.ground.txt", prompt).unwrap();
   }
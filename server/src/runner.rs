@@ -1,4 +1,5 @@
 use std::{
+  collections::HashMap,
   ops::Div,
   path::PathBuf,
   time::{Duration, Instant},
@@ -14,33 +15,45 @@ use turborand::{TurboRand, rng::Rng};
 use engine::{
   AIRSPACE_PADDING_RADIUS, AIRSPACE_RADIUS, DEFAULT_TICK_RATE_TPS,
   NAUTICALMILES_TO_FEET, WORLD_RADIUS,
-  command::{CommandWithFreq, OutgoingCommandReply, Task},
+  airlines::registry,
+  command::{CommandReply, CommandWithFreq, OutgoingCommandReply, Task},
+  coordination::{Coordination, CoordinationKind},
+  economy::{DELAYED_DEPARTURE_PENALTY, Economy, Template},
   engine::{Engine, EngineConfig, Event},
   entities::{
     aircraft::{
-      Aircraft, AircraftState, FlightSegment,
+      Aircraft, AircraftKind, AircraftState, Emergency, FlightSegment,
       events::{AircraftEvent, EventKind},
     },
-    airport::Frequencies,
-    world::{AirportStatus, World},
+    airport::{Frequencies, Gate},
+    world::{AirportStats, AirportStatus, World},
   },
   geometry::{Translate, circle_circle_intersection},
   pathfinder::{Node, NodeBehavior, NodeKind},
+  scoring::{ScoreReport, Scorecard, Scoring},
 };
 
 use crate::{
+  instructor::{InstructorAction, LogEntry, SpawnRequest, SpawnState},
   job::{JobQueue, JobReq},
   merge_points,
+  positions::{Controller, Position, Positions},
   ring::RingBuffer,
+  scenario::{ObjectiveStatus, Scenario, ScenarioReport, ScenarioRun},
+  schedule::{Schedule, ScheduledFlight},
   signal_gen::SignalGenerator,
+  traffic::{DemandCurve, DemandPoint, TrafficConfig},
 };
 
-pub const DEPARTURE_SPAWN_CHANCE: f64 = 0.8;
-// TODO: Remove this since it's 100%?
-pub const NON_AUTO_DEPARTURE_CHANCE: f64 = 1.0;
-pub const ARRIVE_TO_NON_AUTO_CHANCE: f64 = 0.2;
-pub const SPAWN_RATE_SECONDS: usize = 75;
+pub const EMERGENCY_CHANCE: f64 = 0.02;
+pub const EMERGENCY_RATE_SECONDS: usize = 60;
 pub const PERF_LOG_SECONDS: usize = 60;
+/// How long before a scheduled departure the aircraft appears at its gate.
+pub const SCHEDULED_BOARDING_MINUTES: usize = 20;
+/// How long before a scheduled arrival the aircraft enters the airspace.
+pub const SCHEDULED_ARRIVAL_LEAD_MINUTES: usize = 12;
+/// How far outside of the airspace arrivals are spawned.
+pub const ARRIVAL_SPAWN_DISTANCE: f32 = NAUTICALMILES_TO_FEET * 5.0;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -70,15 +83,43 @@ pub enum TinyReqKind {
   World,
   AirportStatus(Intern<String>),
   SetAirportStatus(Intern<String>, AirportStatus),
+  AirportStats(Intern<String>),
+  Score,
+  AirportScore(Intern<String>),
+  Economy(Intern<String>),
+  Templates(Intern<String>),
+  Purchase(Intern<String>, Intern<String>),
+  Demand(Intern<String>),
+  /// Replaces an airport's demand curve, with minutes counted from now.
+  SetDemand(Intern<String>, Vec<DemandPoint>),
+
+  // Positions
+  Controllers,
+  Login(Controller),
+  Logout(Intern<String>),
+  Coordination,
+  /// Coordination sent by the controller with the session ID, if logged in.
+  Coordinate(Coordination, Option<Intern<String>>),
+
+  // Instructor
+  SessionLog,
+  Scenario,
 }
 
 #[derive(Debug, Clone)]
 pub enum ArgReqKind {
   // Comms
   /// A command sent from ATC to an aircraft.
-  CommandATC(CommandWithFreq),
-  /// A reply from an aircraft to ATC.
-  CommandReply(CommandWithFreq),
+  /// A transmission from ATC, from the controller with the session ID, if
+  /// logged in.
+  CommandATC(CommandWithFreq, Option<Intern<String>>),
+  /// A reply from an aircraft to ATC, from the controller with the session
+  /// ID, if logged in, and the altitude the aircraft was given if the pilot
+  /// read back a different one.
+  CommandReply(CommandWithFreq, Option<Intern<String>>, Option<f32>),
+
+  // Instructor
+  Instructor(InstructorAction),
 }
 
 #[derive(Debug, Clone, Default)]
@@ -97,14 +138,32 @@ pub enum ResKind {
   Messages(Vec<OutgoingCommandReply>),
   World(World),
   AirspaceStatus(AirportStatus),
+  AirportStats(AirportStats),
+  Score(ScoreReport),
+  AirportScore(Scorecard),
+  Economy(Economy),
+  Templates(Vec<Template>),
+  Demand(DemandCurve),
+  Controllers(Vec<Controller>),
+  Session(Intern<String>),
+  Coordination(Vec<Coordination>),
+  SessionLog(Vec<LogEntry>),
+  Scenario(Option<ScenarioReport>),
 }
 
 #[derive(Debug)]
 pub struct Runner {
   pub engine: Engine,
   pub messages: RingBuffer<CommandWithFreq>,
+  /// Coordination between positions, kept apart from radio messages.
+  pub coordination: RingBuffer<Coordination>,
+  pub scoring: Scoring,
+  pub positions: Positions,
 
   pub preparing: bool,
+  pub disconnected: bool,
+  /// Whether to generate random traffic, alongside any schedule.
+  pub random_traffic: bool,
 
   pub get_queue: JobQueue<TinyReqKind, ResKind>,
   pub post_queue: JobQueue<ArgReqKind, ResKind>,
@@ -112,6 +171,30 @@ pub struct Runner {
   pub save_to: Option<PathBuf>,
 
   spawns: SignalGenerator,
+  traffic: TrafficConfig,
+  /// Demand curves by airport, and the arrivals and departures owed to each
+  /// that have yet to spawn.
+  demand: HashMap<Intern<String>, (DemandCurve, f32, f32)>,
+  emergencies: SignalGenerator,
+  /// Requests awaiting a reply, by aircraft and the sending and receiving
+  /// frequencies.
+  pending_coordination: HashMap<(Intern<String>, u32, u32), CoordinationKind>,
+  /// Handoffs that have been accepted, by aircraft and the frequency of the
+  /// accepting position.
+  handoffs: HashMap<Intern<String>, f32>,
+  /// Departures held at the gate because their destination is delaying
+  /// departures, by aircraft and destination.
+  held_departures: HashMap<Intern<String>, Intern<String>>,
+  /// Scheduled flights by the second of the session they spawn at and the
+  /// second they are due, latest first.
+  schedule: Vec<(usize, usize, ScheduledFlight)>,
+  session_start: usize,
+  /// Instructor actions taken during the session, oldest first.
+  session_log: Vec<LogEntry>,
+  /// Events made by instructor actions. They are handled by the engine like
+  /// any other, and reported with the events of the tick that handles them.
+  instructor_events: Vec<Event>,
+  scenario: Option<ScenarioRun>,
   perf_log: SignalGenerator,
 
   last_perf_tick: usize,
@@ -133,24 +216,201 @@ impl Runner {
     Self {
       engine,
       messages: RingBuffer::new(30),
+      coordination: RingBuffer::new(30),
+      scoring: Scoring::default(),
+      positions: Positions::default(),
 
       preparing: false,
+      disconnected: false,
+      random_traffic: true,
 
       get_queue: JobQueue::new(get_rcv),
       post_queue: JobQueue::new(post_rcv),
 
       save_to,
 
-      spawns: SignalGenerator::new(DEFAULT_TICK_RATE_TPS * SPAWN_RATE_SECONDS),
+      spawns: SignalGenerator::new(
+        DEFAULT_TICK_RATE_TPS * TrafficConfig::default().spawn_rate_seconds,
+      ),
+      traffic: TrafficConfig::default(),
+      demand: HashMap::new(),
+      emergencies: SignalGenerator::new(
+        DEFAULT_TICK_RATE_TPS * EMERGENCY_RATE_SECONDS,
+      ),
       perf_log: SignalGenerator::new(DEFAULT_TICK_RATE_TPS * PERF_LOG_SECONDS),
+      pending_coordination: HashMap::new(),
+      handoffs: HashMap::new(),
+      held_departures: HashMap::new(),
+      schedule: Vec::new(),
+      session_start: 0,
+      session_log: Vec::new(),
+      instructor_events: Vec::new(),
+      scenario: None,
 
       last_perf_tick: 0,
       perf_tick_time_ms: Duration::default(),
     }
   }
 
+  pub fn set_schedule(&mut self, schedule: Schedule) {
+    let boarding = SCHEDULED_BOARDING_MINUTES * 60;
+    let arrival_lead = SCHEDULED_ARRIVAL_LEAD_MINUTES * 60;
+
+    self.schedule = schedule
+      .flights
+      .iter()
+      .filter_map(|f| {
+        let (due, spawn_before) = match (f.std, f.sta) {
+          (Some(std), _) => (schedule.seconds_since_start(std), boarding),
+          (None, Some(sta)) => {
+            (schedule.seconds_since_start(sta), arrival_lead)
+          }
+          (None, None) => {
+            tracing::warn!("Scheduled flight {} has no STD or STA", f.callsign);
+            return None;
+          }
+        };
+
+        Some((due.saturating_sub(spawn_before), due, f.clone()))
+      })
+      .collect();
+    self
+      .schedule
+      .sort_by_key(|(t, _, _)| core::cmp::Reverse(*t));
+  }
+
+  pub fn set_traffic(&mut self, traffic: TrafficConfig) {
+    self.spawns = SignalGenerator::new(
+      self.engine.tick_rate_tps * traffic.spawn_rate_seconds.max(1),
+    );
+    self.demand = traffic
+      .demand
+      .iter()
+      .map(|(id, curve)| {
+        (Intern::from(id.to_uppercase()), (curve.clone(), 0.0, 0.0))
+      })
+      .collect();
+    self.traffic = traffic;
+  }
+
+  /// Replaces the demand curve for an airport. An empty curve returns the
+  /// airport to random traffic.
+  pub fn set_demand(&mut self, id: Intern<String>, curve: DemandCurve) {
+    if curve.is_empty() {
+      self.demand.remove(&id);
+    } else {
+      self.demand.insert(id, (curve, 0.0, 0.0));
+    }
+  }
+
+  /// Automates the positions that no one holds at the airports of
+  /// `positions`.
+  fn update_position_statuses(&mut self, positions: &[Position]) {
+    for airport in positions.iter().map(|p| p.airport).unique() {
+      let current = self.engine.world.airport_status(airport);
+      let status = self.positions.status(airport, current);
+      self.engine.world.airport_statuses.insert(airport, status);
+    }
+  }
+
+  /// Keeps a controller's session alive.
+  fn touch_session(&mut self, controller: Option<Intern<String>>) {
+    if let Some(id) = controller {
+      self.positions.touch(id, Instant::now());
+    }
+  }
+
+  fn check_coordination(
+    &self,
+    coordination: &Coordination,
+    controller: Option<Intern<String>>,
+  ) -> Result<(), &'static str> {
+    let world = &self.engine.world;
+    if !self
+      .positions
+      .can_transmit(world, controller, coordination.from)
+    {
+      return Err("sending position is not held by the sender");
+    }
+    if !self
+      .engine
+      .game
+      .aircraft
+      .iter()
+      .any(|a| a.id == coordination.aircraft)
+    {
+      return Err("unknown aircraft");
+    }
+
+    let key = coordination_key(coordination);
+    if coordination.kind.is_request() {
+      if self.pending_coordination.contains_key(&key) {
+        return Err("a request is already pending");
+      }
+    } else {
+      // Replies go back the other way.
+      let (id, from, to) = key;
+      if !self.pending_coordination.contains_key(&(id, to, from)) {
+        return Err("no pending request to reply to");
+      }
+    }
+
+    Ok(())
+  }
+
+  /// Records the coordination waiting for this tick and settles requests.
+  /// Positions that no one holds are automated and approve every request,
+  /// which is returned to be sent next tick.
+  fn handle_coordination(&mut self) -> Vec<Event> {
+    let incoming: Vec<Coordination> = self
+      .engine
+      .events
+      .iter()
+      .filter_map(|e| match e {
+        Event::Coordination(coordination) => Some(coordination.clone()),
+        _ => None,
+      })
+      .collect();
+
+    let mut replies = Vec::new();
+    for coordination in incoming.iter() {
+      self.coordination.push(coordination.clone());
+
+      let key = coordination_key(coordination);
+      if coordination.kind.is_request() {
+        self.pending_coordination.insert(key, coordination.kind);
+
+        if self.positions.can_transmit(
+          &self.engine.world,
+          None,
+          coordination.to,
+        ) {
+          replies.push(Event::Coordination(
+            coordination.reply(CoordinationKind::Approved),
+          ));
+        }
+      } else {
+        let (id, from, to) = key;
+        let request = self.pending_coordination.remove(&(id, to, from));
+        if request == Some(CoordinationKind::Handoff)
+          && coordination.kind == CoordinationKind::Approved
+        {
+          self.handoffs.insert(id, coordination.from);
+        }
+      }
+    }
+
+    replies
+  }
+
+  fn session_minutes(&self) -> f32 {
+    let ticks = self.engine.tick_counter.saturating_sub(self.session_start);
+    ticks as f32 / self.engine.tick_rate_tps as f32 / 60.0
+  }
+
   pub fn reset_signal_gens(&mut self) {
     self.spawns.set_first();
+    self.emergencies.set_first();
     self.perf_log.set_first();
   }
 
@@ -166,21 +426,6 @@ impl Runner {
       "EGKK", "EGHI",
     ];
 
-    let frequencies = Frequencies {
-      approach: 0.0,
-      departure: 0.0,
-      tower: 0.0,
-      ground: 0.0,
-      center: config_frequencies.center,
-    };
-
-    let mut airport = self
-      .engine
-      .default_airport()
-      .expect("Could not find default airport.")
-      .clone();
-    airport.frequencies = frequencies;
-
     // Generate randomly positioned uncontrolled airports..
     for airport_name in airport_names {
       // TODO: This is a brute-force approach. A better solution would be to use
@@ -218,17 +463,117 @@ impl Runner {
         break position;
       };
 
-      let mut airport = airport.clone();
-      airport.id = Intern::from_ref(airport_name);
-      airport.translate(airport_position);
+      self.add_uncontrolled_airport(
+        Intern::from_ref(airport_name),
+        airport_position,
+        config_frequencies.center,
+      );
+    }
+  }
 
-      self
-        .engine
-        .world
-        .airport_statuses
-        .insert(airport.id, AirportStatus::all_auto());
-      self.engine.world.airports.push(airport);
+  /// Adds an automated airport at a position, laid out like the default
+  /// airport and sharing the `center` frequency.
+  fn add_uncontrolled_airport(
+    &mut self,
+    id: Intern<String>,
+    position: Vec2,
+    center: f32,
+  ) {
+    let mut airport = self
+      .engine
+      .default_airport()
+      .expect("Could not find default airport.")
+      .clone();
+    airport.id = id;
+    airport.frequencies = Frequencies {
+      approach: 0.0,
+      departure: 0.0,
+      tower: 0.0,
+      ground: 0.0,
+      center,
+      clearance: None,
+    };
+    airport.translate(position);
+
+    self
+      .engine
+      .world
+      .airport_statuses
+      .insert(airport.id, AirportStatus::all_auto());
+    self.engine.world.airports.push(airport);
+  }
+
+  /// Adds an airport from the assets at its real position relative to the
+  /// `main` airport, which must both have a location.
+  pub fn add_real_airport(
+    &mut self,
+    main: Intern<String>,
+    name: &str,
+  ) -> Result<(), String> {
+    let Some(main) = self.engine.world.airport(main) else {
+      return Err(format!("unknown airport {main}"));
+    };
+    let Some(origin) = main.location else {
+      return Err(format!("airport {} has no location", main.id));
+    };
+    let Some(mut airport) = self.engine.airport(name).cloned() else {
+      return Err(format!("unknown airport {name}"));
+    };
+    let Some(location) = airport.location else {
+      return Err(format!("airport {} has no location", airport.id));
+    };
+
+    let position = main.center + location.project(origin);
+    airport.translate(position - airport.center);
+    tracing::info!(
+      "Placed airport {} {:.1}nm from {}",
+      airport.id,
+      position.distance(main.center) / NAUTICALMILES_TO_FEET,
+      main.id
+    );
+
+    self
+      .engine
+      .world
+      .airport_statuses
+      .insert(airport.id, AirportStatus::all_auto());
+    self.engine.world.airports.push(airport);
+
+    Ok(())
+  }
+
+  /// Sets up the world from a scenario, in place of generating airports and
+  /// traffic.
+  pub fn load_scenario(&mut self, scenario: &Scenario) -> Result<(), String> {
+    let id = scenario.airport.as_str();
+    let Some(airport) = self.engine.airport(id).cloned() else {
+      return Err(format!("unknown airport {id}"));
+    };
+    let center = airport.frequencies.center;
+
+    self
+      .engine
+      .world
+      .airport_statuses
+      .insert(airport.id, scenario.status);
+    self.engine.world.airports.push(airport);
+    for other in scenario.airports.iter() {
+      self.add_uncontrolled_airport(
+        Intern::from(other.id.to_uppercase()),
+        Vec2::from(other.pos),
+        center,
+      );
+    }
+    self.generate_waypoints();
+
+    for request in scenario.aircraft.iter() {
+      self.instructor_spawn(request.clone())?;
     }
+
+    self.random_traffic = scenario.random_traffic;
+    self.scenario = Some(ScenarioRun::new(scenario));
+
+    Ok(())
   }
 
   pub fn generate_waypoints(&mut self) {
@@ -275,9 +620,17 @@ impl Runner {
     self.engine.world.waypoints = waypoints.collect();
   }
 
+  /// Parks an aircraft at every gate. Without en-route traffic, the other
+  /// airports in the world are only the ends of flights, so only airports with
+  /// a controller get any.
   pub fn fill_gates(&mut self) {
     let mut aircrafts: Vec<Aircraft> = Vec::new();
-    for airport in self.engine.world.airports.iter() {
+    let world = &self.engine.world;
+    let airports = world.airports.iter().filter(|a| {
+      self.traffic.en_route
+        || world.airport_status(a.id) != AirportStatus::all_auto()
+    });
+    for airport in airports {
       for terminal in airport.terminals.iter() {
         for gate in terminal.gates.iter() {
           let mut aircraft =
@@ -303,91 +656,407 @@ impl Runner {
 
   fn do_spawns(&mut self) {
     // If spawn tick, do spawns.
-    if self.spawns.tick(self.engine.tick_counter) {
-      let airports = self.engine.world.airports.iter();
-      for airport in airports {
-        let do_spawn = self.engine.rng.chance(DEPARTURE_SPAWN_CHANCE);
-        if !do_spawn {
-          continue;
+    if !self.spawns.tick(self.engine.tick_counter) {
+      return;
+    }
+
+    let hours = self.traffic.spawn_rate_seconds as f32 / 3600.0;
+    let minute = self.session_minutes();
+    let airports: Vec<Intern<String>> =
+      self.engine.world.airports.iter().map(|a| a.id).collect();
+    for id in airports {
+      // Demand curves only apply once the session has begun.
+      let demand = self.demand.get_mut(&id).filter(|_| !self.preparing);
+      let (arrivals, departures) = match demand {
+        Some((curve, arrivals, departures)) => {
+          let (arrival_rate, departure_rate) = curve.at(minute);
+          *arrivals += arrival_rate * hours;
+          *departures += departure_rate * hours;
+
+          (arrivals.floor() as usize, departures.floor() as usize)
+        }
+        None => {
+          let departure_chance = self.traffic.departure_spawn_chance;
+          let departures = self.engine.rng.chance(departure_chance);
+
+          // Without en-route traffic, controlled airports get their arrivals
+          // at the edge of their airspace instead.
+          let arrival_chance = self.traffic.arrival_spawn_chance;
+          let arrivals = !self.traffic.en_route
+            && !self.preparing
+            && !self.engine.world.airport_status(id).automate_air
+            && self.engine.rng.chance(arrival_chance);
+
+          (usize::from(arrivals), usize::from(departures))
         }
+      };
 
-        let gates = airport
-          .terminals
-          .iter()
-          .flat_map(|t| t.gates.iter().filter(|g| !g.available));
-        let random_gate = self.engine.rng.sample_iter(gates);
-        if let Some(gate) = random_gate {
-          let aircraft = self
-            .engine
-            .game
-            .aircraft
-            .iter_mut()
-            .filter(|a| {
-              a.flight_time.is_none() && a.segment == FlightSegment::Dormant
-            })
-            .find(|a| {
-              // Find the aircraft that is parked at the gate.
-              if let AircraftState::Parked { at } = &a.state {
-                at.name == gate.id && a.pos == gate.pos
-              } else {
-                false
-              }
-            });
-
-          if let Some(aircraft) = aircraft {
-            // Chance for a flight to go to a non-auto airports.
-            let go_to_non_auto =
-              self.engine.rng.chance(ARRIVE_TO_NON_AUTO_CHANCE);
-            let destination = self.engine.rng.sample_iter(
-              self.engine.world.airports.iter().filter(|a| {
-                if a.id == airport.id {
-                  return false;
-                }
-
-                let is_auto =
-                  self.engine.world.airport_status(a.id).automate_air;
-                if go_to_non_auto { !is_auto } else { is_auto }
-              }),
-            );
-            if let Some(destination) = destination {
-              // If we are preparing, only schedule departures from auto
-              // airports, otherwise choose airports with normal or automated
-              // departure status.
-              if (!self.preparing
-                || self.engine.world.airport_status(airport.id).automate_ground)
-                && !self
-                  .engine
-                  .world
-                  .airport_status(destination.id)
-                  .delay_departures
-              {
-                aircraft.flight_plan.departing = airport.id;
-                aircraft.flight_plan.arriving = destination.id;
-
-                let min_time_seconds = if self.preparing { 0 } else { 60 };
-                let max_time_seconds = 60 * 5;
-                let delay_seconds =
-                  self.engine.rng.usize(min_time_seconds..=max_time_seconds);
-                let delay = delay_seconds * self.engine.tick_rate_tps;
-
-                aircraft.flight_time = Some(self.engine.tick_counter + delay);
-              }
+      let departed = (0..departures)
+        .take_while(|_| self.dispatch_departure(id))
+        .count();
+      let arrived = (0..arrivals)
+        .take_while(|_| self.spawn_random_arrival(id))
+        .count();
+
+      // Only what was released is paid off. The rest is still owed on the
+      // next spawn tick.
+      let demand = self.demand.get_mut(&id).filter(|_| !self.preparing);
+      if let Some((_, arrivals, departures)) = demand {
+        *arrivals -= arrived as f32;
+        *departures -= departed as f32;
+      }
+    }
+  }
+
+  /// Gives a parked aircraft at the airport a flight to a random destination.
+  /// Returns whether a departure was released.
+  fn dispatch_departure(&mut self, id: Intern<String>) -> bool {
+    let Some(airport) = self.engine.world.airport(id) else {
+      return false;
+    };
+    // Without en-route traffic, nobody departs from the rest of the world.
+    let status = self.engine.world.airport_status(id);
+    if !self.traffic.en_route && status == AirportStatus::all_auto() {
+      return false;
+    }
+
+    // Find the aircraft that are parked at the airport's gates.
+    let is_at_gate = |a: &Aircraft| {
+      let AircraftState::Parked { at } = &a.state else {
+        return false;
+      };
+      airport
+        .terminals
+        .iter()
+        .flat_map(|t| t.gates.iter())
+        .any(|g| at.name == g.id && a.pos == g.pos)
+    };
+    let is_eligible = |a: &Aircraft| {
+      a.flight_time.is_none()
+        && a.segment == FlightSegment::Dormant
+        && is_at_gate(a)
+    };
+
+    // A held flight keeps its aircraft and destination until it is released.
+    let held = self.held_departures.iter().find(|(aircraft, _)| {
+      self
+        .engine
+        .game
+        .aircraft
+        .iter()
+        .any(|a| a.id == **aircraft && is_eligible(a))
+    });
+    let (aircraft, destination) =
+      if let Some((aircraft, destination)) = held {
+        let aircraft = self
+          .engine
+          .game
+          .aircraft
+          .iter_mut()
+          .find(|a| a.id == *aircraft);
+        (aircraft, Some(*destination))
+      } else {
+        let eligible = self
+          .engine
+          .game
+          .aircraft
+          .iter_mut()
+          .filter(|a| is_eligible(a));
+        let aircraft = self.engine.rng.sample_iter(eligible);
+
+        // Chance for a flight to go to a non-auto airports.
+        let non_auto_chance = self.traffic.arrive_to_non_auto_chance;
+        let go_to_non_auto =
+          self.traffic.en_route && self.engine.rng.chance(non_auto_chance);
+        let destination = self.engine.rng.sample_iter(
+          self.engine.world.airports.iter().filter(|a| {
+            if a.id == airport.id {
+              return false;
             }
-          }
-        }
+
+            let is_auto = self.engine.world.airport_status(a.id).automate_air;
+            if go_to_non_auto { !is_auto } else { is_auto }
+          }),
+        );
+
+        (aircraft, destination.map(|a| a.id))
+      };
+    let (Some(aircraft), Some(destination)) = (aircraft, destination) else {
+      return false;
+    };
+
+    // If we are preparing, only schedule departures from auto airports,
+    // otherwise choose airports with normal or automated departure status.
+    let delayed = self
+      .engine
+      .world
+      .airport_status(destination)
+      .delay_departures;
+    if delayed && !self.preparing {
+      // The flight stays at the gate, and is only charged for once however
+      // long it is held.
+      if self
+        .held_departures
+        .insert(aircraft.id, destination)
+        .is_none()
+      {
+        self
+          .engine
+          .game
+          .economies
+          .entry(destination)
+          .or_default()
+          .pay_penalty(DELAYED_DEPARTURE_PENALTY);
       }
+
+      return false;
+    }
+
+    let release = (!self.preparing
+      || self.engine.world.airport_status(airport.id).automate_ground)
+      && !delayed;
+    if release {
+      aircraft.flight_plan.departing = airport.id;
+      aircraft.flight_plan.arriving = destination;
+
+      let min_time_seconds = if self.preparing { 0 } else { 60 };
+      let max_time_seconds = 60 * 5;
+      let delay_seconds =
+        self.engine.rng.usize(min_time_seconds..=max_time_seconds);
+      let delay = delay_seconds * self.engine.tick_rate_tps;
+
+      aircraft.flight_time = Some(self.engine.tick_counter + delay);
+      self.held_departures.remove(&aircraft.id);
+    }
+
+    release
+  }
+
+  /// Spawns an arrival into the airport from a random origin. Returns whether
+  /// one was spawned.
+  fn spawn_random_arrival(&mut self, id: Intern<String>) -> bool {
+    let origin = self.engine.rng.sample_iter(
+      self
+        .engine
+        .world
+        .airports
+        .iter()
+        .filter(|a| a.id != id)
+        .map(|a| a.id),
+    );
+    let Some(origin) = origin else {
+      return false;
+    };
+
+    let (callsign, kind) =
+      registry().random_callsign(&mut self.engine.rng, Some(origin));
+    let callsign = Intern::from(callsign);
+    if self.engine.game.aircraft.iter().any(|a| a.id == callsign) {
+      return false;
+    }
+
+    self.spawn_arrival(callsign, kind, origin, id)
+  }
+
+  fn do_schedule(&mut self) {
+    if self.preparing {
+      return;
+    }
+
+    let tps = self.engine.tick_rate_tps;
+    let elapsed = (self.engine.tick_counter - self.session_start) / tps;
+    while self.schedule.last().is_some_and(|(t, _, _)| *t <= elapsed) {
+      if let Some((_, due, flight)) = self.schedule.pop() {
+        self.spawn_scheduled(flight, due.saturating_sub(elapsed));
+      }
+    }
+  }
+
+  /// Spawns a scheduled flight that is due in `due_in` seconds.
+  fn spawn_scheduled(&mut self, flight: ScheduledFlight, due_in: usize) {
+    let id = Intern::from(flight.callsign.to_uppercase());
+    if self.engine.game.aircraft.iter().any(|a| a.id == id) {
+      tracing::warn!("Scheduled flight {id} is already in the world");
+      return;
+    }
+
+    let origin = Intern::from(flight.origin.to_uppercase());
+    let destination = Intern::from(flight.destination.to_uppercase());
+    if flight.std.is_some() {
+      self.spawn_departure(
+        id,
+        flight.kind,
+        origin,
+        destination,
+        flight.gate.as_deref(),
+        due_in,
+      );
+    } else {
+      self.spawn_arrival(id, flight.kind, origin, destination);
+    }
+  }
+
+  /// Parks a departure at a gate (or any free gate), ready for taxi after
+  /// `delay` seconds. Parked aircraft without a flight make way for it.
+  fn spawn_departure(
+    &mut self,
+    id: Intern<String>,
+    kind: AircraftKind,
+    origin: Intern<String>,
+    destination: Intern<String>,
+    gate: Option<&str>,
+    delay: usize,
+  ) -> bool {
+    let Some(airport) = self.engine.world.airport(origin) else {
+      tracing::warn!("Unable to spawn {id}: unknown airport {origin}");
+      return false;
+    };
+    if self.engine.world.airport(destination).is_none() {
+      tracing::warn!("Unable to spawn {id}: unknown airport {destination}");
+      return false;
+    }
+
+    let aircraft = &self.engine.game.aircraft;
+    let occupant = |g: &Gate| {
+      aircraft.iter().position(|a| match &a.state {
+        AircraftState::Parked { at } => at.name == g.id && a.pos == g.pos,
+        _ => false,
+      })
+    };
+    let spot = airport
+      .terminals
+      .iter()
+      .flat_map(|t| t.gates.iter())
+      .filter(|g| gate.is_none_or(|gate| g.id.as_str() == gate))
+      .find_map(|g| match occupant(g) {
+        None => Some((g, None)),
+        Some(i) if aircraft[i].flight_time.is_none() => Some((g, Some(i))),
+        Some(_) => None,
+      });
+    let Some((gate, occupant)) = spot else {
+      tracing::warn!("Unable to spawn {id}: no free gate at {origin}");
+      return false;
+    };
+
+    let mut departure =
+      Aircraft::random_dormant(gate, &mut self.engine.rng, airport);
+    departure.id = id;
+    departure.kind = kind;
+    departure.flight_plan.departing = origin;
+    departure.flight_plan.arriving = destination;
+    departure.flight_time =
+      Some(self.engine.tick_counter + delay * self.engine.tick_rate_tps);
+
+    if let Some(index) = occupant {
+      self.engine.game.aircraft.swap_remove(index);
+    }
+    self.engine.add_aircraft(departure);
+    true
+  }
+
+  /// Spawns an arrival just outside of the destination's airspace, on its way
+  /// in from the origin.
+  fn spawn_arrival(
+    &mut self,
+    id: Intern<String>,
+    kind: AircraftKind,
+    origin: Intern<String>,
+    destination: Intern<String>,
+  ) -> bool {
+    let world = &self.engine.world;
+    let Some((origin, destination)) =
+      world.airport(origin).zip(world.airport(destination))
+    else {
+      tracing::warn!("Unable to spawn {id}: unknown airport");
+      return false;
+    };
+
+    let mut arrival =
+      Aircraft::arriving(id, kind, origin, destination, ARRIVAL_SPAWN_DISTANCE);
+    arrival.flight_time = Some(self.engine.tick_counter);
+
+    self.engine.add_aircraft(arrival);
+    true
+  }
+
+  fn do_scenario(&mut self, events: &[Event]) {
+    if self.preparing {
+      return;
+    }
+    let Some(scenario) = self.scenario.as_mut() else {
+      return;
+    };
+
+    let elapsed = self.engine.tick_counter.saturating_sub(self.session_start);
+    let actions = scenario.triggered(elapsed, events);
+    let was_complete = scenario.report.is_complete();
+    scenario.update(elapsed, events, &self.scoring.report.session);
+    if !was_complete && scenario.report.is_complete() {
+      let result = if scenario.report.passed() {
+        "passed"
+      } else {
+        "failed"
+      };
+      tracing::info!("Scenario \"{}\" {result}", scenario.report.name);
+    }
+
+    for action in actions {
+      if let Err(e) = self.apply_instructor(action) {
+        tracing::warn!("Scenario event failed: {e}");
+      }
+    }
+  }
+
+  fn do_emergencies(&mut self) {
+    // Scenarios script their own emergencies.
+    if self.scenario.is_some() {
+      return;
+    }
+    if self.preparing || !self.emergencies.tick(self.engine.tick_counter) {
+      return;
+    }
+
+    if !self.engine.rng.chance(EMERGENCY_CHANCE) {
+      return;
+    }
+
+    // Only trigger emergencies for aircraft that a player is controlling.
+    let aircraft =
+      self
+        .engine
+        .rng
+        .sample_iter(self.engine.game.aircraft.iter().filter(|a| {
+          a.segment.in_air()
+            && a.emergency.is_none()
+            && a.airspace.is_some_and(|id| {
+              !self.engine.world.airport_status(id).automate_air
+            })
+        }));
+    let emergency = self.engine.rng.sample(&Emergency::RANDOM).copied();
+
+    if let Some((id, emergency)) = aircraft.map(|a| a.id).zip(emergency) {
+      tracing::info!("Declaring {emergency} for {id}");
+      self.engine.declare_emergency(id, emergency);
     }
   }
 
   pub fn tick(&mut self) -> Vec<Event> {
     let tick_start = Instant::now();
-    let mut commands: Vec<CommandWithFreq> = Vec::new();
+    let mut commands: Vec<(CommandWithFreq, Option<f32>)> = Vec::new();
+
+    for controller in self.positions.expire(Instant::now()) {
+      tracing::info!("{} was logged out after inactivity", controller.name);
+      self.update_position_statuses(&controller.positions);
+    }
 
     // GET
     loop {
       let incoming = match self.get_queue.recv() {
         Ok(incoming) => incoming,
-        Err(TryRecvError::Disconnected) => return Vec::new(),
+        Err(TryRecvError::Disconnected) => {
+          self.disconnected = true;
+          return Vec::new();
+        }
         Err(TryRecvError::Empty) => break,
       };
 
@@ -432,8 +1101,124 @@ impl Runner {
             incoming.reply(ResKind::Err);
           }
         }
+        TinyReqKind::AirportStats(id) => {
+          let id = *id;
+          if self.engine.world.airport(id).is_some() {
+            incoming
+              .reply(ResKind::AirportStats(self.engine.game.airport_stats(id)));
+          } else {
+            incoming.reply(ResKind::Err);
+          }
+        }
 
         // Other State
+        TinyReqKind::Score => {
+          incoming.reply(ResKind::Score(self.scoring.report.clone()))
+        }
+        TinyReqKind::AirportScore(id) => {
+          if let Some(card) = self.scoring.report.airports.get(id) {
+            incoming.reply(ResKind::AirportScore(*card));
+          } else if self.engine.world.airport(*id).is_some() {
+            incoming.reply(ResKind::AirportScore(Scorecard::default()));
+          } else {
+            incoming.reply(ResKind::Err);
+          }
+        }
+        TinyReqKind::Economy(id) => {
+          let id = *id;
+          if self.engine.world.airport(id).is_some() {
+            incoming.reply(ResKind::Economy(self.engine.game.economy(id)));
+          } else {
+            incoming.reply(ResKind::Err);
+          }
+        }
+        TinyReqKind::Templates(id) => {
+          let id = *id;
+          incoming.reply(ResKind::Templates(
+            self.engine.available_templates(id).cloned().collect(),
+          ))
+        }
+        TinyReqKind::Purchase(id, template) => {
+          match self.engine.purchase(*id, *template) {
+            Ok(()) => incoming.reply(ResKind::Any),
+            Err(e) => {
+              tracing::warn!("Unable to build {template} at {id}: {e}");
+              incoming.reply(ResKind::Err);
+            }
+          }
+        }
+        TinyReqKind::Demand(id) => {
+          if let Some((curve, _, _)) = self.demand.get(id) {
+            incoming.reply(ResKind::Demand(curve.clone()));
+          } else if self.engine.world.airport(*id).is_some() {
+            incoming.reply(ResKind::Demand(DemandCurve::default()));
+          } else {
+            incoming.reply(ResKind::Err);
+          }
+        }
+        TinyReqKind::SetDemand(id, points) => {
+          if self.engine.world.airport(*id).is_some() {
+            let curve =
+              DemandCurve::new(points.clone()).offset(self.session_minutes());
+            self.set_demand(*id, curve);
+            incoming.reply(ResKind::Any);
+          } else {
+            incoming.reply(ResKind::Err);
+          }
+        }
+        TinyReqKind::Controllers => incoming.reply(ResKind::Controllers(
+          self.positions.controllers().cloned().collect(),
+        )),
+        TinyReqKind::Login(controller) => {
+          match self.positions.login(&self.engine.world, controller.clone()) {
+            Ok(id) => {
+              tracing::info!("{} logged in", controller.name);
+              self.update_position_statuses(&controller.positions);
+              incoming.reply(ResKind::Session(id));
+            }
+            Err(e) => {
+              tracing::warn!("Unable to log in {}: {e}", controller.name);
+              incoming.reply(ResKind::Err);
+            }
+          }
+        }
+        TinyReqKind::Logout(id) => {
+          if let Some(controller) = self.positions.logout(*id) {
+            tracing::info!("{} logged out", controller.name);
+            self.update_position_statuses(&controller.positions);
+            incoming.reply(ResKind::Any);
+          } else {
+            incoming.reply(ResKind::Err);
+          }
+        }
+        TinyReqKind::Coordination => incoming.reply(ResKind::Coordination(
+          self.coordination.iter().cloned().collect(),
+        )),
+        TinyReqKind::Coordinate(coordination, controller) => {
+          self.touch_session(*controller);
+          match self.check_coordination(coordination, *controller) {
+            Ok(()) => {
+              self
+                .engine
+                .events
+                .push(Event::Coordination(coordination.clone()));
+              incoming.reply(ResKind::Any);
+            }
+            Err(e) => {
+              tracing::warn!(
+                "Rejected coordination for {}: {e}",
+                coordination.aircraft
+              );
+              incoming.reply(ResKind::Err);
+            }
+          }
+        }
+        TinyReqKind::SessionLog => {
+          incoming.reply(ResKind::SessionLog(self.session_log.clone()))
+        }
+        TinyReqKind::Scenario => incoming.reply(ResKind::Scenario(
+          self.scenario.as_ref().map(|s| s.report.clone()),
+        )),
         TinyReqKind::Messages => incoming.reply(ResKind::Messages(
           self.messages.iter().cloned().map(|m| m.into()).collect(),
         )),
@@ -447,18 +1232,53 @@ impl Runner {
     loop {
       let incoming = match self.post_queue.recv() {
         Ok(incoming) => incoming,
-        Err(TryRecvError::Disconnected) => return Vec::new(),
+        Err(TryRecvError::Disconnected) => {
+          self.disconnected = true;
+          return Vec::new();
+        }
         Err(TryRecvError::Empty) => break,
       };
 
       match incoming.req() {
-        ArgReqKind::CommandATC(command) => {
-          self.messages.push(command.clone());
-          incoming.reply(ResKind::Any);
+        ArgReqKind::CommandATC(command, controller) => {
+          self.touch_session(*controller);
+          if self.positions.can_transmit(
+            &self.engine.world,
+            *controller,
+            command.frequency,
+          ) {
+            self.engine.record_transmission(command.frequency);
+            self.messages.push(command.clone());
+            incoming.reply(ResKind::Any);
+          } else {
+            tracing::warn!(
+              "Rejected transmission on {}: frequency not owned by the sender",
+              command.frequency
+            );
+            incoming.reply(ResKind::Err);
+          }
         }
-        ArgReqKind::CommandReply(command) => {
-          commands.push(command.clone());
-          incoming.reply(ResKind::Any);
+        ArgReqKind::CommandReply(command, controller, misread) => {
+          self.touch_session(*controller);
+          match self.check_command(command, *controller) {
+            Ok(()) => {
+              commands.push((command.clone(), *misread));
+              incoming.reply(ResKind::Any);
+            }
+            Err(e) => {
+              tracing::warn!("Rejected command for {}: {e}", command.id);
+              incoming.reply(ResKind::Err);
+            }
+          }
+        }
+        ArgReqKind::Instructor(action) => {
+          match self.apply_instructor(action.clone()) {
+            Ok(()) => incoming.reply(ResKind::Any),
+            Err(e) => {
+              tracing::warn!("Rejected instructor action: {e}");
+              incoming.reply(ResKind::Err);
+            }
+          }
         }
       }
     }
@@ -467,11 +1287,24 @@ impl Runner {
       return Vec::new();
     }
 
-    for command in commands {
-      self.execute_command(command);
+    for (command, misread) in commands {
+      self.execute_command(command, misread);
     }
 
-    let events = self.engine.tick();
+    let replies = self.handle_coordination();
+    let mut events = self.engine.tick();
+    self.engine.events.extend(replies);
+    events.append(&mut self.instructor_events);
+
+    if !self.preparing {
+      self.scoring.record_events(
+        &events,
+        &self.engine.game,
+        &self.engine.world,
+        self.engine.tick_counter,
+        self.engine.tick_rate_tps,
+      );
+    }
 
     // Run through all callout events and broadcast them
     self.messages.extend(
@@ -487,7 +1320,12 @@ impl Runner {
         .cloned(),
     );
 
-    self.do_spawns();
+    self.do_schedule();
+    self.do_scenario(&events);
+    if self.random_traffic {
+      self.do_spawns();
+    }
+    self.do_emergencies();
     self.cleanup(events.iter());
     // TODO: self.save_world();
 
@@ -567,14 +1405,234 @@ impl Runner {
 
   pub fn begin_loop(&mut self) {
     self.engine.config = EngineConfig::Full;
+    self.session_start = self.engine.tick_counter;
 
-    loop {
+    while !self.disconnected {
       if Instant::now() - self.engine.last_tick
         >= Duration::from_secs_f32(1.0 / self.engine.tick_rate_tps as f32)
       {
         self.tick();
       }
     }
+
+    self.log_summary();
+  }
+
+  fn log_summary(&self) {
+    let report = &self.scoring.report;
+    tracing::info!("Session {}", report.session);
+    for (id, card) in
+      report.airports.iter().sorted_by_key(|(id, _)| id.as_str())
+    {
+      tracing::info!("{id} {card}");
+    }
+
+    // Objectives are logged as they are decided, leaving the rest.
+    if let Some(scenario) = &self.scenario {
+      for (objective, status) in scenario.report.objectives.iter() {
+        if *status == ObjectiveStatus::Pending {
+          tracing::info!("Objective \"{}\": {status:?}", objective.name);
+        }
+      }
+    }
+  }
+
+  /// Applies an instructor's action to the session and records it in the
+  /// session log.
+  fn apply_instructor(
+    &mut self,
+    action: InstructorAction,
+  ) -> Result<(), String> {
+    match action.clone() {
+      InstructorAction::Spawn(request) => self.instructor_spawn(request)?,
+      InstructorAction::Delete { id } => {
+        let index = self.aircraft_index(&id)?;
+        let id = self.engine.game.aircraft.swap_remove(index).id;
+        self.handoffs.remove(&id);
+        self.held_departures.remove(&id);
+        self.scoring.forget(id);
+        self.pending_coordination.retain(|(a, _, _), _| *a != id);
+      }
+      InstructorAction::Teleport {
+        id,
+        pos,
+        altitude,
+        heading,
+      } => {
+        let index = self.aircraft_index(&id)?;
+        let aircraft = &mut self.engine.game.aircraft[index];
+        aircraft.pos = Vec2::from(pos);
+        aircraft.update_airspace(&self.engine.world);
+        if let Some(altitude) = altitude {
+          aircraft.altitude = altitude;
+          aircraft.target.altitude = altitude;
+        }
+        if let Some(heading) = heading {
+          aircraft.heading = heading;
+          aircraft.target.heading = heading;
+        }
+      }
+      InstructorAction::SetSegment { id, segment } => {
+        // Sent as an event, so that its handlers and scenario triggers run.
+        let index = self.aircraft_index(&id)?;
+        let aircraft = &self.engine.game.aircraft[index];
+        let event: Event = AircraftEvent::new(
+          aircraft.id,
+          EventKind::Segment(aircraft.segment, segment),
+        )
+        .into();
+        self.engine.events.push(event.clone());
+        self.instructor_events.push(event);
+      }
+      InstructorAction::Emergency { id, emergency } => {
+        let index = self.aircraft_index(&id)?;
+        let id = self.engine.game.aircraft[index].id;
+        self.engine.declare_emergency(id, emergency);
+      }
+      InstructorAction::PilotRequest { id, text } => {
+        let index = self.aircraft_index(&id)?;
+        let aircraft = &self.engine.game.aircraft[index];
+        let request = CommandWithFreq::new(
+          aircraft.id.to_string(),
+          aircraft.frequency,
+          CommandReply::Request { text },
+          Vec::new(),
+        );
+        self.engine.record_transmission(request.frequency);
+        self.messages.push(request);
+      }
+      InstructorAction::CloseRunway {
+        airport,
+        runway,
+        closed,
+      } => {
+        let airport_id = Intern::from(airport.to_uppercase());
+        let runway_id = Intern::from(runway.to_uppercase());
+        let exists = self
+          .engine
+          .world
+          .airport(airport_id)
+          .is_some_and(|a| a.runways.iter().any(|r| r.id == runway_id));
+        if !exists {
+          return Err(format!("unknown runway {runway} at {airport}"));
+        }
+
+        if closed {
+          self
+            .engine
+            .world
+            .closed_runways
+            .insert((airport_id, runway_id));
+
+          // Anyone on final for the runway has to go around.
+          for aircraft in self.engine.game.aircraft.iter() {
+            let on_final = matches!(
+              &aircraft.state,
+              AircraftState::Landing { runway, .. } if runway.id == runway_id
+            );
+            if on_final && aircraft.flight_plan.arriving == airport_id {
+              self.engine.events.push(
+                AircraftEvent::new(aircraft.id, EventKind::GoAround).into(),
+              );
+            }
+          }
+        } else {
+          self
+            .engine
+            .world
+            .closed_runways
+            .remove(&(airport_id, runway_id));
+        }
+      }
+      InstructorAction::FailNavaid { name, failed } => {
+        let name = Intern::from(name.to_uppercase());
+        if failed {
+          self.engine.world.failed_navaids.insert(name);
+        } else {
+          self.engine.world.failed_navaids.remove(&name);
+        }
+      }
+    }
+
+    tracing::info!("Instructor action: {action:?}");
+    self.session_log.push(LogEntry {
+      tick: self.engine.tick_counter,
+      action,
+    });
+
+    Ok(())
+  }
+
+  fn instructor_spawn(&mut self, request: SpawnRequest) -> Result<(), String> {
+    let origin = Intern::from(request.origin.to_uppercase());
+    let destination = Intern::from(request.destination.to_uppercase());
+    let id = match request.callsign {
+      Some(callsign) => Intern::from(callsign.to_uppercase()),
+      None => Intern::from(
+        registry()
+          .random_callsign(&mut self.engine.rng, Some(origin))
+          .0,
+      ),
+    };
+    if self.engine.game.aircraft.iter().any(|a| a.id == id) {
+      return Err(format!("{id} is already in the world"));
+    }
+
+    let spawned = match request.state {
+      SpawnState::Gate { gate } => self.spawn_departure(
+        id,
+        request.kind,
+        origin,
+        destination,
+        gate.as_deref(),
+        0,
+      ),
+      SpawnState::Arrival => {
+        self.spawn_arrival(id, request.kind, origin, destination)
+      }
+      SpawnState::Airborne {
+        pos,
+        altitude,
+        heading,
+        speed,
+      } => {
+        let world = &self.engine.world;
+        let Some((origin, destination)) =
+          world.airport(origin).zip(world.airport(destination))
+        else {
+          return Err("unknown airport".to_owned());
+        };
+
+        let mut aircraft =
+          Aircraft::arriving(id, request.kind, origin, destination, 0.0);
+        aircraft.pos = Vec2::from(pos);
+        aircraft.altitude = altitude;
+        aircraft.heading = heading;
+        aircraft.speed = speed;
+        aircraft.sync_targets_to_vals();
+        aircraft.flight_time = Some(self.engine.tick_counter);
+
+        self.engine.add_aircraft(aircraft);
+        true
+      }
+    };
+
+    if spawned {
+      Ok(())
+    } else {
+      Err(format!("unable to spawn {id}"))
+    }
+  }
+
+  fn aircraft_index(&self, id: &str) -> Result<usize, String> {
+    let id = Intern::from(id.to_uppercase());
+    self
+      .engine
+      .game
+      .aircraft
+      .iter()
+      .position(|a| a.id == id)
+      .ok_or_else(|| format!("no aircraft {id}"))
   }
 
   fn cleanup<'a, T>(&mut self, events: T)
@@ -583,7 +1641,7 @@ impl Runner {
   {
     for event in events.filter_map(|e| match e {
       Event::Aircraft(aircraft_event) => Some(aircraft_event),
-      Event::UiEvent(_) => None,
+      Event::UiEvent(_) | Event::Coordination(_) => None,
     }) {
       if let AircraftEvent {
         id,
@@ -600,19 +1658,87 @@ impl Runner {
         if let Some(index) = index {
           self.engine.game.aircraft.swap_remove(index);
         }
+        self.handoffs.remove(id);
+        self.held_departures.remove(id);
+        self.scoring.forget(*id);
+        self.pending_coordination.retain(|(a, _, _), _| a != id);
+      }
+    }
+  }
+
+  fn check_command(
+    &self,
+    command: &CommandWithFreq,
+    controller: Option<Intern<String>>,
+  ) -> Result<(), &'static str> {
+    let world = &self.engine.world;
+    if !self
+      .positions
+      .can_transmit(world, controller, command.frequency)
+    {
+      return Err("frequency not owned by the sender");
+    }
+
+    // An aircraft can only be sent to a position held by another controller
+    // once they have accepted the handoff.
+    let id = Intern::from_ref(&command.id);
+    if let Some(frequency) = self.handoff_frequency(command) {
+      let held_by_other = !self.positions.can_transmit(world, None, frequency)
+        && !self.positions.can_transmit(world, controller, frequency);
+      if held_by_other && self.handoffs.get(&id) != Some(&frequency) {
+        return Err("no accepted handoff to the frequency");
       }
     }
+
+    Ok(())
   }
 
-  fn execute_command(&mut self, command: CommandWithFreq) {
+  /// The frequency a command hands an aircraft off to, if any, with named
+  /// frequencies resolved for the aircraft.
+  fn handoff_frequency(&self, command: &CommandWithFreq) -> Option<f32> {
     let id = Intern::from_ref(&command.id);
-    if self
+    let aircraft = self.engine.game.aircraft.iter().find(|a| a.id == id);
+    command.tasks.iter().find_map(|t| match t {
+      Task::Frequency(frequency) => Some(*frequency),
+      Task::NamedFrequency(name) => aircraft
+        .and_then(|a| a.named_frequency(name, &self.engine.world.airports)),
+      _ => None,
+    })
+  }
+
+  fn execute_command(
+    &mut self,
+    command: CommandWithFreq,
+    misread: Option<f32>,
+  ) {
+    let id = Intern::from_ref(&command.id);
+    let handoff = self.handoff_frequency(&command);
+
+    self.engine.record_transmission(command.frequency);
+
+    let airspace = self
       .engine
       .game
       .aircraft
       .iter()
-      .any(|a| a.id == id && a.frequency == command.frequency)
-    {
+      .find(|a| a.id == id && a.frequency == command.frequency)
+      .map(|a| a.airspace);
+    if let Some(airspace) = airspace {
+      if handoff.is_some() {
+        self.handoffs.remove(&id);
+      }
+
+      let (tick, tps) = (self.engine.tick_counter, self.engine.tick_rate_tps);
+      self.scoring.record_command(&command, tick, tps);
+
+      // It's up to the controller to catch a pilot reading back the wrong
+      // altitude.
+      if let (Some(intended), Some(airport)) = (misread, airspace) {
+        self
+          .scoring
+          .record_readback_error(id, airport, tick, intended);
+      }
+
       self.engine.events.extend(
         command
           .tasks
@@ -642,3 +1768,145 @@ impl Runner {
     }
   }
 }
+
+fn coordination_key(coordination: &Coordination) -> (Intern<String>, u32, u32) {
+  (
+    coordination.aircraft,
+    coordination.from.to_bits(),
+    coordination.to.to_bits(),
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use engine::{
+    entities::airport::{Airport, Frequencies, Gate, Terminal},
+    line::Line,
+  };
+  use tokio::sync::mpsc;
+
+  use crate::positions::Role;
+
+  use super::*;
+
+  #[test]
+  fn test_handoff_needs_acceptance() {
+    let (_, get_rx) = mpsc::unbounded_channel();
+    let (_, post_rx) = mpsc::unbounded_channel();
+    let mut runner = Runner::new(get_rx, post_rx, None, Rng::new());
+
+    let id = Intern::from_ref("KSFO");
+    let mut airport = Airport::new(id, Vec2::ZERO);
+    airport.frequencies = Frequencies {
+      approach: 124.0,
+      departure: 124.0,
+      tower: 120.5,
+      ground: 121.8,
+      center: 132.5,
+      clearance: None,
+    };
+    runner.engine.world.airports.push(airport);
+    runner.engine.add_aircraft(Aircraft {
+      id: Intern::from_ref("AAL1"),
+      airspace: Some(id),
+      frequency: 124.0,
+      ..Default::default()
+    });
+
+    let mut login = |name: &str, role| {
+      let controller = Controller {
+        name: name.to_owned(),
+        positions: vec![Position { airport: id, role }],
+      };
+      runner
+        .positions
+        .login(&runner.engine.world, controller)
+        .ok()
+    };
+    let approach = login("Approach", Role::Approach);
+    login("Tower", Role::Tower);
+
+    let contact = |task| {
+      CommandWithFreq::new(
+        "AAL1".to_owned(),
+        124.0,
+        CommandReply::Empty,
+        vec![task],
+      )
+    };
+    let by_frequency = contact(Task::Frequency(120.5));
+    let by_name = contact(Task::NamedFrequency("tower".to_owned()));
+
+    assert!(runner.check_command(&by_frequency, approach).is_err());
+    assert!(runner.check_command(&by_name, approach).is_err());
+
+    runner.handoffs.insert(Intern::from_ref("AAL1"), 120.5);
+    assert!(runner.check_command(&by_frequency, approach).is_ok());
+    assert!(runner.check_command(&by_name, approach).is_ok());
+  }
+
+  #[test]
+  fn test_delayed_departure_is_held() {
+    let (_, get_rx) = mpsc::unbounded_channel();
+    let (_, post_rx) = mpsc::unbounded_channel();
+    let mut runner = Runner::new(get_rx, post_rx, None, Rng::new());
+    runner.traffic.arrive_to_non_auto_chance = 0.0;
+
+    let origin = Intern::from_ref("KSFO");
+    let destination = Intern::from_ref("KOAK");
+    let gate = Gate {
+      id: Intern::from_ref("A1"),
+      pos: Vec2::ZERO,
+      heading: 0.0,
+      available: true,
+    };
+    let mut airport = Airport::new(origin, Vec2::ZERO);
+    airport.terminals.push(Terminal {
+      id: Intern::from_ref("A"),
+      a: Vec2::ZERO,
+      b: Vec2::ZERO,
+      c: Vec2::ZERO,
+      d: Vec2::ZERO,
+      gates: vec![gate.clone()],
+      apron: Line::new(Vec2::ZERO, Vec2::ZERO),
+    });
+    runner.engine.world.airports.push(airport);
+    runner
+      .engine
+      .world
+      .airports
+      .push(Airport::new(destination, Vec2::ZERO));
+    runner.engine.world.airport_statuses.insert(
+      destination,
+      AirportStatus {
+        delay_departures: true,
+        ..AirportStatus::all_auto()
+      },
+    );
+    runner.engine.add_aircraft(Aircraft {
+      id: Intern::from_ref("AAL1"),
+      state: AircraftState::Parked {
+        at: Node::from(&gate),
+      },
+      segment: FlightSegment::Dormant,
+      ..Default::default()
+    });
+
+    // The flight is held, and only charged for once.
+    assert!(!runner.dispatch_departure(origin));
+    assert!(!runner.dispatch_departure(origin));
+    let penalties = runner.engine.game.economies[&destination].penalties;
+    assert_eq!(penalties, DELAYED_DEPARTURE_PENALTY);
+    assert!(runner.engine.game.aircraft[0].flight_time.is_none());
+
+    runner
+      .engine
+      .world
+      .airport_statuses
+      .insert(destination, AirportStatus::all_auto());
+    assert!(runner.dispatch_departure(origin));
+    let aircraft = &runner.engine.game.aircraft[0];
+    assert!(aircraft.flight_time.is_some());
+    assert_eq!(aircraft.flight_plan.arriving, destination);
+  }
+}
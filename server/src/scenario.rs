@@ -0,0 +1,368 @@
+use std::path::Path;
+
+use engine::{
+  compile::{add_module_path, eval_script, setup_lua},
+  engine::Event,
+  entities::{
+    aircraft::{
+      FlightSegment,
+      events::{AircraftEvent, EventKind},
+    },
+    world::AirportStatus,
+  },
+  scoring::Scorecard,
+};
+use internment::Intern;
+use mlua::LuaSerdeExt;
+use serde::{Deserialize, Serialize};
+
+use crate::instructor::{InstructorAction, SpawnRequest};
+
+/// What sets off a scenario event.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+#[serde(tag = "type", content = "value")]
+pub enum Trigger {
+  /// A number of ticks after the scenario begins.
+  At(usize),
+  /// An aircraft entering a flight segment.
+  Segment { id: String, segment: FlightSegment },
+}
+
+impl Trigger {
+  fn is_met(&self, elapsed: usize, events: &[Event]) -> bool {
+    match self {
+      Self::At(tick) => elapsed >= *tick,
+      Self::Segment { id, segment } => entered_segment(events, id, *segment),
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScenarioEvent {
+  pub trigger: Trigger,
+  pub action: InstructorAction,
+}
+
+/// A session statistic that an objective can limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Stat {
+  SeparationLosses,
+  ResolutionAdvisories,
+  GoArounds,
+  TaxiStops,
+  Diversions,
+}
+
+impl Stat {
+  fn value(&self, card: &Scorecard) -> usize {
+    match self {
+      Self::SeparationLosses => card.separation_losses,
+      Self::ResolutionAdvisories => card.resolution_advisories,
+      Self::GoArounds => card.go_arounds,
+      Self::TaxiStops => card.taxi_stops,
+      Self::Diversions => card.diversions,
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+#[serde(tag = "type", content = "value")]
+pub enum Goal {
+  /// An aircraft reaches a flight segment, such as landing.
+  Segment { id: String, segment: FlightSegment },
+  /// A statistic stays at or below a limit.
+  Limit { stat: Stat, max: usize },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Objective {
+  pub name: String,
+  pub goal: Goal,
+  /// The tick after the scenario begins by which the objective is decided.
+  /// A segment not reached by then fails, and a limit not broken by then
+  /// passes.
+  #[serde(default)]
+  pub deadline: Option<usize>,
+}
+
+#[derive(
+  Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum ObjectiveStatus {
+  #[default]
+  Pending,
+  Passed,
+  Failed,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScenarioAirport {
+  pub id: String,
+  pub pos: (f32, f32),
+}
+
+/// A scripted training scenario: an airport, the aircraft in it, events that
+/// play out over the session, and the objectives it is graded on.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Scenario {
+  pub name: String,
+  #[serde(default)]
+  pub description: String,
+  /// The ID of the controlled airport.
+  pub airport: String,
+  /// The automation of the controlled airport. By default, the player
+  /// controls everything.
+  #[serde(default)]
+  pub status: AirportStatus,
+  /// Uncontrolled airports that flights come from and go to.
+  #[serde(default)]
+  pub airports: Vec<ScenarioAirport>,
+  #[serde(default)]
+  pub aircraft: Vec<SpawnRequest>,
+  #[serde(default)]
+  pub events: Vec<ScenarioEvent>,
+  #[serde(default)]
+  pub objectives: Vec<Objective>,
+  /// Whether to generate random traffic alongside the scenario.
+  #[serde(default)]
+  pub random_traffic: bool,
+}
+
+impl Scenario {
+  pub fn from_path<T>(path: T) -> Result<Self, String>
+  where
+    T: AsRef<Path>,
+  {
+    let path = path.as_ref();
+
+    // Scenarios can `require` shared modules next to them, like airports.
+    let lua = setup_lua();
+    if let Some(dir) = path.parent() {
+      add_module_path(&lua, dir)
+        .map_err(|e| format!("Failed to set up scenario {path:?}: {e}"))?;
+    }
+
+    eval_script(&lua, path)
+      .and_then(|value| lua.from_value(value))
+      .map_err(|e| format!("Failed to load scenario {path:?}: {e}"))
+  }
+}
+
+/// The progress of a running scenario.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScenarioReport {
+  pub name: String,
+  pub objectives: Vec<(Objective, ObjectiveStatus)>,
+}
+
+impl ScenarioReport {
+  pub fn is_complete(&self) -> bool {
+    self
+      .objectives
+      .iter()
+      .all(|(_, s)| *s != ObjectiveStatus::Pending)
+  }
+
+  pub fn passed(&self) -> bool {
+    self
+      .objectives
+      .iter()
+      .all(|(_, s)| *s == ObjectiveStatus::Passed)
+  }
+}
+
+/// Plays out a scenario's events and keeps track of its objectives.
+#[derive(Debug, Clone)]
+pub struct ScenarioRun {
+  events: Vec<ScenarioEvent>,
+  pub report: ScenarioReport,
+}
+
+impl ScenarioRun {
+  pub fn new(scenario: &Scenario) -> Self {
+    Self {
+      events: scenario.events.clone(),
+      report: ScenarioReport {
+        name: scenario.name.clone(),
+        objectives: scenario
+          .objectives
+          .iter()
+          .cloned()
+          .map(|o| (o, ObjectiveStatus::Pending))
+          .collect(),
+      },
+    }
+  }
+
+  /// Removes and returns the actions of events triggered this tick, `elapsed`
+  /// ticks after the scenario began.
+  pub fn triggered(
+    &mut self,
+    elapsed: usize,
+    events: &[Event],
+  ) -> Vec<InstructorAction> {
+    let (triggered, waiting) = self
+      .events
+      .drain(..)
+      .partition(|e| e.trigger.is_met(elapsed, events));
+    self.events = waiting;
+
+    triggered.into_iter().map(|e| e.action).collect()
+  }
+
+  /// Decides pending objectives from this tick's events and the session's
+  /// scorecard.
+  pub fn update(&mut self, elapsed: usize, events: &[Event], card: &Scorecard) {
+    for (objective, status) in self.report.objectives.iter_mut() {
+      if *status != ObjectiveStatus::Pending {
+        continue;
+      }
+
+      let expired = objective.deadline.is_some_and(|d| elapsed >= d);
+      *status = match &objective.goal {
+        Goal::Segment { id, segment } => {
+          if entered_segment(events, id, *segment) {
+            ObjectiveStatus::Passed
+          } else if expired {
+            ObjectiveStatus::Failed
+          } else {
+            ObjectiveStatus::Pending
+          }
+        }
+        Goal::Limit { stat, max } => {
+          if stat.value(card) > *max {
+            ObjectiveStatus::Failed
+          } else if expired {
+            ObjectiveStatus::Passed
+          } else {
+            ObjectiveStatus::Pending
+          }
+        }
+      };
+
+      if *status != ObjectiveStatus::Pending {
+        tracing::info!("Objective \"{}\": {:?}", objective.name, status);
+      }
+    }
+  }
+}
+
+fn entered_segment(events: &[Event], id: &str, segment: FlightSegment) -> bool {
+  let id = Intern::from(id.to_uppercase());
+  events.iter().any(|e| {
+    matches!(
+      e,
+      Event::Aircraft(AircraftEvent {
+        id: aircraft,
+        kind: EventKind::Segment(_, s),
+      }) if *aircraft == id && *s == segment
+    )
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const SCENARIO: &str = r#"
+    return {
+      name = "Engine failure on approach",
+      airport = "KSFO",
+      airports = { { id = "KLAX", pos = { 0, -200000 } } },
+      aircraft = {
+        {
+          callsign = "AAL123",
+          origin = "KLAX",
+          destination = "KSFO",
+          state = { type = "arrival" },
+        },
+      },
+      events = {
+        {
+          trigger = {
+            type = "segment",
+            value = { id = "AAL123", segment = "approach" },
+          },
+          action = {
+            type = "emergency",
+            value = { id = "AAL123", emergency = "engine-failure" },
+          },
+        },
+      },
+      objectives = {
+        {
+          name = "Land AAL123",
+          goal = {
+            type = "segment",
+            value = { id = "AAL123", segment = "landing" },
+          },
+          deadline = 900,
+        },
+      },
+    }
+  "#;
+
+  fn segment(id: &str, segment: FlightSegment) -> Event {
+    AircraftEvent::new(
+      Intern::from_ref(id),
+      EventKind::Segment(FlightSegment::Unknown, segment),
+    )
+    .into()
+  }
+
+  #[test]
+  fn test_scenario() {
+    let lua = setup_lua();
+    let scenario: Scenario =
+      lua.from_value(lua.load(SCENARIO).eval().unwrap()).unwrap();
+    assert_eq!(scenario.aircraft.len(), 1);
+
+    let mut run = ScenarioRun::new(&scenario);
+    let card = Scorecard::default();
+    assert!(run.triggered(10, &[]).is_empty());
+
+    let approach = [segment("AAL123", FlightSegment::Approach)];
+    assert_eq!(run.triggered(20, &approach).len(), 1);
+    assert!(run.triggered(30, &approach).is_empty());
+
+    run.update(30, &approach, &card);
+    assert!(!run.report.is_complete());
+    run.update(900, &[], &card);
+    assert!(run.report.is_complete());
+    assert!(!run.report.passed());
+  }
+
+  #[test]
+  fn test_scenario_from_path() {
+    let dir = std::env::temp_dir()
+      .join(format!("airwave-scenario-{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("lib")).unwrap();
+    std::fs::write(
+      dir.join("lib").join("shared.lua"),
+      "return { airport = \"KSFO\" }",
+    )
+    .unwrap();
+    std::fs::write(
+      dir.join("good.lua"),
+      "local shared = require(\"lib.shared\")\n\
+       return { name = \"Good\", airport = shared.airport }",
+    )
+    .unwrap();
+    std::fs::write(dir.join("bad.lua"), "return {\n  name = nil .. \"\",\n}")
+      .unwrap();
+
+    // Shared modules are required from next to the scenario.
+    let scenario = Scenario::from_path(dir.join("good.lua"));
+    assert_eq!(scenario.map(|s| s.airport), Ok("KSFO".to_owned()));
+
+    // Errors point to the file and line they came from.
+    let error = Scenario::from_path(dir.join("bad.lua")).unwrap_err();
+    assert!(error.contains("bad.lua:2:"), "{error}");
+
+    std::fs::remove_dir_all(dir).unwrap();
+  }
+}
@@ -0,0 +1,122 @@
+use std::path::Path;
+
+use engine::entities::aircraft::AircraftKind;
+use serde::{Deserialize, Deserializer};
+
+/// Parses a clock time ("HH:MM" or "HH:MM:SS") into seconds since midnight.
+pub fn parse_clock(s: &str) -> Option<usize> {
+  let mut parts = s.trim().split(':');
+  let hours: usize = parts.next()?.parse().ok()?;
+  let minutes: usize = parts.next()?.parse().ok()?;
+  let seconds: usize = match parts.next() {
+    Some(seconds) => seconds.parse().ok()?,
+    None => 0,
+  };
+
+  if parts.next().is_some() || hours > 23 || minutes > 59 || seconds > 59 {
+    return None;
+  }
+
+  Some(hours * 3600 + minutes * 60 + seconds)
+}
+
+fn deserialize_clock<'de, D>(deserializer: D) -> Result<usize, D::Error>
+where
+  D: Deserializer<'de>,
+{
+  let s = String::deserialize(deserializer)?;
+  parse_clock(&s)
+    .ok_or_else(|| serde::de::Error::custom(format!("invalid time: {s}")))
+}
+
+fn deserialize_optional_clock<'de, D>(
+  deserializer: D,
+) -> Result<Option<usize>, D::Error>
+where
+  D: Deserializer<'de>,
+{
+  let s = Option::<String>::deserialize(deserializer)?;
+  s.map(|s| {
+    parse_clock(&s)
+      .ok_or_else(|| serde::de::Error::custom(format!("invalid time: {s}")))
+  })
+  .transpose()
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ScheduledFlight {
+  pub callsign: String,
+  #[serde(rename = "type", default)]
+  pub kind: AircraftKind,
+  pub origin: String,
+  pub destination: String,
+  /// Scheduled time of departure, for flights departing our airport.
+  #[serde(default, deserialize_with = "deserialize_optional_clock")]
+  pub std: Option<usize>,
+  /// Scheduled time of arrival, for flights arriving at our airport.
+  #[serde(default, deserialize_with = "deserialize_optional_clock")]
+  pub sta: Option<usize>,
+  #[serde(default)]
+  pub gate: Option<String>,
+}
+
+/// A timetable of flights, with times given on the clock starting at
+/// `start` when the session begins.
+#[derive(Debug, Clone, PartialEq, Default, Deserialize)]
+pub struct Schedule {
+  #[serde(deserialize_with = "deserialize_clock")]
+  pub start: usize,
+  pub flights: Vec<ScheduledFlight>,
+}
+
+impl Schedule {
+  pub fn from_path<T>(path: T) -> Result<Self, String>
+  where
+    T: AsRef<Path>,
+  {
+    let path = path.as_ref();
+    match std::fs::read_to_string(path) {
+      Ok(schedule) => match serde_json::from_str(&schedule) {
+        Ok(schedule) => Ok(schedule),
+        Err(err) => Err(format!("Failed to parse schedule file: {}", err)),
+      },
+      Err(err) => Err(format!("Failed to read schedule file: {}", err)),
+    }
+  }
+
+  /// Converts a clock time into seconds since the session began, wrapping
+  /// past midnight.
+  pub fn seconds_since_start(&self, time: usize) -> usize {
+    (time + 24 * 3600 - self.start) % (24 * 3600)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_parse_clock() {
+    assert_eq!(parse_clock("06:30"), Some(6 * 3600 + 30 * 60));
+    assert_eq!(parse_clock("00:00:15"), Some(15));
+    assert_eq!(parse_clock("24:00"), None);
+    assert_eq!(parse_clock("6"), None);
+  }
+
+  #[test]
+  fn test_seconds_since_start() {
+    let schedule = Schedule {
+      start: parse_clock("23:00").unwrap(),
+      flights: Vec::new(),
+    };
+
+    assert_eq!(
+      schedule.seconds_since_start(parse_clock("23:30").unwrap()),
+      1800
+    );
+    assert_eq!(
+      schedule.seconds_since_start(parse_clock("00:15").unwrap()),
+      4500
+    );
+  }
+}
@@ -0,0 +1,155 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Traffic demand at a point in the session, in flights per hour.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct DemandPoint {
+  /// Minutes since the session began.
+  pub minute: f32,
+  #[serde(default)]
+  pub arrivals: f32,
+  #[serde(default)]
+  pub departures: f32,
+}
+
+/// Arrivals and departures per hour over the session, interpolated linearly
+/// between points and held past either end.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct DemandCurve(Vec<DemandPoint>);
+
+impl DemandCurve {
+  pub fn new(mut points: Vec<DemandPoint>) -> Self {
+    points.sort_by(|a, b| a.minute.total_cmp(&b.minute));
+    Self(points)
+  }
+
+  pub fn points(&self) -> &[DemandPoint] {
+    &self.0
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.0.is_empty()
+  }
+
+  /// Moves every point `minutes` later in the session.
+  pub fn offset(mut self, minutes: f32) -> Self {
+    for point in self.0.iter_mut() {
+      point.minute += minutes;
+    }
+    self
+  }
+
+  /// Returns the arrivals and departures per hour at `minute`.
+  pub fn at(&self, minute: f32) -> (f32, f32) {
+    let next = self.0.iter().position(|p| p.minute > minute);
+    let (a, b) = match next {
+      Some(0) => (self.0[0], self.0[0]),
+      Some(i) => (self.0[i - 1], self.0[i]),
+      None => match self.0.last() {
+        Some(last) => (*last, *last),
+        None => return (0.0, 0.0),
+      },
+    };
+
+    let span = b.minute - a.minute;
+    let t = if span > 0.0 {
+      (minute - a.minute) / span
+    } else {
+      0.0
+    };
+
+    (
+      a.arrivals + (b.arrivals - a.arrivals) * t,
+      a.departures + (b.departures - a.departures) * t,
+    )
+  }
+}
+
+/// Parameters for generating traffic.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrafficConfig {
+  /// How often, in seconds, to spawn traffic.
+  #[serde(default = "default_spawn_rate_seconds")]
+  pub spawn_rate_seconds: usize,
+  /// Chance that an airport without a demand curve schedules a departure each
+  /// spawn.
+  #[serde(default = "default_departure_spawn_chance")]
+  pub departure_spawn_chance: f64,
+  /// Chance that a controlled airport without a demand curve gets an arrival
+  /// at the edge of its airspace each spawn, if traffic isn't flown en route.
+  #[serde(default = "default_arrival_spawn_chance")]
+  pub arrival_spawn_chance: f64,
+  /// Chance that a departure is bound for an airport that a player controls.
+  /// Only applies if traffic is flown en route.
+  #[serde(default = "default_arrive_to_non_auto_chance")]
+  pub arrive_to_non_auto_chance: f64,
+  /// Whether arrivals at controlled airports are flown in from the other
+  /// airports in the world, which needs a quick start to fill the skies.
+  /// Otherwise, they spawn at the edge of the airspace.
+  #[serde(default = "default_true")]
+  pub en_route: bool,
+  /// Demand curves by airport ID.
+  #[serde(default)]
+  pub demand: HashMap<String, DemandCurve>,
+}
+
+impl Default for TrafficConfig {
+  fn default() -> Self {
+    Self {
+      spawn_rate_seconds: default_spawn_rate_seconds(),
+      departure_spawn_chance: default_departure_spawn_chance(),
+      arrival_spawn_chance: default_arrival_spawn_chance(),
+      arrive_to_non_auto_chance: default_arrive_to_non_auto_chance(),
+      en_route: true,
+      demand: HashMap::new(),
+    }
+  }
+}
+
+fn default_spawn_rate_seconds() -> usize {
+  75
+}
+
+fn default_departure_spawn_chance() -> f64 {
+  0.8
+}
+
+fn default_arrival_spawn_chance() -> f64 {
+  0.5
+}
+
+fn default_arrive_to_non_auto_chance() -> f64 {
+  0.2
+}
+
+fn default_true() -> bool {
+  true
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_demand_curve() {
+    let curve = DemandCurve::new(vec![
+      DemandPoint {
+        minute: 60.0,
+        arrivals: 30.0,
+        departures: 10.0,
+      },
+      DemandPoint {
+        minute: 0.0,
+        arrivals: 10.0,
+        departures: 10.0,
+      },
+    ]);
+
+    assert_eq!(curve.at(-5.0), (10.0, 10.0));
+    assert_eq!(curve.at(30.0), (20.0, 10.0));
+    assert_eq!(curve.at(90.0), (30.0, 10.0));
+    assert_eq!(DemandCurve::default().at(0.0), (0.0, 0.0));
+  }
+}